@@ -0,0 +1,69 @@
+extern crate ringo;
+
+use ringo::chem::library::{enumerate_library, rgroup_lists, LibraryOptions};
+use ringo::io::smiles::reader::molecule::parse_molecule_line;
+use std::fs::read_to_string;
+
+const USAGE: &str =
+    "usage: ringo-enumerate <scaffold SMILES> <R group file> [--max <products>] [--seed <seed>]";
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut options = LibraryOptions::default();
+    let mut positional = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // the value after a flag, like `--max 100`
+            "--max" | "--seed" => {
+                let Some(value) = args.next().and_then(|v| v.parse::<u64>().ok()) else {
+                    exit_with(&format!("{} needs a number\n{}", arg, USAGE));
+                };
+                if arg == "--max" {
+                    options.max_products = value as usize;
+                } else {
+                    options.sample_seed = Some(value);
+                }
+            }
+            _ if arg.starts_with("--") => exit_with(&format!("unknown option {}\n{}", arg, USAGE)),
+            _ => positional.push(arg),
+        }
+    }
+    let [scaffold, rgroup_file] = positional.as_slice() else {
+        exit_with(USAGE);
+    };
+
+    let Ok((_, scaffold)) = parse_molecule_line(scaffold) else {
+        exit_with(&format!("invalid scaffold SMILES {}", scaffold));
+    };
+    let Ok(rgroups) = read_to_string(rgroup_file) else {
+        exit_with(&format!("could not read {}", rgroup_file));
+    };
+    // one R group per line, like `[*:1]CC methyl`
+    let mut fragments = Vec::new();
+    for (line_number, line) in rgroups.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok((_, fragment)) = parse_molecule_line(line) else {
+            exit_with(&format!(
+                "invalid R group on line {}: {:?}",
+                line_number + 1,
+                line
+            ));
+        };
+        fragments.push(fragment);
+    }
+    match enumerate_library(&scaffold, &rgroup_lists(&fragments), &options) {
+        Ok(products) => {
+            for product in products {
+                println!("{}", product.canonical_smiles());
+            }
+        }
+        Err(error) => exit_with(&format!("{:?}", error)),
+    }
+}
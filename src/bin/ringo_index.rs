@@ -1,8 +1,34 @@
 extern crate ringo;
 
-use ringo::db::index::index_file;
+use ringo::chem::standardize::StandardizeOptions;
+use ringo::chem::tautomers::TautomerOptions;
+use ringo::db::index::{
+    index_file_with_options, index_reaction_file, IndexOptions, ReactionFingerprint,
+};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    index_file(&args[1]);
+    let flag = |name: &str| args.iter().any(|a| a == name);
+    let options = IndexOptions {
+        standardize: flag("--standardize").then(StandardizeOptions::default),
+        canonical_tautomers: flag("--tautomers").then(TautomerOptions::default),
+    };
+    let kind = if flag("--structural") {
+        ReactionFingerprint::Structural
+    } else {
+        ReactionFingerprint::Difference
+    };
+    let rejected = if flag("--reactions") {
+        index_reaction_file(&args[1], kind)
+    } else {
+        index_file_with_options(&args[1], &options)
+    };
+    for rejected in rejected {
+        eprintln!(
+            "Skipped line {}: {:?} {:?}",
+            rejected.line_number + 1,
+            rejected.line,
+            rejected.problems
+        );
+    }
 }
@@ -1,16 +1,34 @@
 extern crate ringo;
 
-use ringo::db::search::similarity_search;
+use ringo::db::search::{reaction_similarity_search, similarity_search};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    let results = similarity_search(
-        &args[1],
-        &args[2],
-        args[3].parse().unwrap(),
-        args[4].parse().unwrap(),
-    );
-    for result in results {
-        println!("{:?} {:?}", result.line, result.similarity);
+    let flag = |name: &str| args.iter().any(|a| a == name);
+    let results = if flag("--reactions") {
+        reaction_similarity_search(
+            &args[1],
+            &args[2],
+            args[3].parse().unwrap(),
+            args[4].parse().unwrap(),
+        )
+    } else {
+        similarity_search(
+            &args[1],
+            &args[2],
+            args[3].parse().unwrap(),
+            args[4].parse().unwrap(),
+        )
+    };
+    match results {
+        Ok(results) => {
+            for result in results {
+                println!("{:?} {:?}", result.line, result.similarity);
+            }
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
     }
 }
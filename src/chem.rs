@@ -0,0 +1,19 @@
+pub mod aromaticity;
+pub mod brics;
+pub mod canonical;
+pub mod cip;
+pub mod fragment;
+pub mod library;
+pub mod mmp;
+pub mod reaction;
+pub mod recap;
+pub mod rgroup;
+pub mod ring_systems;
+pub mod rings;
+pub mod sanitize;
+pub mod scaffold;
+pub mod standardize;
+pub mod stereo;
+pub mod substructure;
+pub mod tautomers;
+pub mod valence;
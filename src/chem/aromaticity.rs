@@ -0,0 +1,361 @@
+use crate::chem::valence::allowed_valences;
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Aromaticity {
+    pub atoms: BTreeSet<NodeIndex>,
+    pub bonds: BTreeSet<EdgeIndex>,
+}
+
+impl Aromaticity {
+    pub fn is_atom_aromatic(&self, atom: NodeIndex) -> bool {
+        self.atoms.contains(&atom)
+    }
+
+    pub fn is_bond_aromatic(&self, bond: EdgeIndex) -> bool {
+        self.bonds.contains(&bond)
+    }
+}
+
+impl Molecule {
+    /// Perceives aromatic atoms and bonds using the Hückel 4n+2 rule on every SSSR ring and
+    /// on every pair of fused SSSR rings (to catch systems like azulene).
+    pub fn aromaticity(&self) -> Aromaticity {
+        let rings = &self.ring_info().rings;
+        let mut systems: Vec<BTreeSet<EdgeIndex>> = Vec::new();
+        for (i, ring) in rings.iter().enumerate() {
+            systems.push(ring.bonds.iter().copied().collect());
+            for other in &rings[i + 1..] {
+                if ring.bonds.iter().any(|b| other.contains_bond(*b)) {
+                    let mut union: BTreeSet<EdgeIndex> = ring.bonds.iter().copied().collect();
+                    union.extend(other.bonds.iter().copied());
+                    systems.push(union);
+                }
+            }
+        }
+
+        // repeat until stable: once a ring is aromatic, its double bonds count as shared
+        // pi electrons for the rings fused to it (e.g. the middle ring of anthracene)
+        let mut aromaticity = Aromaticity::default();
+        loop {
+            let mut changed = false;
+            for bonds in &systems {
+                if bonds.is_subset(&aromaticity.bonds) {
+                    continue;
+                }
+                let mut atoms: BTreeSet<NodeIndex> = BTreeSet::new();
+                for bond in bonds {
+                    let (a, b) = self.graph.edge_endpoints(*bond).unwrap();
+                    atoms.insert(a);
+                    atoms.insert(b);
+                }
+                let electrons: Option<u8> = atoms
+                    .iter()
+                    .map(|atom| self.pi_electrons(*atom, bonds, &aromaticity.bonds))
+                    .sum();
+                if electrons.is_some_and(|e| e % 4 == 2) {
+                    aromaticity.atoms.extend(atoms);
+                    aromaticity.bonds.extend(bonds.iter().copied());
+                    changed = true;
+                }
+            }
+            if !changed {
+                return aromaticity;
+            }
+        }
+    }
+
+    /// Sets the bonds of perceived aromatic rings to `BondOrder::Aromatic`. Atoms whose
+    /// hydrogen count would change with the new bonds (like the NH of pyrrole) keep it as an
+    /// explicit count.
+    pub fn aromatize(&mut self) {
+        let hydrogens: Vec<(NodeIndex, u8)> = self
+            .graph
+            .node_indices()
+            .map(|a| (a, self.total_hydrogens(a)))
+            .collect();
+        for bond in self.aromaticity().bonds {
+            self.graph[bond].order = BondOrder::Aromatic;
+        }
+        for (atom, hs) in hydrogens {
+            if self.total_hydrogens(atom) != hs {
+                self.graph[atom].hs = hs;
+                self.graph[atom].no_implicit_hs = true;
+            }
+        }
+    }
+
+    /// Replaces aromatic bonds with alternating single and double bonds, keeping hydrogen
+    /// counts. Atoms that can not take another bond (like the NH of pyrrole or O of furan)
+    /// get no double bond. Returns false and leaves the molecule unchanged if no such
+    /// assignment exists.
+    pub fn kekulize(&mut self) -> bool {
+        let aromatic: Vec<EdgeIndex> = self
+            .graph
+            .edge_indices()
+            .filter(|e| self.graph[*e].order == BondOrder::Aromatic)
+            .collect();
+        if aromatic.is_empty() {
+            return true;
+        }
+        let hydrogens: Vec<(NodeIndex, u8)> = self
+            .graph
+            .node_indices()
+            .map(|a| (a, self.total_hydrogens(a)))
+            .collect();
+
+        // atoms whose valence is not reached with aromatic bonds counted as single
+        let needs_double: BTreeSet<NodeIndex> = hydrogens
+            .iter()
+            .filter(|(atom, hs)| {
+                if !self
+                    .graph
+                    .edges(*atom)
+                    .any(|e| e.weight().order == BondOrder::Aromatic)
+                {
+                    return false;
+                }
+                let a = &self.graph[*atom];
+                let valence = self.explicit_valence(*atom) - 1 + hs - a.hs;
+                allowed_valences(&a.element, a.charge)
+                    .first()
+                    .is_some_and(|v| *v > valence)
+            })
+            .map(|(atom, _)| *atom)
+            .collect();
+        let candidates: Vec<EdgeIndex> = aromatic
+            .iter()
+            .copied()
+            .filter(|e| {
+                let (a, b) = self.graph.edge_endpoints(*e).unwrap();
+                needs_double.contains(&a) && needs_double.contains(&b)
+            })
+            .collect();
+        let atoms: Vec<NodeIndex> = needs_double.into_iter().collect();
+        let mut double: Vec<EdgeIndex> = Vec::new();
+        if !self.match_double_bonds(&atoms, &candidates, &mut BTreeSet::new(), &mut double) {
+            return false;
+        }
+
+        for edge in aromatic {
+            self.graph[edge].order = if double.contains(&edge) {
+                BondOrder::Double
+            } else {
+                BondOrder::Single
+            };
+        }
+        for (atom, hs) in hydrogens {
+            self.set_total_hydrogens(atom, hs);
+        }
+        true
+    }
+
+    /// Backtracking search for bonds covering each of `atoms` exactly once.
+    fn match_double_bonds(
+        &self,
+        atoms: &[NodeIndex],
+        candidates: &[EdgeIndex],
+        matched: &mut BTreeSet<NodeIndex>,
+        double: &mut Vec<EdgeIndex>,
+    ) -> bool {
+        let Some(atom) = atoms.iter().find(|a| !matched.contains(a)).copied() else {
+            return true;
+        };
+        for edge in candidates {
+            let (a, b) = self.graph.edge_endpoints(*edge).unwrap();
+            let other = match (a == atom, b == atom) {
+                (true, _) => b,
+                (_, true) => a,
+                _ => continue,
+            };
+            if matched.contains(&other) {
+                continue;
+            }
+            matched.insert(atom);
+            matched.insert(other);
+            double.push(*edge);
+            if self.match_double_bonds(atoms, candidates, matched, double) {
+                return true;
+            }
+            double.pop();
+            matched.remove(&atom);
+            matched.remove(&other);
+        }
+        false
+    }
+
+    /// Number of pi electrons the atom contributes to the ring system formed by `ring_bonds`,
+    /// or None if the atom breaks conjugation. Double bonds in `aromatic_bonds` are shared
+    /// with an aromatic ring fused to the system.
+    fn pi_electrons(
+        &self,
+        atom: NodeIndex,
+        ring_bonds: &BTreeSet<EdgeIndex>,
+        aromatic_bonds: &BTreeSet<EdgeIndex>,
+    ) -> Option<u8> {
+        let a = &self.graph[atom];
+        let mut ring_double = false;
+        let mut ring_aromatic = false;
+        let mut exocyclic_double: Option<NodeIndex> = None;
+        for edge in self.graph.edges(atom) {
+            let in_ring = ring_bonds.contains(&edge.id());
+            match edge.weight().order {
+                BondOrder::Triple => return None,
+                BondOrder::Double if in_ring || aromatic_bonds.contains(&edge.id()) => {
+                    ring_double = true
+                }
+                BondOrder::Double => {
+                    exocyclic_double = Some(if edge.source() == atom {
+                        edge.target()
+                    } else {
+                        edge.source()
+                    })
+                }
+                BondOrder::Aromatic if in_ring => ring_aromatic = true,
+                _ => {}
+            }
+        }
+        if ring_double {
+            return Some(1);
+        }
+
+        let connections = self.get_bonds_for_atom(atom).len() as u8 + self.total_hydrogens(atom);
+        match (a.element.atomic_number, a.charge) {
+            (7, 0) | (15, 0) | (33, 0) if connections == 3 => return Some(2),
+            (8, 0) | (16, 0) | (34, 0) if connections == 2 => return Some(2),
+            _ => {}
+        }
+        // exocyclic double bonds and charges also apply to atoms written as aromatic,
+        // like `c(=O)` or `[cH-]`
+        if let Some(neighbor) = exocyclic_double {
+            match self.graph[neighbor].element.atomic_number {
+                7 | 8 | 16 => return Some(0),
+                _ if !ring_aromatic => return None,
+                _ => {}
+            }
+        }
+        match (a.element.atomic_number, a.charge) {
+            (6, -1) => Some(2),
+            (6, 1) | (5, 0) => Some(0),
+            _ if ring_aromatic => Some(1),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::bond::BondOrder;
+    use petgraph::stable_graph::NodeIndex;
+
+    fn count_aromatic_atoms(smiles: &str) -> usize {
+        parse_molecule(smiles).unwrap().1.aromaticity().atoms.len()
+    }
+
+    #[test]
+    fn aromaticity_benzene() {
+        assert_eq!(count_aromatic_atoms("C1=CC=CC=C1"), 6);
+        assert_eq!(count_aromatic_atoms("C1:C:C:C:C:C:1"), 6);
+    }
+
+    #[test]
+    fn aromaticity_cyclohexene() {
+        assert_eq!(count_aromatic_atoms("C1=CCCCC1"), 0);
+    }
+
+    #[test]
+    fn aromaticity_heterocycles() {
+        assert_eq!(count_aromatic_atoms("C1=CC=CN1"), 5);
+        assert_eq!(count_aromatic_atoms("C1=CC=CO1"), 5);
+        assert_eq!(count_aromatic_atoms("C1=CC=NC=C1"), 6);
+        assert_eq!(count_aromatic_atoms("O=C1C=CC=CN1"), 6);
+    }
+
+    #[test]
+    fn aromaticity_cyclooctatetraene() {
+        assert_eq!(count_aromatic_atoms("C1=CC=CC=CC=C1"), 0);
+    }
+
+    #[test]
+    fn aromaticity_fused() {
+        assert_eq!(count_aromatic_atoms("C1=CC2=CC=CC=C2C=C1"), 10);
+        assert_eq!(count_aromatic_atoms("C1=CC=C2C=CC=C2C=C1"), 10);
+        assert_eq!(count_aromatic_atoms("C1=CC=C2CCCC2=C1"), 6);
+        assert_eq!(count_aromatic_atoms("C1=CC=C2C=C3C=CC=CC3=CC2=C1"), 14);
+        assert_eq!(count_aromatic_atoms("C1=CC2=CC3=CC=CC=C3C=C2C=C1"), 14);
+    }
+
+    #[test]
+    fn aromaticity_written_aromatic() {
+        for smiles in [
+            "Cn1cnc2c1c(=O)n(C)c(=O)n2C",
+            "O=c1cccc[nH]1",
+            "[cH-]1cccc1",
+            "c1ccccc1",
+        ] {
+            let m = parse_molecule(smiles).unwrap().1;
+            assert!(m.sanitize().is_empty(), "{}", smiles);
+        }
+        assert_eq!(count_aromatic_atoms("[cH-]1cccc1"), 5);
+        assert_eq!(count_aromatic_atoms("O=c1cccc[nH]1"), 6);
+    }
+
+    #[test]
+    fn aromatize() {
+        let mut m = parse_molecule("C1=CC=C2C(=C1)C=CN2").unwrap().1;
+        let formula = m.formula();
+        m.aromatize();
+        assert!(m
+            .graph
+            .edge_weights()
+            .all(|b| b.order == BondOrder::Aromatic));
+        assert_eq!(m.formula(), formula);
+        assert!(m.graph[NodeIndex::new(8)].no_implicit_hs);
+        assert!(!m.graph[NodeIndex::new(0)].no_implicit_hs);
+    }
+
+    #[test]
+    fn kekulize() {
+        for smiles in [
+            "c1ccccc1",
+            "c1cc[nH]c1",
+            "c1ccoc1",
+            "c1ccc2ccccc2c1",
+            "Cn1cnc2c1c(=O)n(C)c(=O)n2C",
+            "O=c1cccc[nH]1",
+        ] {
+            let mut m = parse_molecule(smiles).unwrap().1;
+            assert!(m.sanitize().is_empty(), "{}", smiles);
+            let formula = m.formula();
+            let canonical = m.canonical_smiles();
+            assert!(m.kekulize(), "{}", smiles);
+            assert!(m
+                .graph
+                .edge_weights()
+                .all(|b| b.order != BondOrder::Aromatic));
+            assert_eq!(m.formula(), formula);
+            assert_eq!(m.canonical_smiles(), canonical);
+            assert!(m.sanitize().is_empty());
+        }
+        assert!(parse_molecule("[Na+].[O-]c1ccccc1").unwrap().1.kekulize());
+        let mut m = parse_molecule("c1cccc1").unwrap().1;
+        assert!(!m.kekulize());
+        assert!(m
+            .graph
+            .edge_weights()
+            .all(|b| b.order == BondOrder::Aromatic));
+    }
+
+    #[test]
+    fn aromaticity_ibuprofen() {
+        let m = parse_molecule("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O").unwrap().1;
+        let aromaticity = m.aromaticity();
+        assert_eq!(aromaticity.atoms.len(), 6);
+        assert_eq!(aromaticity.bonds.len(), 6);
+    }
+}
@@ -0,0 +1,479 @@
+use crate::chem::fragment::{attachment, RuleContext};
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Pairs of environment labels whose atoms are separated by BRICS. All bonds are single,
+/// except 7-7 which is a double bond between two aliphatic carbons.
+const BRICS_BONDS: [(u16, u16); 46] = [
+    (1, 3),
+    (1, 5),
+    (1, 10),
+    (3, 4),
+    (3, 13),
+    (3, 14),
+    (3, 15),
+    (3, 16),
+    (4, 5),
+    (4, 11),
+    (5, 12),
+    (5, 13),
+    (5, 14),
+    (5, 15),
+    (5, 16),
+    (6, 13),
+    (6, 14),
+    (6, 15),
+    (6, 16),
+    (7, 7),
+    (8, 9),
+    (8, 10),
+    (8, 13),
+    (8, 14),
+    (8, 15),
+    (8, 16),
+    (9, 13),
+    (9, 14),
+    (9, 15),
+    (9, 16),
+    (10, 13),
+    (10, 14),
+    (10, 15),
+    (10, 16),
+    (11, 13),
+    (11, 14),
+    (11, 15),
+    (11, 16),
+    (13, 14),
+    (13, 15),
+    (13, 16),
+    (14, 14),
+    (14, 15),
+    (14, 16),
+    (15, 16),
+    (16, 16),
+];
+
+/// Environment labels in use; there is no environment 2.
+const BRICS_ENVIRONMENTS: [u16; 15] = [1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BricsBuildOptions {
+    /// Largest number of fragments joined into one product.
+    pub max_fragments: usize,
+    /// Enumeration stops once this many products are found.
+    pub max_products: usize,
+}
+
+impl Default for BricsBuildOptions {
+    fn default() -> Self {
+        BricsBuildOptions {
+            max_fragments: 3,
+            max_products: 1000,
+        }
+    }
+}
+
+impl Molecule {
+    /// Bonds broken by BRICS (Degen et al., ChemMedChem 2008): acyclic bonds between atoms
+    /// in certain chemical environments. Returned with the environment labels of their first
+    /// and second atom (as returned by `edge_endpoints`), ready for
+    /// `Molecule::fragment_on_bonds`.
+    pub fn brics_bonds(&self) -> Vec<(EdgeIndex, [u16; 2])> {
+        let context = RuleContext::new(self);
+        let environments: HashMap<NodeIndex, BTreeSet<u16>> = self
+            .graph
+            .node_indices()
+            .map(|atom| {
+                let labels = BRICS_ENVIRONMENTS
+                    .iter()
+                    .copied()
+                    .filter(|label| matches_environment(&context, atom, *label))
+                    .collect();
+                (atom, labels)
+            })
+            .collect();
+
+        let mut bonds = Vec::new();
+        for edge in self.graph.edge_indices() {
+            if context.is_bond_in_ring(edge) {
+                continue;
+            }
+            let (a, b) = self.graph.edge_endpoints(edge).unwrap();
+            let (env_a, env_b) = (&environments[&a], &environments[&b]);
+            let labels = BRICS_BONDS.iter().find_map(|(x, y)| {
+                let order = if *x == 7 {
+                    BondOrder::Double
+                } else {
+                    BondOrder::Single
+                };
+                if !context.has_order(edge, order) {
+                    None
+                } else if env_a.contains(x) && env_b.contains(y) {
+                    Some([*x, *y])
+                } else if env_a.contains(y) && env_b.contains(x) {
+                    Some([*y, *x])
+                } else {
+                    None
+                }
+            });
+            if let Some(labels) = labels {
+                bonds.push((edge, labels));
+            }
+        }
+        bonds
+    }
+
+    /// BRICS fragments with attachment points labelled with the environment of the atom they
+    /// are attached to, e.g. `[16*]c1ccccc1`. Unique by canonical SMILES and in
+    /// order of their first atom. A molecule without BRICS bonds is its own only fragment.
+    pub fn brics_fragments(&self) -> Vec<Molecule> {
+        let mut seen = HashSet::new();
+        self.fragment_on_bonds(&self.brics_bonds())
+            .fragments()
+            .into_iter()
+            .filter(|f| seen.insert(f.canonical_smiles()))
+            .collect()
+    }
+}
+
+/// Combines BRICS fragments into new molecules by joining attachment points whose labels
+/// form a BRICS bond. Fragments may be used more than once; products are unique by canonical
+/// SMILES and have no attachment points left.
+pub fn brics_build(fragments: &[Molecule], options: &BricsBuildOptions) -> Vec<Molecule> {
+    let fragments: Vec<&Molecule> = fragments
+        .iter()
+        .filter(|f| !attachment_points(f).is_empty())
+        .collect();
+    let mut products = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut current: Vec<Molecule> = fragments.iter().map(|f| (*f).clone()).collect();
+    for _ in 1..options.max_fragments {
+        let mut next = Vec::new();
+        for partial in &current {
+            // filling the attachment points in order reaches every combination once
+            let Some(&open) = attachment_points(partial).first() else {
+                continue;
+            };
+            for fragment in &fragments {
+                for point in attachment_points(fragment) {
+                    let Some(joined) = join(partial, open, fragment, point) else {
+                        continue;
+                    };
+                    if !seen.insert(joined.canonical_smiles()) {
+                        continue;
+                    }
+                    if attachment_points(&joined).is_empty() {
+                        products.push(joined);
+                        if products.len() >= options.max_products {
+                            return products;
+                        }
+                    } else {
+                        next.push(joined);
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    products
+}
+
+/// Labelled wildcard atoms with exactly one neighbor.
+fn attachment_points(molecule: &Molecule) -> Vec<NodeIndex> {
+    molecule
+        .graph
+        .node_indices()
+        .filter(|a| {
+            let atom = &molecule.graph[*a];
+            atom.is_wildcard() && atom.isotope > 0 && molecule.graph.edges(*a).count() == 1
+        })
+        .collect()
+}
+
+/// Joins `fragment` to `molecule` at the given attachment points if their labels form a
+/// BRICS bond: both wildcards are removed and their neighbors are bonded instead.
+fn join(
+    molecule: &Molecule,
+    point: NodeIndex,
+    fragment: &Molecule,
+    fragment_point: NodeIndex,
+) -> Option<Molecule> {
+    let labels = (
+        molecule.graph[point].isotope,
+        fragment.graph[fragment_point].isotope,
+    );
+    if !BRICS_BONDS.contains(&(labels.0.min(labels.1), labels.0.max(labels.1))) {
+        return None;
+    }
+    let (bond, _) = attachment(molecule, point);
+    let (fragment_bond, _) = attachment(fragment, fragment_point);
+    if molecule.graph[bond].order != fragment.graph[fragment_bond].order {
+        return None;
+    }
+    Some(molecule.join_attachment_points(point, fragment, fragment_point))
+}
+
+/// Whether the atom is in BRICS environment `label`; the SMARTS of each environment is
+/// given next to its rule.
+fn matches_environment(context: &RuleContext, atom: NodeIndex, label: u16) -> bool {
+    let neighbors = context.neighbors(atom);
+    let degree = neighbors.len();
+    let z = |n: NodeIndex| context.atomic_number(n);
+    let single = |e: EdgeIndex| context.has_order(e, BondOrder::Single);
+    let plain = |e: EdgeIndex| single(e) || context.is_aromatic_bond(e);
+    let acyclic_single = |e: EdgeIndex| single(e) && !context.is_bond_in_ring(e);
+    let ring_single = |e: EdgeIndex| single(e) && context.is_bond_in_ring(e);
+    let aliphatic_in = |n: NodeIndex, zs: &[u8]| !context.is_aromatic(n) && zs.contains(&z(n));
+    let aromatic_in = |n: NodeIndex, zs: &[u8]| context.is_aromatic(n) && zs.contains(&z(n));
+    // neighbors over ring single bonds and over aromatic bonds
+    let ring_neighbors = |zs: &[u8]| -> Vec<NodeIndex> {
+        neighbors
+            .iter()
+            .filter(|(n, e)| ring_single(*e) && aliphatic_in(*n, zs))
+            .map(|(n, _)| *n)
+            .collect()
+    };
+    let aromatic_neighbors = |zs: &[u8]| -> Vec<NodeIndex> {
+        neighbors
+            .iter()
+            .filter(|(n, e)| context.is_aromatic_bond(*e) && aromatic_in(*n, zs))
+            .map(|(n, _)| *n)
+            .collect()
+    };
+    let any_neighbor = |bond: &dyn Fn(EdgeIndex) -> bool, zs: &[u8]| {
+        neighbors
+            .iter()
+            .any(|(n, e)| bond(*e) && zs.contains(&z(*n)))
+    };
+    let has_double = neighbors
+        .iter()
+        .any(|(_, e)| context.has_order(*e, BondOrder::Double));
+
+    match label {
+        // [C;D3]([#0,#6,#7,#8])(=O)
+        1 => {
+            context.is_aliphatic(atom, 6)
+                && degree == 3
+                && context.has_double_bond_to(atom, 8)
+                && any_neighbor(&plain, &[0, 6, 7, 8])
+        }
+        // [O;D2]-;!@[#0,#6,#1]
+        3 => {
+            context.is_aliphatic(atom, 8)
+                && degree == 2
+                && any_neighbor(&acyclic_single, &[0, 6, 1])
+        }
+        // [C;!D1;!$(C=*)]-;!@[#6]
+        4 => {
+            context.is_aliphatic(atom, 6)
+                && degree != 1
+                && !has_double
+                && any_neighbor(&acyclic_single, &[6])
+        }
+        // [N;!D1;!$(N=*);!$(N-[!#6;!#16;!#0;!#1]);!$([N;R]@[C;R]=O)]
+        5 => {
+            let lactam = context.is_atom_in_ring(atom)
+                && neighbors.iter().any(|(n, e)| {
+                    context.is_bond_in_ring(*e)
+                        && context.is_aliphatic(*n, 6)
+                        && context.has_double_bond_to(*n, 8)
+                });
+            context.is_aliphatic(atom, 7)
+                && degree != 1
+                && !has_double
+                && neighbors
+                    .iter()
+                    .all(|(n, e)| !single(*e) || [6, 16, 0, 1].contains(&z(*n)))
+                && !lactam
+        }
+        // [C;D3;!R](=O)-;!@[#0,#6,#7,#8]
+        6 => {
+            context.is_aliphatic(atom, 6)
+                && degree == 3
+                && !context.is_atom_in_ring(atom)
+                && context.has_double_bond_to(atom, 8)
+                && any_neighbor(&acyclic_single, &[0, 6, 7, 8])
+        }
+        // [C;D2,D3]-[#6]
+        7 => {
+            context.is_aliphatic(atom, 6)
+                && (degree == 2 || degree == 3)
+                && any_neighbor(&single, &[6])
+        }
+        // [C;!R;!D1;!$(C!-*)]
+        8 => {
+            context.is_aliphatic(atom, 6)
+                && !context.is_atom_in_ring(atom)
+                && degree != 1
+                && neighbors.iter().all(|(_, e)| single(*e))
+        }
+        // [n;+0;$(n(:[c,n,o,s]):[c,n,o,s])]
+        9 => {
+            context.is_aromatic_element(atom, 7)
+                && context.charge(atom) == 0
+                && aromatic_neighbors(&[6, 7, 8, 16]).len() >= 2
+        }
+        // [N;R;$(N(@C(=O))@[C,N,O,S])]
+        10 => {
+            let carbonyls: Vec<NodeIndex> = neighbors
+                .iter()
+                .filter(|(n, e)| {
+                    context.is_bond_in_ring(*e)
+                        && context.is_aliphatic(*n, 6)
+                        && context.has_double_bond_to(*n, 8)
+                })
+                .map(|(n, _)| *n)
+                .collect();
+            let others: Vec<NodeIndex> = neighbors
+                .iter()
+                .filter(|(n, e)| context.is_bond_in_ring(*e) && aliphatic_in(*n, &[6, 7, 8, 16]))
+                .map(|(n, _)| *n)
+                .collect();
+            context.is_aliphatic(atom, 7)
+                && context.is_atom_in_ring(atom)
+                && distinct_pair(&carbonyls, &others)
+        }
+        // [S;D2](-;!@[#0,#6])
+        11 => {
+            context.is_aliphatic(atom, 16) && degree == 2 && any_neighbor(&acyclic_single, &[0, 6])
+        }
+        // [S;D4]([#6,#0])(=O)(=O)
+        12 => {
+            context.is_aliphatic(atom, 16)
+                && degree == 4
+                && context.count_double_bonds_to(atom, 8) >= 2
+                && any_neighbor(&plain, &[6, 0])
+        }
+        // [C;$(C(-;@[C,N,O,S])-;@[N,O,S])]
+        13 => {
+            context.is_aliphatic(atom, 6)
+                && distinct_pair(
+                    &ring_neighbors(&[6, 7, 8, 16]),
+                    &ring_neighbors(&[7, 8, 16]),
+                )
+        }
+        // [c;$(c(:[c,n,o,s]):[n,o,s])]
+        14 => {
+            context.is_aromatic_element(atom, 6)
+                && distinct_pair(
+                    &aromatic_neighbors(&[6, 7, 8, 16]),
+                    &aromatic_neighbors(&[7, 8, 16]),
+                )
+        }
+        // [C;$(C(-;@C)-;@C)]
+        15 => context.is_aliphatic(atom, 6) && ring_neighbors(&[6]).len() >= 2,
+        // [c;$(c(:c):c)]
+        16 => context.is_aromatic_element(atom, 6) && aromatic_neighbors(&[6]).len() >= 2,
+        _ => false,
+    }
+}
+
+/// Whether two different atoms can be picked, the first from `a` and the second from `b`.
+fn distinct_pair(a: &[NodeIndex], b: &[NodeIndex]) -> bool {
+    a.iter().any(|x| b.iter().any(|y| x != y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+
+    fn canonical(smiles: &[&str]) -> BTreeSet<String> {
+        smiles
+            .iter()
+            .map(|s| parse_molecule(s).unwrap().1.canonical_smiles())
+            .collect()
+    }
+
+    fn fragments(smiles: &str) -> BTreeSet<String> {
+        parse_molecule(smiles)
+            .unwrap()
+            .1
+            .brics_fragments()
+            .iter()
+            .map(|f| f.canonical_smiles())
+            .collect()
+    }
+
+    #[test]
+    fn brics_fragments() {
+        let expected = canonical(&["[16*]c1ccccc1", "[3*]O[3*]", "[4*]CCC", "[4*]CCC([6*])=O"]);
+        assert_eq!(fragments("CCCOCCC(=O)c1ccccc1"), expected);
+        // aromaticity is perceived, so Kekulé input gives the same fragments
+        assert_eq!(fragments("CCCOCCC(=O)C1=CC=CC=C1"), expected);
+        // amide and biaryl bonds
+        assert_eq!(
+            fragments("CC(=O)NCc1ccc(-c2ccccn2)cc1"),
+            canonical(&[
+                "[1*]C(C)=O",
+                "[5*]N[5*]",
+                "[4*]C[8*]",
+                "[16*]c1ccc([16*])cc1",
+                "[14*]c1ccccn1",
+            ]),
+        );
+        // ring bonds are never broken
+        assert_eq!(fragments("C1CCOC1"), canonical(&["C1CCOC1"]));
+        // biaryls with the bond written implicitly
+        assert_eq!(
+            fragments("c1ccccc1c1ccncc1"),
+            canonical(&["[16*]c1ccccc1", "[16*]c1ccncc1"])
+        );
+    }
+
+    #[test]
+    fn brics_double_bond() {
+        let m = parse_molecule("CC/C=C/CC").unwrap().1;
+        let bonds = m.brics_bonds();
+        assert_eq!(bonds.len(), 1);
+        assert_eq!(bonds[0].1, [7, 7]);
+        assert_eq!(
+            m.brics_fragments()
+                .iter()
+                .map(|f| f.canonical_smiles())
+                .collect::<Vec<_>>(),
+            vec!["[7*]=CCC"],
+        );
+    }
+
+    #[test]
+    fn brics_build() {
+        let fragments: Vec<Molecule> = ["[4*]CCC", "[3*]O[3*]"]
+            .iter()
+            .map(|s| parse_molecule(s).unwrap().1)
+            .collect();
+        let products: Vec<String> = super::brics_build(&fragments, &BricsBuildOptions::default())
+            .iter()
+            .map(|p| p.canonical_smiles())
+            .collect();
+        assert_eq!(products, vec![canonical(&["CCCOCCC"]).pop_first().unwrap()]);
+
+        let options = BricsBuildOptions {
+            max_fragments: 2,
+            ..Default::default()
+        };
+        assert!(super::brics_build(&fragments, &options).is_empty());
+    }
+
+    #[test]
+    fn brics_roundtrip() {
+        let m = parse_molecule("CCCOCCC(=O)c1ccccc1").unwrap().1;
+        let options = BricsBuildOptions {
+            max_fragments: 5,
+            max_products: 10000,
+        };
+        let products: BTreeSet<String> = super::brics_build(&m.brics_fragments(), &options)
+            .iter()
+            .map(|p| p.canonical_smiles())
+            .collect();
+        assert!(products.contains(&m.canonical_smiles()));
+        // fragments are recombined in new ways, e.g. the ether with two ketone chains
+        assert!(products.contains(
+            &canonical(&["O(CCC(=O)c1ccccc1)CCC(=O)c1ccccc1"])
+                .pop_first()
+                .unwrap()
+        ));
+    }
+}
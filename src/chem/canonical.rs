@@ -0,0 +1,274 @@
+use crate::chem::stereo::permutation_parity;
+use crate::model::atom::Chirality;
+use crate::model::bond::{BondOrder, DoubleBondStereo};
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::HashMap;
+
+impl Molecule {
+    /// Symmetry classes of atoms: atoms with equal class are topologically equivalent
+    /// (up to the limits of iterative refinement). Classes are numbered from 0 without gaps,
+    /// and depend only on the molecule structure, not on the atom order.
+    pub fn symmetry_classes(&self) -> HashMap<NodeIndex, usize> {
+        let atoms: Vec<NodeIndex> = self.graph.node_indices().collect();
+        let classes = self.refine(&atoms, self.initial_classes(&atoms));
+        atoms.into_iter().zip(classes).collect()
+    }
+
+    /// Canonical ranks 0..n of all atoms: symmetry classes with ties broken one at a time,
+    /// so that equal molecules get equal ranks regardless of the input atom order.
+    /// Stereo configurations split classes before ties are broken, so that e.g. the two
+    /// centers of a meso compound are told apart.
+    pub fn canonical_ranks(&self) -> HashMap<NodeIndex, usize> {
+        self.canonical_rank_candidates(1).pop().unwrap_or_default()
+    }
+
+    /// Ranks for up to `limit` ways of breaking ties, trying every atom of a tied class
+    /// instead of the first one only. The first candidate equals `canonical_ranks`.
+    /// Needed for stereo, where the configuration written for an atom can depend on which of
+    /// its equivalent neighbors comes first.
+    pub(crate) fn canonical_rank_candidates(&self, limit: usize) -> Vec<HashMap<NodeIndex, usize>> {
+        let atoms: Vec<NodeIndex> = self.graph.node_indices().collect();
+        let mut classes = self.refine(&atoms, self.initial_classes(&atoms));
+        classes = self.refine_stereo(&atoms, classes);
+        let mut candidates = Vec::new();
+        self.break_ties(&atoms, classes, limit, &mut candidates);
+        candidates
+            .into_iter()
+            .map(|classes| atoms.iter().copied().zip(classes).collect())
+            .collect()
+    }
+
+    fn break_ties(
+        &self,
+        atoms: &[NodeIndex],
+        classes: Vec<usize>,
+        limit: usize,
+        candidates: &mut Vec<Vec<usize>>,
+    ) {
+        // the smallest class shared by several atoms
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for class in &classes {
+            *counts.entry(*class).or_default() += 1;
+        }
+        let tied = match counts
+            .iter()
+            .filter(|(_, n)| **n > 1)
+            .map(|(c, _)| *c)
+            .min()
+        {
+            Some(class) => class,
+            None => {
+                candidates.push(classes);
+                return;
+            }
+        };
+        for (chosen, _) in classes.iter().enumerate().filter(|(_, c)| **c == tied) {
+            if candidates.len() >= limit {
+                return;
+            }
+            // split off the chosen atom and propagate the difference
+            let mut split: Vec<usize> = classes.iter().map(|c| c * 2 + 1).collect();
+            split[chosen] -= 1;
+            let refined = self.refine(atoms, dense_ranks(&split));
+            let refined = self.refine_stereo(atoms, refined);
+            self.break_ties(atoms, refined, limit, candidates);
+        }
+    }
+
+    fn initial_classes(&self, atoms: &[NodeIndex]) -> Vec<usize> {
+        let ring_info = self.ring_info();
+        let invariants: Vec<_> = atoms
+            .iter()
+            .map(|atom| {
+                let a = &self.graph[*atom];
+                let mut orders: Vec<u8> = self
+                    .graph
+                    .edges(*atom)
+                    .map(|e| order_code(e.weight().order))
+                    .collect();
+                orders.sort();
+                // degree first, so that the lowest ranks are terminal atoms
+                (
+                    orders.len(),
+                    a.element.atomic_number,
+                    a.isotope,
+                    a.charge,
+                    self.total_hydrogens(*atom),
+                    ring_info.is_atom_in_ring(*atom),
+                    orders,
+                )
+            })
+            .collect();
+        dense_ranks(&invariants)
+    }
+
+    /// Refines classes by stereo configurations relative to the current classes, until no
+    /// more classes are split.
+    fn refine_stereo(&self, atoms: &[NodeIndex], mut classes: Vec<usize>) -> Vec<usize> {
+        if !self.has_stereo() {
+            return classes;
+        }
+        let count = |classes: &[usize]| classes.iter().max().map_or(0, |c| c + 1);
+        loop {
+            let labels = self.stereo_labels(atoms, &classes);
+            let keys: Vec<(usize, (u8, u8))> = classes.iter().copied().zip(labels).collect();
+            let refined = self.refine(atoms, dense_ranks(&keys));
+            if count(&refined) == count(&classes) {
+                return classes;
+            }
+            classes = refined;
+        }
+    }
+
+    /// Configurations of stereocenters and double bond atoms relative to the classes of their
+    /// neighbors, 0 where neighbors are tied.
+    fn stereo_labels(&self, atoms: &[NodeIndex], classes: &[usize]) -> Vec<(u8, u8)> {
+        let positions: HashMap<NodeIndex, usize> =
+            atoms.iter().enumerate().map(|(i, a)| (*a, i)).collect();
+        let mut labels = vec![(0, 0); atoms.len()];
+        for (i, atom) in atoms.iter().enumerate() {
+            let chirality = self.graph[*atom].chirality;
+            if chirality == Chirality::None {
+                continue;
+            }
+            // the implicit hydrogen comes before all atoms
+            let keys: Vec<Option<usize>> = self
+                .stereo_neighbors(*atom)
+                .into_iter()
+                .map(|n| n.map(|n| classes[positions[&n]]))
+                .collect();
+            let mut sorted = keys.clone();
+            sorted.sort();
+            sorted.dedup();
+            if let Some(odd) = permutation_parity(&keys, &sorted) {
+                labels[i].0 = 1 + ((chirality == Chirality::Clockwise) ^ odd) as u8;
+            }
+        }
+        for edge in (&self.graph).edge_references() {
+            let stereo = edge.weight().double_bond_stereo;
+            if stereo == DoubleBondStereo::None {
+                continue;
+            }
+            let (a, b) = (edge.source(), edge.target());
+            // whether the highest ranked neighbor is not the reference neighbor
+            let side = |atom: NodeIndex, partner: NodeIndex| {
+                let neighbors = self.double_bond_neighbors(atom, partner);
+                let ranked: Vec<usize> = neighbors.iter().map(|n| classes[positions[n]]).collect();
+                match ranked.as_slice() {
+                    [_] => Some(false),
+                    [first, second] if first != second => Some(second > first),
+                    _ => None,
+                }
+            };
+            if let (Some(swap_a), Some(swap_b)) = (side(a, b), side(b, a)) {
+                let cis = (stereo == DoubleBondStereo::Cis) ^ swap_a ^ swap_b;
+                let label = 1 + cis as u8;
+                labels[positions[&a]].1 = labels[positions[&a]].1.max(label);
+                labels[positions[&b]].1 = labels[positions[&b]].1.max(label);
+            }
+        }
+        labels
+    }
+
+    /// Refines classes by the classes of neighbors until the number of classes is stable.
+    fn refine(&self, atoms: &[NodeIndex], mut classes: Vec<usize>) -> Vec<usize> {
+        let positions: HashMap<NodeIndex, usize> =
+            atoms.iter().enumerate().map(|(i, a)| (*a, i)).collect();
+        let count = |classes: &[usize]| {
+            let mut sorted = classes.to_vec();
+            sorted.sort();
+            sorted.dedup();
+            sorted.len()
+        };
+        let mut class_count = count(&classes);
+        loop {
+            let keys: Vec<(usize, Vec<(usize, u8)>)> = atoms
+                .iter()
+                .enumerate()
+                .map(|(i, atom)| {
+                    let mut neighbors: Vec<(usize, u8)> = self
+                        .graph
+                        .edges(*atom)
+                        .map(|e| {
+                            let other = if e.source() == *atom {
+                                e.target()
+                            } else {
+                                e.source()
+                            };
+                            (classes[positions[&other]], order_code(e.weight().order))
+                        })
+                        .collect();
+                    neighbors.sort();
+                    (classes[i], neighbors)
+                })
+                .collect();
+            classes = dense_ranks(&keys);
+            let new_count = count(&classes);
+            if new_count == class_count {
+                return classes;
+            }
+            class_count = new_count;
+        }
+    }
+}
+
+fn order_code(order: BondOrder) -> u8 {
+    match order {
+        BondOrder::Single => 1,
+        BondOrder::Double => 2,
+        BondOrder::Triple => 3,
+        BondOrder::Aromatic => 4,
+    }
+}
+
+/// Replaces values by their position among the sorted distinct values.
+fn dense_ranks<T: Ord + Clone>(values: &[T]) -> Vec<usize> {
+    let mut sorted: Vec<T> = values.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    values
+        .iter()
+        .map(|v| sorted.binary_search(v).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use petgraph::stable_graph::NodeIndex;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_symmetry_classes() {
+        let m = parse_molecule("CC(C)O").unwrap().1;
+        let classes = m.symmetry_classes();
+        assert_eq!(classes[&NodeIndex::new(0)], classes[&NodeIndex::new(2)]);
+        assert_ne!(classes[&NodeIndex::new(0)], classes[&NodeIndex::new(1)]);
+        assert_eq!(classes.values().collect::<BTreeSet<_>>().len(), 3);
+
+        let m = parse_molecule("c1ccccc1").unwrap().1;
+        assert!(m.symmetry_classes().values().all(|c| *c == 0));
+    }
+
+    #[test]
+    fn test_canonical_ranks() {
+        let m = parse_molecule("OC(C)CC1=CC=CC=C1").unwrap().1;
+        let ranks = m.canonical_ranks();
+        let distinct: BTreeSet<usize> = ranks.values().copied().collect();
+        assert_eq!(distinct, (0..m.count_atoms()).collect());
+    }
+
+    #[test]
+    fn test_canonical_ranks_independent_of_order() {
+        // the same molecule written from two ends: rank of the oxygen and of the ring
+        // attachment atom must agree
+        let a = parse_molecule("OCCCC1CCCCC1").unwrap().1;
+        let b = parse_molecule("C1CCC(CCCO)CC1").unwrap().1;
+        let ranks_a = a.canonical_ranks();
+        let ranks_b = b.canonical_ranks();
+        assert_eq!(ranks_a[&NodeIndex::new(0)], ranks_b[&NodeIndex::new(7)]);
+        assert_eq!(ranks_a[&NodeIndex::new(4)], ranks_b[&NodeIndex::new(3)]);
+    }
+}
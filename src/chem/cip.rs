@@ -0,0 +1,419 @@
+use crate::chem::stereo::permutation_parity;
+use crate::model::atom::Chirality;
+use crate::model::bond::{BondOrder, DoubleBondStereo};
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Spheres of the hierarchical digraph explored before two branches are considered equal.
+const MAX_SPHERES: usize = 32;
+/// Limit of digraph nodes in one sphere of a branch, as rings make the digraph grow
+/// exponentially.
+const MAX_SPHERE_SIZE: usize = 4096;
+
+/// Cahn-Ingold-Prelog descriptor of a stereocenter (R, S) or a double bond (E, Z).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CipLabel {
+    R,
+    S,
+    E,
+    Z,
+}
+
+/// CIP descriptors of the stereocenters and double bonds with a specified configuration.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CipLabels {
+    pub atoms: BTreeMap<NodeIndex, CipLabel>,
+    pub bonds: BTreeMap<EdgeIndex, CipLabel>,
+}
+
+/// Node of the hierarchical digraph: an atom reached over a path from the root, a
+/// duplicate atom (ring closure or multiple bond), or a hydrogen (`atom` is `None`).
+#[derive(Clone)]
+struct DigraphNode {
+    atom: Option<NodeIndex>,
+    atomic_number: u8,
+    /// Mass number for rule 2: the isotope, or the standard atomic weight in thousandths.
+    mass: u32,
+    duplicate: bool,
+    /// Atoms from the root to this node, excluding the node.
+    path: Vec<NodeIndex>,
+}
+
+#[derive(Clone, Copy)]
+enum Rule {
+    /// Higher atomic number first.
+    AtomicNumber,
+    /// Higher atomic mass first.
+    Mass,
+}
+
+impl DigraphNode {
+    fn key(&self, rule: Rule) -> u32 {
+        match rule {
+            Rule::AtomicNumber => self.atomic_number as u32,
+            Rule::Mass => self.mass,
+        }
+    }
+}
+
+/// A node of a sphere expanded to its tie group, the keys of its substituents and the
+/// substituents, sorted by decreasing priority.
+type Expanded = (usize, Vec<u32>, Vec<DigraphNode>);
+
+/// The next sphere from an expanded one. Substituents tie if their parents tie with the same
+/// substituent keys and they have the same key themselves; groups are numbered in decreasing
+/// priority and the sphere is sorted by group.
+fn next_sphere(expanded: Vec<Expanded>, rule: Rule) -> Vec<(usize, DigraphNode)> {
+    let mut children = Vec::new();
+    let mut parent_group = 0;
+    let mut previous: Option<(usize, Vec<u32>)> = None;
+    for (group, keys, nodes) in expanded {
+        if previous
+            .as_ref()
+            .is_some_and(|(g, k)| *g != group || *k != keys)
+        {
+            parent_group += 1;
+        }
+        children.extend(nodes.into_iter().map(|n| (parent_group, n.key(rule), n)));
+        previous = Some((group, keys));
+    }
+    children.sort_by_key(|(group, key, _)| (*group, std::cmp::Reverse(*key)));
+    let mut next = Vec::with_capacity(children.len());
+    let mut group = 0;
+    let mut previous = None;
+    for (parent_group, key, node) in children {
+        if previous.is_some_and(|p| p != (parent_group, key)) {
+            group += 1;
+        }
+        next.push((group, node));
+        previous = Some((parent_group, key));
+    }
+    next
+}
+
+impl Molecule {
+    /// CIP labels of all atoms and double bonds with a specified configuration, using the
+    /// hierarchical digraph with rules 1a (atomic number) and 2 (atomic mass). Aromatic rings
+    /// are ranked in a Kekulé form. Centers whose neighbors tie under these rules, like
+    /// pseudoasymmetric centers, get no label.
+    pub fn cip_labels(&self) -> CipLabels {
+        let mut labels = CipLabels::default();
+        if !self.has_stereo() {
+            return labels;
+        }
+        let mut kekule = self.clone();
+        kekule.kekulize();
+        for atom in self.graph.node_indices() {
+            if let Some(label) = kekule.tetrahedral_label(atom) {
+                labels.atoms.insert(atom, label);
+            }
+        }
+        for edge in (&self.graph).edge_references() {
+            if let Some(label) = kekule.double_bond_label(edge.id()) {
+                labels.bonds.insert(edge.id(), label);
+            }
+        }
+        labels
+    }
+
+    fn tetrahedral_label(&self, atom: NodeIndex) -> Option<CipLabel> {
+        let chirality = self.graph[atom].chirality;
+        if chirality == Chirality::None {
+            return None;
+        }
+        let neighbors = self.stereo_neighbors(atom);
+        if neighbors.len() != 4 {
+            return None;
+        }
+        let mut ranked = neighbors.clone();
+        ranked.sort_by(|a, b| self.compare_branches(atom, *b, *a));
+        if ranked
+            .windows(2)
+            .any(|w| self.compare_branches(atom, w[0], w[1]) == Ordering::Equal)
+        {
+            return None;
+        }
+        // lowest priority first: anticlockwise when looking from it is clockwise when
+        // looking towards it
+        let mut order = vec![ranked[3]];
+        order.extend(&ranked[..3]);
+        let odd = permutation_parity(&neighbors, &order)?;
+        let anticlockwise = (chirality == Chirality::AntiClockwise) ^ odd;
+        Some(if anticlockwise {
+            CipLabel::R
+        } else {
+            CipLabel::S
+        })
+    }
+
+    fn double_bond_label(&self, bond: EdgeIndex) -> Option<CipLabel> {
+        let stereo = self.graph[bond].double_bond_stereo;
+        if stereo == DoubleBondStereo::None || self.graph[bond].order != BondOrder::Double {
+            return None;
+        }
+        let (a, b) = self.graph.edge_endpoints(bond).unwrap();
+        // whether the substituent with higher priority is not the reference neighbor
+        let side = |atom: NodeIndex, partner: NodeIndex| -> Option<bool> {
+            let neighbors = self.double_bond_neighbors(atom, partner);
+            let mut substituents: Vec<Option<NodeIndex>> =
+                neighbors.iter().copied().map(Some).collect();
+            if self.total_hydrogens(atom) > 0 {
+                substituents.push(None);
+            }
+            match substituents.as_slice() {
+                [_] => Some(false),
+                [first, second] => match self.compare_branches(atom, *first, *second) {
+                    Ordering::Equal => None,
+                    ordering => Some(ordering == Ordering::Less),
+                },
+                _ => None,
+            }
+        };
+        let cis = (stereo == DoubleBondStereo::Cis) ^ side(a, b)? ^ side(b, a)?;
+        Some(if cis { CipLabel::Z } else { CipLabel::E })
+    }
+
+    /// Compares the branches from `root` to two of its neighbors (`None` for an implicit
+    /// hydrogen), by rule 1a and then by rule 2.
+    fn compare_branches(
+        &self,
+        root: NodeIndex,
+        a: Option<NodeIndex>,
+        b: Option<NodeIndex>,
+    ) -> Ordering {
+        if a == b {
+            return Ordering::Equal;
+        }
+        let a = self.digraph_node(a, false, vec![root]);
+        let b = self.digraph_node(b, false, vec![root]);
+        self.compare_digraphs(&a, &b, Rule::AtomicNumber)
+            .then_with(|| self.compare_digraphs(&a, &b, Rule::Mass))
+    }
+
+    /// Explores both branches sphere by sphere. In every sphere the substituent sets of the
+    /// nodes are compared in order of the nodes, each set sorted by decreasing priority.
+    /// Nodes which tie so far are ordered by their own substituents first, so that the
+    /// comparison does not depend on the numbering of the atoms.
+    fn compare_digraphs(&self, a: &DigraphNode, b: &DigraphNode, rule: Rule) -> Ordering {
+        let ordering = a.key(rule).cmp(&b.key(rule));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        let mut sphere_a = vec![(0, a.clone())];
+        let mut sphere_b = vec![(0, b.clone())];
+        for _ in 0..MAX_SPHERES {
+            let expanded_a = self.expand_sphere(&sphere_a, rule);
+            let expanded_b = self.expand_sphere(&sphere_b, rule);
+            for i in 0..expanded_a.len().max(expanded_b.len()) {
+                let keys_a = expanded_a.get(i).map_or(&[][..], |(_, keys, _)| keys);
+                let keys_b = expanded_b.get(i).map_or(&[][..], |(_, keys, _)| keys);
+                // missing substituents are phantom atoms with the lowest priority
+                for j in 0..keys_a.len().max(keys_b.len()) {
+                    let key_a = keys_a.get(j).copied().unwrap_or(0);
+                    let key_b = keys_b.get(j).copied().unwrap_or(0);
+                    let ordering = key_a.cmp(&key_b);
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+            }
+            let next_a = next_sphere(expanded_a, rule);
+            let next_b = next_sphere(expanded_b, rule);
+            if (next_a.is_empty() && next_b.is_empty())
+                || next_a.len().max(next_b.len()) > MAX_SPHERE_SIZE
+            {
+                break;
+            }
+            sphere_a = next_a;
+            sphere_b = next_b;
+        }
+        Ordering::Equal
+    }
+
+    /// Substituents of the nodes of a sphere, given with the tie group of each node. Nodes of
+    /// one group are sorted by the keys of their substituents, in decreasing order.
+    fn expand_sphere(&self, sphere: &[(usize, DigraphNode)], rule: Rule) -> Vec<Expanded> {
+        let mut expanded: Vec<Expanded> = sphere
+            .iter()
+            .map(|(group, node)| {
+                let children = self.children(node, rule);
+                let keys = children.iter().map(|n| n.key(rule)).collect();
+                (*group, keys, children)
+            })
+            .collect();
+        expanded.sort_by(|(group_a, keys_a, _), (group_b, keys_b, _)| {
+            group_a.cmp(group_b).then_with(|| keys_b.cmp(keys_a))
+        });
+        expanded
+    }
+
+    fn digraph_node(
+        &self,
+        atom: Option<NodeIndex>,
+        duplicate: bool,
+        path: Vec<NodeIndex>,
+    ) -> DigraphNode {
+        let (atomic_number, mass) = match atom {
+            Some(atom) => {
+                let a = &self.graph[atom];
+                let mass = if a.isotope != 0 {
+                    a.isotope as u32 * 1000
+                } else {
+                    (a.element.atomic_weight().unwrap_or(0.0) * 1000.0).round() as u32
+                };
+                (a.element.atomic_number, mass)
+            }
+            None => (1, 1008),
+        };
+        DigraphNode {
+            atom,
+            atomic_number,
+            mass,
+            duplicate,
+            path,
+        }
+    }
+
+    /// Substituents of a digraph node by decreasing priority under `rule`. Multiple bonds
+    /// add duplicates of the atom at the other end; bonds back to an atom on the path end in
+    /// a duplicate.
+    fn children(&self, node: &DigraphNode, rule: Rule) -> Vec<DigraphNode> {
+        let atom = match node.atom {
+            Some(atom) if !node.duplicate => atom,
+            _ => return Vec::new(),
+        };
+        let parent = node.path.last().copied();
+        let mut path = node.path.clone();
+        path.push(atom);
+        let mut children = Vec::new();
+        for edge in self.graph.edges(atom) {
+            let other = if edge.source() == atom {
+                edge.target()
+            } else {
+                edge.source()
+            };
+            let multiplicity = match edge.weight().order {
+                BondOrder::Double => 2,
+                BondOrder::Triple => 3,
+                BondOrder::Single | BondOrder::Aromatic => 1,
+            };
+            let duplicates = if Some(other) == parent {
+                multiplicity - 1
+            } else if node.path.contains(&other) {
+                multiplicity
+            } else {
+                children.push(self.digraph_node(Some(other), false, path.clone()));
+                multiplicity - 1
+            };
+            for _ in 0..duplicates {
+                children.push(self.digraph_node(Some(other), true, path.clone()));
+            }
+        }
+        for _ in 0..self.total_hydrogens(atom) {
+            children.push(self.digraph_node(None, false, path.clone()));
+        }
+        children.sort_by_key(|n| std::cmp::Reverse(n.key(rule)));
+        children
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::cip::CipLabel;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+
+    fn atom_labels(smiles: &str) -> Vec<CipLabel> {
+        let m = parse_molecule(smiles).unwrap().1;
+        m.cip_labels().atoms.into_values().collect()
+    }
+
+    fn bond_labels(smiles: &str) -> Vec<CipLabel> {
+        let m = parse_molecule(smiles).unwrap().1;
+        m.cip_labels().bonds.into_values().collect()
+    }
+
+    #[test]
+    fn tetrahedral_labels() {
+        // L-alanine
+        assert_eq!(atom_labels("N[C@@H](C)C(=O)O"), vec![CipLabel::S]);
+        assert_eq!(atom_labels("C[C@@H](C(=O)O)N"), vec![CipLabel::S]);
+        assert_eq!(atom_labels("N[C@H](C)C(=O)O"), vec![CipLabel::R]);
+        // (R)-butan-2-ol
+        assert_eq!(atom_labels("CC[C@@H](C)O"), vec![CipLabel::R]);
+        // (R)-bromochlorofluoromethane
+        assert_eq!(atom_labels("F[C@H](Cl)Br"), vec![CipLabel::R]);
+        // L-cysteine is R, as the sulfur outranks the carboxyl group
+        assert_eq!(atom_labels("N[C@@H](CS)C(=O)O"), vec![CipLabel::R]);
+        // (R)-glyceraldehyde: the duplicated oxygen of the aldehyde outranks CH2OH
+        assert_eq!(atom_labels("OC[C@@H](O)C=O"), vec![CipLabel::R]);
+        // deuterium outranks hydrogen by rule 2
+        assert_eq!(atom_labels("[2H][C@@H](C)O"), vec![CipLabel::S]);
+        // no configuration, or equal neighbors
+        assert!(atom_labels("CC(O)CC").is_empty());
+        assert!(atom_labels("C[C@H](C)O").is_empty());
+    }
+
+    #[test]
+    fn tetrahedral_labels_rings() {
+        // (R)-3-methylcyclohexanone and (S)-1-phenylethanol
+        assert_eq!(atom_labels("C[C@@H]1CCCC(=O)C1"), vec![CipLabel::R]);
+        assert_eq!(atom_labels("C[C@H](O)c1ccccc1"), vec![CipLabel::S]);
+        // L-threonine is 2S,3R
+        let m = parse_molecule("C[C@H]([C@@H](C(=O)O)N)O").unwrap().1;
+        let labels = m.cip_labels().atoms;
+        assert_eq!(labels[&NodeIndex::new(1)], CipLabel::R);
+        assert_eq!(labels[&NodeIndex::new(2)], CipLabel::S);
+    }
+
+    #[test]
+    fn tetrahedral_labels_renumbered() {
+        // the ring branches of 1,4-dimethylcyclohexane tie however the atoms are numbered
+        assert!(atom_labels("CC1CC[C@H](C)CC1").is_empty());
+        assert!(atom_labels("C[C@H]1CCC(C)CC1").is_empty());
+        assert!(atom_labels("C[C@H]1CC[C@@H](C)CC1").is_empty());
+        assert!(atom_labels("C1C[C@@H](C)CC[C@H]1C").is_empty());
+        // the same molecule gets the same labels in any atom order
+        for smiles in [
+            [
+                "C[C@@H]1CCCC(=O)C1",
+                "O=C1CCC[C@@H](C)C1",
+                "C1C(=O)CCC[C@H]1C",
+            ],
+            [
+                "CC[C@@H](C)CC(C)CO",
+                "OCC(C)C[C@H](C)CC",
+                "C(C(C)CO)[C@@H](CC)C",
+            ],
+        ] {
+            let mut expected = atom_labels(smiles[0]);
+            expected.sort();
+            assert_eq!(expected.len(), 1);
+            for other in &smiles[1..] {
+                let mut labels = atom_labels(other);
+                labels.sort();
+                assert_eq!(labels, expected, "{other}");
+            }
+        }
+    }
+
+    #[test]
+    fn double_bond_labels() {
+        assert_eq!(bond_labels("C/C=C/C"), vec![CipLabel::E]);
+        assert_eq!(bond_labels("C/C=C\\C"), vec![CipLabel::Z]);
+        assert!(bond_labels("CC=CC").is_empty());
+        // the chlorine outranks the methyl group on the second atom
+        assert_eq!(bond_labels("C/C=C(/Cl)C"), vec![CipLabel::E]);
+        assert_eq!(bond_labels("C/C=C(\\Cl)C"), vec![CipLabel::Z]);
+        assert!(bond_labels("C/C=C(/C)C").is_empty());
+        // oximes: the hydroxyl group against the lone pair
+        assert_eq!(bond_labels("C/C(CC)=N/O"), vec![CipLabel::Z]);
+        let m = parse_molecule("F/C=C/C=C\\C").unwrap().1;
+        let labels = m.cip_labels().bonds;
+        assert_eq!(labels[&EdgeIndex::new(1)], CipLabel::E);
+        assert_eq!(labels[&EdgeIndex::new(3)], CipLabel::Z);
+    }
+}
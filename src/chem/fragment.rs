@@ -0,0 +1,202 @@
+use crate::chem::aromaticity::Aromaticity;
+use crate::model::atom::Atom;
+use crate::model::bond::{Bond, BondOrder};
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+
+impl Molecule {
+    /// Breaks the given bonds and caps both ends with wildcard attachment atoms. The labels
+    /// are the isotope labels of the wildcards at the first and the second atom of the bond,
+    /// as returned by `edge_endpoints`. Wildcards are bonded with the order of the broken
+    /// bond, so that hydrogen counts are kept.
+    pub fn fragment_on_bonds(&self, cuts: &[(EdgeIndex, [u16; 2])]) -> Molecule {
+        let mut fragmented = self.clone();
+        let snapshot = fragmented.stereo_snapshot();
+        let mut replaced = HashMap::new();
+        for (edge, [label_a, label_b]) in cuts {
+            let Some((a, b)) = self.graph.edge_endpoints(*edge) else {
+                continue;
+            };
+            let order = self.graph[*edge].order;
+            fragmented.graph.remove_edge(*edge);
+            for (atom, other, label) in [(a, b, *label_a), (b, a, *label_b)] {
+                let wildcard = fragmented.graph.add_node(Atom::wildcard(label));
+                fragmented.graph.add_edge(atom, wildcard, Bond::new(order));
+                replaced.insert((atom, Some(other)), Some(wildcard));
+            }
+        }
+        fragmented.invalidate_caches();
+        fragmented.restore_stereo(snapshot, &replaced);
+        fragmented
+    }
+
+    /// Connected components as separate molecules, in order of their lowest atom.
+    pub fn fragments(&self) -> Vec<Molecule> {
+        self.connected_components()
+            .iter()
+            .map(|atoms| self.submolecule(atoms).0)
+            .collect()
+    }
+
+    /// Number of wildcard attachment atoms.
+    pub fn count_wildcards(&self) -> usize {
+        self.graph
+            .node_weights()
+            .filter(|a| a.is_wildcard())
+            .count()
+    }
+
+    /// Joins a copy of `fragment` at the given attachment points, which must have exactly one
+    /// neighbor each: both wildcards are removed and their neighbors are bonded instead, with
+    /// the order of the bond at `point`.
+    pub(crate) fn join_attachment_points(
+        &self,
+        point: NodeIndex,
+        fragment: &Molecule,
+        fragment_point: NodeIndex,
+    ) -> Molecule {
+        let (bond, atom) = attachment(self, point);
+        let mut joined = self.clone();
+        let mapping = joined.merge(fragment);
+        let other_point = mapping[&fragment_point];
+        let (_, other_atom) = attachment(&joined, other_point);
+        let snapshot = joined.stereo_snapshot();
+        joined.remove_atom(point);
+        joined.remove_atom(other_point);
+        joined.add_bond(atom, other_atom, Bond::new(self.graph[bond].order));
+        let replaced = HashMap::from([
+            ((atom, Some(point)), Some(other_atom)),
+            ((other_atom, Some(other_point)), Some(atom)),
+        ]);
+        joined.restore_stereo(snapshot, &replaced);
+        joined
+    }
+}
+
+/// The bond of an attachment point and the atom it is attached to.
+pub(crate) fn attachment(molecule: &Molecule, point: NodeIndex) -> (EdgeIndex, NodeIndex) {
+    let bond = molecule.get_bonds_for_atom(point)[0];
+    let (a, b) = molecule.graph.edge_endpoints(bond).unwrap();
+    (bond, if a == point { b } else { a })
+}
+
+/// Perceived aromaticity and ring membership of a molecule, for matching the atom
+/// environments of fragmentation rules. Atoms are aromatic as perceived, whether written as
+/// aromatic or in Kekulé form.
+pub(crate) struct RuleContext<'a> {
+    pub molecule: &'a Molecule,
+    aromaticity: Aromaticity,
+}
+
+impl<'a> RuleContext<'a> {
+    pub fn new(molecule: &'a Molecule) -> RuleContext<'a> {
+        RuleContext {
+            molecule,
+            aromaticity: molecule.aromaticity(),
+        }
+    }
+
+    pub fn atomic_number(&self, atom: NodeIndex) -> u8 {
+        self.molecule.graph[atom].element.atomic_number
+    }
+
+    pub fn charge(&self, atom: NodeIndex) -> i8 {
+        self.molecule.graph[atom].charge
+    }
+
+    pub fn is_aromatic(&self, atom: NodeIndex) -> bool {
+        self.aromaticity.is_atom_aromatic(atom)
+    }
+
+    /// Whether the atom is non-aromatic with the given atomic number, like SMARTS `C`.
+    pub fn is_aliphatic(&self, atom: NodeIndex, atomic_number: u8) -> bool {
+        self.atomic_number(atom) == atomic_number && !self.is_aromatic(atom)
+    }
+
+    /// Whether the atom is aromatic with the given atomic number, like SMARTS `c`.
+    pub fn is_aromatic_element(&self, atom: NodeIndex, atomic_number: u8) -> bool {
+        self.atomic_number(atom) == atomic_number && self.is_aromatic(atom)
+    }
+
+    pub fn is_atom_in_ring(&self, atom: NodeIndex) -> bool {
+        self.molecule.ring_info().is_atom_in_ring(atom)
+    }
+
+    pub fn is_bond_in_ring(&self, bond: EdgeIndex) -> bool {
+        self.molecule.ring_info().is_bond_in_ring(bond)
+    }
+
+    pub fn is_aromatic_bond(&self, bond: EdgeIndex) -> bool {
+        self.molecule.graph[bond].order == BondOrder::Aromatic
+            || self.aromaticity.is_bond_aromatic(bond)
+    }
+
+    /// Whether the bond is a non-aromatic bond of the given order, like SMARTS `-` or `=`.
+    pub fn has_order(&self, bond: EdgeIndex, order: BondOrder) -> bool {
+        self.molecule.graph[bond].order == order && !self.is_aromatic_bond(bond)
+    }
+
+    /// Neighbors of the atom with the bonds to them.
+    pub fn neighbors(&self, atom: NodeIndex) -> Vec<(NodeIndex, EdgeIndex)> {
+        self.molecule
+            .graph
+            .edges(atom)
+            .map(|e| {
+                let neighbor = if e.source() == atom {
+                    e.target()
+                } else {
+                    e.source()
+                };
+                (neighbor, e.id())
+            })
+            .collect()
+    }
+
+    /// Whether the atom has a non-aromatic double bond to an atom with the given atomic
+    /// number, like SMARTS `$(*=O)`.
+    pub fn has_double_bond_to(&self, atom: NodeIndex, atomic_number: u8) -> bool {
+        self.count_double_bonds_to(atom, atomic_number) > 0
+    }
+
+    pub fn count_double_bonds_to(&self, atom: NodeIndex, atomic_number: u8) -> usize {
+        self.neighbors(atom)
+            .into_iter()
+            .filter(|(n, e)| {
+                self.atomic_number(*n) == atomic_number && self.has_order(*e, BondOrder::Double)
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use petgraph::stable_graph::EdgeIndex;
+
+    #[test]
+    fn fragment_on_bonds() {
+        let m = parse_molecule("CCOC(=O)C").unwrap().1;
+        let fragmented = m.fragment_on_bonds(&[(EdgeIndex::new(1), [3, 4])]);
+        assert_eq!(fragmented.count_wildcards(), 2);
+        let smiles: Vec<String> = fragmented
+            .fragments()
+            .iter()
+            .map(|f| f.canonical_smiles())
+            .collect();
+        assert_eq!(smiles, vec!["[3*]CC", "[4*]OC(C)=O"]);
+        // double bonds stay double
+        let m = parse_molecule("CC=CC").unwrap().1;
+        let fragmented = m.fragment_on_bonds(&[(EdgeIndex::new(1), [7, 7])]);
+        assert_eq!(fragmented.fragments()[0].canonical_smiles(), "[7*]=CC");
+    }
+
+    #[test]
+    fn fragment_keeps_stereo() {
+        let m = parse_molecule("N[C@@H](C)C(=O)OC").unwrap().1;
+        let fragmented = m.fragment_on_bonds(&[(EdgeIndex::new(0), [0, 0])]);
+        let expected = parse_molecule("N*.*[C@@H](C)C(=O)OC").unwrap().1;
+        assert_eq!(fragmented.canonical_smiles(), expected.canonical_smiles());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::chem::fragment::attachment;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::NodeIndex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryOptions {
+    /// Largest number of products.
+    pub max_products: usize,
+    /// If the library has more than `max_products` products, pick them at random with this
+    /// seed instead of taking the first ones.
+    pub sample_seed: Option<u64>,
+}
+
+impl Default for LibraryOptions {
+    fn default() -> Self {
+        LibraryOptions {
+            max_products: 100_000,
+            sample_seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibraryError {
+    /// The scaffold has attachment points with this label, but there are no R groups for it.
+    MissingRGroups(u16),
+    /// The R group at `index` of the list for `label` does not have exactly one attachment
+    /// point.
+    InvalidRGroup { label: u16, index: usize },
+}
+
+/// Groups R group fragments by the label of their attachment point. Fragments without
+/// exactly one labelled attachment point are left out.
+pub fn rgroup_lists(fragments: &[Molecule]) -> BTreeMap<u16, Vec<Molecule>> {
+    let mut lists: BTreeMap<u16, Vec<Molecule>> = BTreeMap::new();
+    for fragment in fragments {
+        if let [(_, label)] = attachment_points(fragment)[..] {
+            if label > 0 {
+                lists.entry(label).or_default().push(fragment.clone());
+            }
+        }
+    }
+    lists
+}
+
+/// Enumerates a combinatorial library: every combination of one R group per label, joined
+/// to the scaffold at the attachment points with that label. Attachment points are wildcards
+/// labelled with an atom map number like `[*:1]` or an isotope like `[1*]`; each R group
+/// has one, whatever its label. A hydrogen R group like `[*:1][H]` leaves an implicit
+/// hydrogen. Products are in order of the R group lists, the last label changing fastest.
+pub fn enumerate_library(
+    scaffold: &Molecule,
+    rgroups: &BTreeMap<u16, Vec<Molecule>>,
+    options: &LibraryOptions,
+) -> Result<Vec<Molecule>, LibraryError> {
+    let mut points: BTreeMap<u16, Vec<NodeIndex>> = BTreeMap::new();
+    for (point, label) in attachment_points(scaffold) {
+        points.entry(label).or_default().push(point);
+    }
+    let mut lists: Vec<(&Vec<NodeIndex>, Vec<Attachment>)> = Vec::new();
+    for (label, label_points) in &points {
+        let list = rgroups
+            .get(label)
+            .filter(|list| !list.is_empty())
+            .ok_or(LibraryError::MissingRGroups(*label))?;
+        let mut fragments = Vec::new();
+        for (index, fragment) in list.iter().enumerate() {
+            match attachment_points(fragment)[..] {
+                [(point, _)] => fragments.push((fragment, point)),
+                _ => {
+                    return Err(LibraryError::InvalidRGroup {
+                        label: *label,
+                        index,
+                    })
+                }
+            }
+        }
+        lists.push((label_points, fragments));
+    }
+
+    let sizes: Vec<u128> = lists.iter().map(|(_, l)| l.len() as u128).collect();
+    let size = sizes
+        .iter()
+        .try_fold(1u128, |size, s| size.checked_mul(*s))
+        .unwrap_or(u128::MAX);
+    let indexes: Vec<u128> = match options.sample_seed {
+        Some(seed) if size > options.max_products as u128 => {
+            let mut random = SplitMix64(seed);
+            let mut picked = HashSet::new();
+            let mut indexes = Vec::new();
+            while indexes.len() < options.max_products {
+                let index = random.next_u128() % size;
+                if picked.insert(index) {
+                    indexes.push(index);
+                }
+            }
+            indexes
+        }
+        _ => (0..size.min(options.max_products as u128)).collect(),
+    };
+
+    Ok(indexes
+        .into_iter()
+        .map(|mut index| {
+            // mixed radix digits of the index, the last list changing fastest
+            let mut choices = vec![0; lists.len()];
+            for (i, size) in sizes.iter().enumerate().rev() {
+                choices[i] = (index % size) as usize;
+                index /= size;
+            }
+            let mut product = scaffold.clone();
+            for ((label_points, fragments), choice) in lists.iter().zip(choices) {
+                let (fragment, fragment_point) = fragments[choice];
+                for point in *label_points {
+                    product = if is_hydrogen(fragment) {
+                        cap_with_hydrogen(&product, *point)
+                    } else {
+                        product.join_attachment_points(*point, fragment, fragment_point)
+                    };
+                }
+            }
+            product
+        })
+        .collect())
+}
+
+/// An R group with its attachment point.
+type Attachment<'a> = (&'a Molecule, NodeIndex);
+
+/// Labelled wildcard atoms with exactly one neighbor, with their atom map number or isotope.
+/// Unlabelled wildcards get label 0.
+fn attachment_points(molecule: &Molecule) -> Vec<(NodeIndex, u16)> {
+    molecule
+        .graph
+        .node_indices()
+        .filter(|a| molecule.graph[*a].is_wildcard() && molecule.graph.edges(*a).count() == 1)
+        .map(|a| {
+            let atom = &molecule.graph[a];
+            let label = if atom.map > 0 { atom.map } else { atom.isotope };
+            (a, label)
+        })
+        .collect()
+}
+
+/// Whether the fragment is a single hydrogen at an attachment point, like `[*:1][H]`.
+fn is_hydrogen(fragment: &Molecule) -> bool {
+    let atoms: Vec<_> = fragment.graph.node_weights().collect();
+    atoms.len() == 2
+        && atoms
+            .iter()
+            .any(|a| a.element.atomic_number == 1 && a.isotope == 0)
+}
+
+/// Removes the attachment point, leaving a hydrogen at its neighbor.
+fn cap_with_hydrogen(molecule: &Molecule, point: NodeIndex) -> Molecule {
+    let (_, atom) = attachment(molecule, point);
+    let mut capped = molecule.clone();
+    let hydrogens = capped.total_hydrogens(atom);
+    let snapshot = capped.stereo_snapshot();
+    capped.remove_atom(point);
+    capped.set_total_hydrogens(atom, hydrogens + 1);
+    capped.restore_stereo(snapshot, &HashMap::from([((atom, Some(point)), None)]));
+    capped
+}
+
+/// SplitMix64 generator, so that samples are reproducible from the seed.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use std::collections::BTreeSet;
+
+    fn molecule(smiles: &str) -> Molecule {
+        parse_molecule(smiles).unwrap().1
+    }
+
+    fn canonical(smiles: &[&str]) -> Vec<String> {
+        smiles
+            .iter()
+            .map(|s| molecule(s).canonical_smiles())
+            .collect()
+    }
+
+    fn library() -> (Molecule, BTreeMap<u16, Vec<Molecule>>) {
+        let scaffold = molecule("[*:1]c1ccc([*:2])cc1");
+        let rgroups = rgroup_lists(
+            &["[*:1]C", "[*:1]Cl", "[*:2]O", "[*:2]N", "[*:2][H]", "CC"]
+                .iter()
+                .map(|s| molecule(s))
+                .collect::<Vec<_>>(),
+        );
+        (scaffold, rgroups)
+    }
+
+    #[test]
+    fn enumerate_library() {
+        let (scaffold, rgroups) = library();
+        assert_eq!(rgroups[&1].len(), 2);
+        assert_eq!(rgroups[&2].len(), 3);
+        let products: Vec<String> =
+            super::enumerate_library(&scaffold, &rgroups, &LibraryOptions::default())
+                .unwrap()
+                .iter()
+                .map(|p| p.canonical_smiles())
+                .collect();
+        assert_eq!(
+            products,
+            canonical(&[
+                "Cc1ccc(O)cc1",
+                "Cc1ccc(N)cc1",
+                "Cc1ccccc1",
+                "Clc1ccc(O)cc1",
+                "Clc1ccc(N)cc1",
+                "Clc1ccccc1",
+            ])
+        );
+
+        // isotope labels, the same label at two points
+        let scaffold = molecule("[1*]OC(=O)CC(=O)O[1*]");
+        let rgroups = BTreeMap::from([(1, vec![molecule("CC*")])]);
+        let products =
+            super::enumerate_library(&scaffold, &rgroups, &LibraryOptions::default()).unwrap();
+        assert_eq!(
+            products[0].canonical_smiles(),
+            canonical(&["CCOC(=O)CC(=O)OCC"])[0]
+        );
+    }
+
+    #[test]
+    fn enumerate_library_sample() {
+        let (scaffold, rgroups) = library();
+        let options = LibraryOptions {
+            max_products: 4,
+            sample_seed: None,
+        };
+        let first = super::enumerate_library(&scaffold, &rgroups, &options).unwrap();
+        assert_eq!(first.len(), 4);
+        assert_eq!(
+            first[3].canonical_smiles(),
+            canonical(&["Clc1ccc(O)cc1"])[0]
+        );
+
+        let options = LibraryOptions {
+            max_products: 4,
+            sample_seed: Some(7),
+        };
+        let sample: Vec<String> = super::enumerate_library(&scaffold, &rgroups, &options)
+            .unwrap()
+            .iter()
+            .map(|p| p.canonical_smiles())
+            .collect();
+        assert_eq!(sample.iter().collect::<BTreeSet<_>>().len(), 4);
+        let again: Vec<String> = super::enumerate_library(&scaffold, &rgroups, &options)
+            .unwrap()
+            .iter()
+            .map(|p| p.canonical_smiles())
+            .collect();
+        assert_eq!(sample, again);
+    }
+
+    #[test]
+    fn enumerate_library_errors() {
+        let (scaffold, mut rgroups) = library();
+        rgroups.remove(&2);
+        assert_eq!(
+            super::enumerate_library(&scaffold, &rgroups, &LibraryOptions::default()).err(),
+            Some(LibraryError::MissingRGroups(2))
+        );
+        rgroups.insert(2, vec![molecule("[*:2]O"), molecule("[*:2]C[*:2]")]);
+        assert_eq!(
+            super::enumerate_library(&scaffold, &rgroups, &LibraryOptions::default()).err(),
+            Some(LibraryError::InvalidRGroup { label: 2, index: 1 })
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::chem::fragment::RuleContext;
+use crate::io::smiles::reader::molecule::parse_molecule;
+use crate::model::atom::Atom;
+use crate::model::bond::{Bond, BondOrder};
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmpOptions {
+    /// Largest number of bonds cut at once, 1 or 2.
+    pub max_cuts: usize,
+    /// Largest number of heavy atoms in the variable part.
+    pub max_variable_atoms: usize,
+    /// Also fragment at hydrogens, so that e.g. H and Cl substituents form a pair.
+    pub hydrogens: bool,
+}
+
+impl Default for MmpOptions {
+    fn default() -> Self {
+        MmpOptions {
+            max_cuts: 2,
+            max_variable_atoms: 13,
+            hydrogens: true,
+        }
+    }
+}
+
+/// A split of a molecule into a constant and a variable part (Hussain and Rea, J. Chem. Inf.
+/// Model. 2010), as canonical SMILES with the attachment points labelled `[1*]` and `[2*]`.
+/// The constant part of a double cut has two components, the variable part links them.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MmpFragmentation {
+    pub constant: String,
+    pub variable: String,
+    /// Number of heavy atoms in the variable part.
+    pub variable_atoms: usize,
+}
+
+/// Two molecules which differ only in the variable part at the same constant part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedPair {
+    pub from: usize,
+    pub to: usize,
+    pub constant: String,
+    pub from_variable: String,
+    pub to_variable: String,
+}
+
+impl MatchedPair {
+    /// The change from one molecule to the other, as `from>>to`.
+    pub fn transformation(&self) -> String {
+        format!("{}>>{}", self.from_variable, self.to_variable)
+    }
+}
+
+/// Fragmentations of a set of molecules, indexed by their constant part.
+#[derive(Debug, Clone, Default)]
+pub struct MmpIndex {
+    /// Record, variable part and its heavy atom count per constant part.
+    constants: BTreeMap<String, Vec<(usize, String, usize)>>,
+}
+
+impl MmpIndex {
+    /// Adds the fragmentations of the molecule under the given record number.
+    pub fn add(&mut self, record: usize, molecule: &Molecule, options: &MmpOptions) {
+        for fragmentation in molecule.mmp_fragmentations(options) {
+            self.constants
+                .entry(fragmentation.constant)
+                .or_default()
+                .push((record, fragmentation.variable, fragmentation.variable_atoms));
+        }
+    }
+
+    /// Matched pairs of different records, sorted by record numbers with the lower record
+    /// first. A pair of records that is matched at several constant parts is reported once,
+    /// with the smallest variable parts.
+    pub fn pairs(&self) -> Vec<MatchedPair> {
+        let mut best: BTreeMap<(usize, usize), (usize, MatchedPair)> = BTreeMap::new();
+        for (constant, entries) in &self.constants {
+            for (i, (record_a, variable_a, atoms_a)) in entries.iter().enumerate() {
+                for (record_b, variable_b, atoms_b) in &entries[i + 1..] {
+                    if record_a == record_b || variable_a == variable_b {
+                        continue;
+                    }
+                    let ((from, from_variable), (to, to_variable)) = if record_a < record_b {
+                        ((record_a, variable_a), (record_b, variable_b))
+                    } else {
+                        ((record_b, variable_b), (record_a, variable_a))
+                    };
+                    let size = atoms_a + atoms_b;
+                    if best
+                        .get(&(*from, *to))
+                        .is_some_and(|(best_size, _)| *best_size <= size)
+                    {
+                        continue;
+                    }
+                    let pair = MatchedPair {
+                        from: *from,
+                        to: *to,
+                        constant: constant.clone(),
+                        from_variable: from_variable.clone(),
+                        to_variable: to_variable.clone(),
+                    };
+                    best.insert((*from, *to), (size, pair));
+                }
+            }
+        }
+        best.into_values().map(|(_, pair)| pair).collect()
+    }
+}
+
+impl Molecule {
+    /// Bonds cut for matched pairs: acyclic single bonds at a neutral carbon which has no
+    /// double or triple bond to a heteroatom.
+    pub fn mmp_bonds(&self) -> Vec<EdgeIndex> {
+        let context = RuleContext::new(self);
+        let cuttable_carbon = |atom: NodeIndex| {
+            context.atomic_number(atom) == 6
+                && context.charge(atom) == 0
+                && !context.neighbors(atom).iter().any(|(n, e)| {
+                    context.atomic_number(*n) != 6
+                        && (context.has_order(*e, BondOrder::Double)
+                            || context.has_order(*e, BondOrder::Triple))
+                })
+        };
+        self.graph
+            .edge_indices()
+            .filter(|e| {
+                let (a, b) = self.graph.edge_endpoints(*e).unwrap();
+                context.has_order(*e, BondOrder::Single)
+                    && !context.is_bond_in_ring(*e)
+                    && [a, b].iter().all(|atom| context.atomic_number(*atom) > 1)
+                    && (cuttable_carbon(a) || cuttable_carbon(b))
+            })
+            .collect()
+    }
+
+    /// All splits into a constant and a variable part by cutting one or two `mmp_bonds`, and
+    /// by substituting a hydrogen if enabled. Aromaticity is perceived first, so Kekulé and
+    /// aromatic input give the same parts.
+    pub fn mmp_fragmentations(&self, options: &MmpOptions) -> Vec<MmpFragmentation> {
+        let mut molecule = self.clone();
+        molecule.aromatize();
+        let bonds = molecule.mmp_bonds();
+        let mut fragmentations = BTreeSet::new();
+
+        if options.max_cuts >= 1 {
+            for bond in &bonds {
+                let parts = molecule.fragment_on_bonds(&[(*bond, [1, 1])]).fragments();
+                for (i, variable) in parts.iter().enumerate() {
+                    let variable_atoms = heavy_atoms(variable);
+                    if variable.count_wildcards() == 0
+                        || variable_atoms > options.max_variable_atoms
+                    {
+                        continue;
+                    }
+                    // other components, like counterions, stay with the constant part
+                    let mut constant = Molecule::new();
+                    for (_, part) in parts.iter().enumerate().filter(|(j, _)| *j != i) {
+                        constant.merge(part);
+                    }
+                    fragmentations.insert(MmpFragmentation {
+                        constant: constant.canonical_smiles(),
+                        variable: variable.canonical_smiles(),
+                        variable_atoms,
+                    });
+                }
+            }
+        }
+
+        if options.max_cuts >= 2 {
+            for (i, first) in bonds.iter().enumerate() {
+                for second in &bonds[i + 1..] {
+                    // labels are assigned both ways round, keeping the smaller result, so
+                    // that the same split of another molecule gets the same labels
+                    let fragmentation = [[1, 2], [2, 1]]
+                        .iter()
+                        .filter_map(|[x, y]| molecule.double_cut(*first, *second, *x, *y, options))
+                        .min();
+                    fragmentations.extend(fragmentation);
+                }
+            }
+        }
+
+        if options.hydrogens {
+            let variable = parse_molecule("[1*][H]").unwrap().1.canonical_smiles();
+            for atom in molecule.graph.node_indices() {
+                let hydrogens = molecule.total_hydrogens(atom);
+                if hydrogens == 0 || molecule.graph[atom].is_wildcard() {
+                    continue;
+                }
+                let mut constant = molecule.clone();
+                let snapshot = constant.stereo_snapshot();
+                let wildcard = constant.add_atom(Atom::wildcard(1));
+                constant.add_bond(atom, wildcard, Bond::new(BondOrder::Single));
+                constant.set_total_hydrogens(atom, hydrogens - 1);
+                constant.restore_stereo(snapshot, &HashMap::from([((atom, None), Some(wildcard))]));
+                fragmentations.insert(MmpFragmentation {
+                    constant: constant.canonical_smiles(),
+                    variable: variable.clone(),
+                    variable_atoms: 0,
+                });
+            }
+        }
+        fragmentations.into_iter().collect()
+    }
+
+    /// Cuts both bonds, labelling the attachment points at the first `x` and at the second
+    /// `y`. None if the linker between them is too large.
+    fn double_cut(
+        &self,
+        first: EdgeIndex,
+        second: EdgeIndex,
+        x: u16,
+        y: u16,
+        options: &MmpOptions,
+    ) -> Option<MmpFragmentation> {
+        let parts = self
+            .fragment_on_bonds(&[(first, [x, x]), (second, [y, y])])
+            .fragments();
+        let (linker, ends): (Vec<Molecule>, Vec<Molecule>) =
+            parts.into_iter().partition(|p| p.count_wildcards() == 2);
+        let variable_atoms = heavy_atoms(linker.first()?);
+        if variable_atoms > options.max_variable_atoms {
+            return None;
+        }
+        let mut constant = Molecule::new();
+        for end in &ends {
+            constant.merge(end);
+        }
+        Some(MmpFragmentation {
+            constant: constant.canonical_smiles(),
+            variable: linker[0].canonical_smiles(),
+            variable_atoms,
+        })
+    }
+}
+
+/// Number of atoms other than hydrogen and wildcards.
+fn heavy_atoms(molecule: &Molecule) -> usize {
+    molecule
+        .graph
+        .node_weights()
+        .filter(|a| a.element.atomic_number > 1)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn molecule(smiles: &str) -> Molecule {
+        parse_molecule(smiles).unwrap().1
+    }
+
+    fn canonical(smiles: &str) -> String {
+        molecule(smiles).canonical_smiles()
+    }
+
+    #[test]
+    fn mmp_bonds() {
+        // the amide C-N bond is not cut, the carbonyl C has a double bond to O
+        assert_eq!(molecule("CC(=O)NC").mmp_bonds().len(), 2);
+        assert_eq!(molecule("c1ccccc1Cl").mmp_bonds().len(), 1);
+        assert!(molecule("C1CCCCC1").mmp_bonds().is_empty());
+    }
+
+    #[test]
+    fn mmp_fragmentations() {
+        let options = MmpOptions::default();
+        let fragmentations = molecule("c1ccccc1CCl").mmp_fragmentations(&options);
+        let expected = MmpFragmentation {
+            constant: canonical("c1ccccc1[1*]"),
+            variable: canonical("[1*]CCl"),
+            variable_atoms: 2,
+        };
+        assert!(fragmentations.contains(&expected));
+        // double cut: the CH2 linker between phenyl and chlorine
+        assert!(fragmentations
+            .iter()
+            .any(|f| f.variable == canonical("[1*]C[2*]") && f.constant.contains('.')));
+        // hydrogen substitutions
+        assert!(fragmentations
+            .iter()
+            .any(|f| f.constant == canonical("[1*]C(Cl)c1ccccc1") && f.variable_atoms == 0));
+
+        let options = MmpOptions {
+            max_cuts: 1,
+            max_variable_atoms: 1,
+            hydrogens: false,
+        };
+        let fragmentations = molecule("c1ccccc1CCl").mmp_fragmentations(&options);
+        let variables: BTreeSet<String> =
+            fragmentations.iter().map(|f| f.variable.clone()).collect();
+        assert_eq!(variables, BTreeSet::from([canonical("[1*]Cl")]));
+    }
+
+    #[test]
+    fn matched_pairs() {
+        let options = MmpOptions::default();
+        let mut index = MmpIndex::default();
+        for (record, smiles) in ["c1ccccc1CCl", "c1ccccc1CBr", "C1=CC=CC=C1CO", "CCCC"]
+            .iter()
+            .enumerate()
+        {
+            index.add(record, &molecule(smiles), &options);
+        }
+        let pairs = index.pairs();
+        let records: Vec<(usize, usize)> = pairs.iter().map(|p| (p.from, p.to)).collect();
+        assert_eq!(records, vec![(0, 1), (0, 2), (1, 2)]);
+        assert_eq!(pairs[0].constant, canonical("c1ccccc1C[1*]"));
+        assert_eq!(
+            pairs[0].transformation(),
+            format!("{}>>{}", canonical("[1*]Cl"), canonical("[1*]Br"))
+        );
+
+        // a hydrogen to methyl pair
+        let mut index = MmpIndex::default();
+        index.add(0, &molecule("c1ccccc1"), &options);
+        index.add(1, &molecule("c1ccccc1C"), &options);
+        let pairs = index.pairs();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(
+            pairs[0].transformation(),
+            format!("{}>>{}", canonical("[1*][H]"), canonical("[1*]C"))
+        );
+    }
+
+    #[test]
+    fn matched_pairs_double_cut() {
+        let options = MmpOptions {
+            hydrogens: false,
+            ..Default::default()
+        };
+        let mut index = MmpIndex::default();
+        index.add(0, &molecule("c1ccccc1CCC(F)(F)F"), &options);
+        index.add(1, &molecule("c1ccccc1OCC(F)(F)F"), &options);
+        let pairs = index.pairs();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].constant, canonical("[2*]c1ccccc1.FC(F)(F)C[1*]"));
+        assert_eq!(
+            pairs[0].transformation(),
+            format!("{}>>{}", canonical("[1*]C[2*]"), canonical("[1*]O[2*]"))
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use crate::model::atom::Atom;
+use crate::model::bond::{Bond, BondOrder};
+use crate::model::molecule::Molecule;
+use crate::model::reaction::Reaction;
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Applying a reaction stops after this many distinct outcomes.
+const MAX_REACTION_OUTCOMES: usize = 1000;
+
+impl Reaction {
+    /// Applies the reaction as a template, like a SMIRKS transform, to one molecule per
+    /// reactant template. Every combination of substructure matches of the reactant
+    /// templates gives an outcome: one molecule per product template, unless the same
+    /// products were found before.
+    ///
+    /// Atoms are carried over by atom map number. Matched atoms which are unmapped, or whose
+    /// map number is not among the products, are removed; unmapped product atoms are added.
+    /// Bonds between mapped atoms are added, removed or changed as in the templates, and
+    /// the element, charge and isotope of mapped atoms where they differ between the
+    /// templates. Other atoms of the reactants stay with the atoms they are bonded to.
+    /// Hydrogens of a product atom written without a hydrogen count, like `[N:1]`, follow
+    /// from valence. A bracket hydrogen count in the product template is set, or, if the
+    /// reactant template has one too, applied as the difference, like `[NH3+:1]>>[NH2:1]`.
+    /// Stereo is kept where the neighbors of the atom or double bond do not change.
+    ///
+    /// Templates are read from SMILES, so SMARTS primitives like `[N;H2:1]` are not
+    /// supported, and `H0` cannot be told apart from no hydrogen count.
+    pub fn run_reactants(&self, reactants: &[Molecule]) -> Vec<Vec<Molecule>> {
+        self.outcomes(reactants, |molecule, images| {
+            let components = molecule.connected_components();
+            images
+                .iter()
+                .map(|atoms| {
+                    let product: BTreeSet<NodeIndex> = components
+                        .iter()
+                        .filter(|c| !c.is_disjoint(atoms))
+                        .flatten()
+                        .copied()
+                        .collect();
+                    molecule.submolecule(&product).0
+                })
+                .collect()
+        })
+    }
+
+    /// Distinct outcomes of every combination of matches. `products` splits the reactants
+    /// after the changes into products, given the atoms of every product template.
+    fn outcomes<F>(&self, reactants: &[Molecule], products: F) -> Vec<Vec<Molecule>>
+    where
+        F: Fn(&Molecule, &[BTreeSet<NodeIndex>]) -> Vec<Molecule>,
+    {
+        let mut outcomes = Vec::new();
+        if reactants.len() != self.reactants.len() {
+            return outcomes;
+        }
+        let matches: Vec<Vec<HashMap<NodeIndex, NodeIndex>>> = self
+            .reactants
+            .iter()
+            .zip(reactants)
+            .map(|(template, reactant)| reactant.substructure_matches(template))
+            .collect();
+        if matches.iter().any(|m| m.is_empty()) {
+            return outcomes;
+        }
+        let mut seen = HashSet::new();
+        // one match per reactant, the last reactant changing fastest
+        let mut choice = vec![0; matches.len()];
+        loop {
+            let mappings: Vec<&HashMap<NodeIndex, NodeIndex>> =
+                matches.iter().zip(&choice).map(|(m, c)| &m[*c]).collect();
+            let (molecule, images) = self.apply(reactants, &mappings);
+            let products = products(&molecule, &images);
+            let key: Vec<String> = products.iter().map(|p| p.canonical_smiles()).collect();
+            if seen.insert(key) {
+                outcomes.push(products);
+                if outcomes.len() >= MAX_REACTION_OUTCOMES {
+                    return outcomes;
+                }
+            }
+            let mut i = choice.len();
+            loop {
+                if i == 0 {
+                    return outcomes;
+                }
+                i -= 1;
+                choice[i] += 1;
+                if choice[i] < matches[i].len() {
+                    break;
+                }
+                choice[i] = 0;
+            }
+        }
+    }
+
+    /// Changes the reactants for the given mapping of every reactant template. Returns them
+    /// as one molecule, with the atoms of every product template.
+    fn apply(
+        &self,
+        reactants: &[Molecule],
+        mappings: &[&HashMap<NodeIndex, NodeIndex>],
+    ) -> (Molecule, Vec<BTreeSet<NodeIndex>>) {
+        let product_maps: HashSet<u16> = self
+            .products
+            .iter()
+            .flat_map(|p| p.graph.node_weights().map(|a| a.map))
+            .filter(|m| *m > 0)
+            .collect();
+
+        let mut molecule = Molecule::new();
+        // reactant template atom by map number, with its atom in the merged reactants
+        let mut mapped: HashMap<u16, (&Atom, NodeIndex)> = HashMap::new();
+        let mut removed = Vec::new();
+        for ((template, reactant), mapping) in self.reactants.iter().zip(reactants).zip(mappings) {
+            let merged = molecule.merge(reactant);
+            for (atom, image) in mapping.iter() {
+                let t = &template.graph[*atom];
+                if product_maps.contains(&t.map) {
+                    mapped.insert(t.map, (t, merged[image]));
+                } else {
+                    removed.push(merged[image]);
+                }
+            }
+        }
+        let reactant_bonds = mapped_bonds(&self.reactants, &mapped);
+        let product_bonds = mapped_bonds(&self.products, &mapped);
+
+        let snapshot = molecule.stereo_snapshot();
+        let before: HashMap<NodeIndex, (u8, u8)> = mapped
+            .values()
+            .map(|(_, a)| {
+                (
+                    *a,
+                    (molecule.total_hydrogens(*a), bond_valence(&molecule, *a)),
+                )
+            })
+            .collect();
+
+        for ((a, b), order) in &reactant_bonds {
+            match product_bonds.get(&(*a, *b)) {
+                None => {
+                    molecule.remove_bond_by_atoms(*a, *b);
+                }
+                Some(o) if o != order => set_bond(&mut molecule, *a, *b, *o),
+                _ => {}
+            }
+        }
+        for ((a, b), order) in &product_bonds {
+            if !reactant_bonds.contains_key(&(*a, *b)) {
+                set_bond(&mut molecule, *a, *b, *order);
+            }
+        }
+
+        // atoms of every product template in the merged molecule
+        let mut images: Vec<BTreeSet<NodeIndex>> = Vec::new();
+        for template in &self.products {
+            let mut atoms: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+            for atom in template.graph.node_indices() {
+                let p = &template.graph[atom];
+                let image = match mapped.get(&p.map) {
+                    Some((t, image)) => {
+                        if !p.is_wildcard() && p.element != t.element {
+                            molecule.set_element(*image, p.element.clone());
+                        }
+                        if p.charge != t.charge {
+                            molecule.set_charge(*image, p.charge);
+                        }
+                        if p.isotope != t.isotope {
+                            molecule.set_isotope(*image, p.isotope);
+                        }
+                        *image
+                    }
+                    None => molecule.add_atom(Atom {
+                        map: 0,
+                        ..p.clone()
+                    }),
+                };
+                atoms.insert(atom, image);
+            }
+            for edge in (&template.graph).edge_references() {
+                let (a, b) = (
+                    &template.graph[edge.source()],
+                    &template.graph[edge.target()],
+                );
+                if !mapped.contains_key(&a.map) || !mapped.contains_key(&b.map) {
+                    molecule.add_bond(
+                        atoms[&edge.source()],
+                        atoms[&edge.target()],
+                        edge.weight().clone(),
+                    );
+                }
+            }
+            images.push(atoms.into_values().collect());
+        }
+        for atom in removed {
+            molecule.remove_atom(atom);
+        }
+        molecule.invalidate_caches();
+
+        for template in &self.products {
+            for p in template.graph.node_weights() {
+                let Some((t, atom)) = mapped.get(&p.map) else {
+                    continue;
+                };
+                let (hydrogens, valence) = before[atom];
+                let has_count = |a: &Atom| a.no_implicit_hs && a.hs > 0;
+                if has_count(p) {
+                    let count = if has_count(t) {
+                        hydrogens as i16 + p.hs as i16 - t.hs as i16
+                    } else {
+                        p.hs as i16
+                    };
+                    molecule.set_total_hydrogens(*atom, count.max(0) as u8);
+                } else if molecule.graph[*atom].no_implicit_hs {
+                    // fixed counts give way to the bonds which were added
+                    let added = bond_valence(&molecule, *atom) as i16 - valence as i16;
+                    let count = hydrogens as i16 - added / 2;
+                    molecule.set_hs(*atom, count.max(0) as u8);
+                }
+            }
+        }
+        molecule.restore_stereo(snapshot, &HashMap::new());
+
+        (molecule, images)
+    }
+}
+
+impl Molecule {
+    /// Applies a transform with one reactant template to this molecule (see
+    /// `Reaction::run_reactants`), like the standardization rule
+    /// `[N:1](=[O:2])=[O:3]>>[N+:1](=[O:2])[O-:3]`. Returns every distinct outcome as one
+    /// molecule, including the components which the transform does not touch.
+    pub fn apply_transform(&self, transform: &Reaction) -> Vec<Molecule> {
+        transform
+            .outcomes(std::slice::from_ref(self), |molecule, _| {
+                vec![molecule.clone()]
+            })
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+/// Bonds between mapped atoms of the templates, as pairs of atoms of the merged reactants.
+fn mapped_bonds(
+    templates: &[Molecule],
+    mapped: &HashMap<u16, (&Atom, NodeIndex)>,
+) -> HashMap<(NodeIndex, NodeIndex), BondOrder> {
+    let mut bonds = HashMap::new();
+    for template in templates {
+        for edge in (&template.graph).edge_references() {
+            let a = mapped.get(&template.graph[edge.source()].map);
+            let b = mapped.get(&template.graph[edge.target()].map);
+            if let (Some((_, a)), Some((_, b))) = (a, b) {
+                bonds.insert((*a.min(b), *a.max(b)), edge.weight().order);
+            }
+        }
+    }
+    bonds
+}
+
+fn set_bond(molecule: &mut Molecule, a: NodeIndex, b: NodeIndex, order: BondOrder) {
+    match molecule.graph.find_edge_undirected(a, b) {
+        Some((edge, _)) => {
+            molecule.set_bond_order(edge, order);
+        }
+        None => {
+            molecule.add_bond(a, b, Bond::new(order));
+        }
+    }
+}
+
+/// Sum of the bond orders of the atom, in half bonds so that aromatic bonds count 1.5.
+fn bond_valence(molecule: &Molecule, atom: NodeIndex) -> u8 {
+    molecule
+        .graph
+        .edges(atom)
+        .map(|e| match e.weight().order {
+            BondOrder::Single => 2,
+            BondOrder::Double => 4,
+            BondOrder::Triple => 6,
+            BondOrder::Aromatic => 3,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::io::smiles::reader::reaction::parse_reaction;
+    use crate::model::molecule::Molecule;
+
+    fn molecule(smiles: &str) -> Molecule {
+        parse_molecule(smiles).unwrap().1
+    }
+
+    fn canonical(smiles: &str) -> String {
+        molecule(smiles).canonical_smiles()
+    }
+
+    fn transform(smirks: &str, smiles: &str) -> Vec<String> {
+        let transform = parse_reaction(smirks).unwrap().1;
+        molecule(smiles)
+            .apply_transform(&transform)
+            .iter()
+            .map(|m| m.canonical_smiles())
+            .collect()
+    }
+
+    #[test]
+    fn run_reactants() {
+        // amide coupling: the hydroxyl of the acid leaves, the amine loses a hydrogen
+        let reaction = parse_reaction("[C:1](=[O:2])O.[NH2:3]>>[C:1](=[O:2])[NH:3]")
+            .unwrap()
+            .1;
+        let outcomes = reaction.run_reactants(&[molecule("CC(=O)O"), molecule("CN")]);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].len(), 1);
+        assert_eq!(outcomes[0][0].canonical_smiles(), canonical("CNC(C)=O"));
+        // a product atom without a hydrogen count gets its hydrogens from valence
+        let reaction = parse_reaction("[C:1](=[O:2])[OH].[NH2:4]>>[C:1](=[O:2])[N:4]")
+            .unwrap()
+            .1;
+        let outcomes = reaction.run_reactants(&[molecule("CC(=O)O"), molecule("CCN")]);
+        assert_eq!(outcomes[0][0].canonical_smiles(), canonical("CCNC(C)=O"));
+        let outcomes = reaction.run_reactants(&[molecule("CC(=O)O"), molecule("CC[NH2]")]);
+        assert_eq!(outcomes[0][0].canonical_smiles(), canonical("CCNC(C)=O"));
+        // wrong number of reactants, or a reactant without a match
+        assert!(reaction.run_reactants(&[molecule("CC(=O)O")]).is_empty());
+        assert!(reaction
+            .run_reactants(&[molecule("CCO"), molecule("CN")])
+            .is_empty());
+
+        // every match gives an outcome, the same products only once
+        let reaction = parse_reaction("[C:1](=[O:2])[OH:3]>>[C:1](=[O:2])[O:3]C")
+            .unwrap()
+            .1;
+        let outcomes = reaction.run_reactants(&[molecule("OC(=O)CCC(=O)O")]);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(
+            outcomes[0][0].canonical_smiles(),
+            canonical("COC(=O)CCC(=O)O")
+        );
+        let outcomes = reaction.run_reactants(&[molecule("OC(=O)CC(C)C(=O)O")]);
+        assert_eq!(outcomes.len(), 2);
+    }
+
+    #[test]
+    fn run_reactants_products() {
+        // ester hydrolysis gives two products, the stereocenter away from the reaction stays
+        let reaction = parse_reaction("[C:1](=[O:2])[O:3][C:4]>>[C:1](=[O:2])[OH:3].[OH][C:4]")
+            .unwrap()
+            .1;
+        let outcomes = reaction.run_reactants(&[molecule("C[C@H](N)C(=O)OCC")]);
+        assert_eq!(outcomes.len(), 1);
+        let products: Vec<String> = outcomes[0].iter().map(|p| p.canonical_smiles()).collect();
+        assert_eq!(
+            products,
+            vec![canonical("C[C@H](N)C(=O)O"), canonical("CCO")]
+        );
+    }
+
+    #[test]
+    fn apply_transform() {
+        // bond and charge changes
+        assert_eq!(
+            transform("[N:1](=[O:2])=[O:3]>>[N+:1](=[O:2])[O-:3]", "CN(=O)=O"),
+            vec![canonical("C[N+](=O)[O-]")]
+        );
+        // hydrogen changes of bracket atoms
+        assert_eq!(
+            transform("[O-:1]>>[OH:1]", "CC(=O)[O-].[Na+]"),
+            vec![canonical("CC(=O)O.[Na+]")]
+        );
+        assert_eq!(
+            transform("[NH3+:1]>>[NH2:1]", "C[NH3+]"),
+            vec![canonical("CN")]
+        );
+        // element changes, atoms outside of the template are kept
+        assert_eq!(
+            transform("[Cl:1][c:2][c:3]>>[F:1][c:2][c:3]", "Clc1ccc(Cl)cc1"),
+            vec![canonical("Fc1ccc(Cl)cc1")]
+        );
+        assert!(transform("[Br:1]>>[I:1]", "CCCl").is_empty());
+        // aromatic template atoms outside of a complete ring
+        assert_eq!(
+            transform("[c:1][Br:2]>>[c:1][C:3]#[N:4]", "Brc1ccccc1"),
+            vec![canonical("N#Cc1ccccc1")]
+        );
+    }
+}
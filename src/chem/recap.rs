@@ -0,0 +1,323 @@
+use crate::chem::fragment::RuleContext;
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::HashMap;
+
+/// RECAP cleavage rules (Lewell et al., J. Chem. Inf. Comput. Sci. 1998), in the order they
+/// are tried on a bond. Only acyclic bonds are broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RecapRule {
+    /// C(=O)-N of a urea.
+    Urea,
+    /// C(=O)-N of an amide.
+    Amide,
+    /// C(=O)-O of an ester.
+    Ester,
+    /// N-S(=O)=O of a sulfonamide.
+    Sulfonamide,
+    /// N-X of an amine, where the N is not bonded to a C=N, C=O, C=P or C=S.
+    Amine,
+    /// C-O of an ether.
+    Ether,
+    /// C=C between aliphatic carbons.
+    Olefin,
+    /// N+-C of a quaternary nitrogen.
+    QuaternaryNitrogen,
+    /// n-C between an aromatic nitrogen and an aliphatic carbon.
+    AromaticNitrogenAliphaticCarbon,
+    /// N-C between a lactam nitrogen and an aliphatic carbon.
+    LactamNitrogenAliphaticCarbon,
+    /// c-c between two aromatic carbons.
+    AromaticCarbonAromaticCarbon,
+}
+
+const RECAP_RULES: [RecapRule; 11] = [
+    RecapRule::Urea,
+    RecapRule::Amide,
+    RecapRule::Ester,
+    RecapRule::Sulfonamide,
+    RecapRule::Amine,
+    RecapRule::Ether,
+    RecapRule::Olefin,
+    RecapRule::QuaternaryNitrogen,
+    RecapRule::AromaticNitrogenAliphaticCarbon,
+    RecapRule::LactamNitrogenAliphaticCarbon,
+    RecapRule::AromaticCarbonAromaticCarbon,
+];
+
+impl RecapRule {
+    /// Whether the rule breaks the bond between `a` and `b`, in this orientation.
+    fn matches(self, context: &RuleContext, bond: EdgeIndex, a: NodeIndex, b: NodeIndex) -> bool {
+        let single = context.has_order(bond, BondOrder::Single);
+        let neutral = |atom: NodeIndex| context.charge(atom) == 0;
+        let degree = |atom: NodeIndex| context.neighbors(atom).len();
+        let nitrogens = |atom: NodeIndex| {
+            context
+                .neighbors(atom)
+                .iter()
+                .filter(|(n, _)| context.atomic_number(*n) == 7)
+                .count()
+        };
+        let carbonyl =
+            |atom: NodeIndex| context.is_aliphatic(atom, 6) && context.has_double_bond_to(atom, 8);
+        match self {
+            RecapRule::Urea => {
+                single
+                    && carbonyl(a)
+                    && nitrogens(a) >= 2
+                    && context.atomic_number(b) == 7
+                    && neutral(b)
+                    && (degree(b) == 2 || degree(b) == 3)
+            }
+            RecapRule::Amide => {
+                single
+                    && carbonyl(a)
+                    && nitrogens(a) < 2
+                    && context.atomic_number(b) == 7
+                    && neutral(b)
+                    && degree(b) > 1
+            }
+            // the oxygen is bonded to carbon on both sides, so acids are not broken
+            RecapRule::Ester => {
+                single && carbonyl(a) && context.is_aliphatic(b, 8) && neutral(b) && degree(b) == 2
+            }
+            RecapRule::Sulfonamide => {
+                single
+                    && context.atomic_number(a) == 7
+                    && neutral(a)
+                    && (degree(a) == 2 || degree(a) == 3)
+                    && context.is_aliphatic(b, 16)
+                    && context.count_double_bonds_to(b, 8) >= 2
+            }
+            RecapRule::Amine => {
+                single
+                    && context.is_aliphatic(a, 7)
+                    && neutral(a)
+                    && degree(a) > 1
+                    && !context.neighbors(a).iter().any(|(n, e)| {
+                        context.has_order(*e, BondOrder::Single)
+                            && context.is_aliphatic(*n, 6)
+                            && [7, 8, 15, 16]
+                                .iter()
+                                .any(|z| context.has_double_bond_to(*n, *z))
+                    })
+            }
+            RecapRule::Ether => {
+                single
+                    && context.is_aliphatic(a, 8)
+                    && neutral(a)
+                    && context.neighbors(a).iter().all(|(n, e)| {
+                        context.atomic_number(*n) == 6
+                            && context.has_order(*e, BondOrder::Single)
+                            && !context.is_bond_in_ring(*e)
+                    })
+                    && degree(a) == 2
+            }
+            RecapRule::Olefin => {
+                context.has_order(bond, BondOrder::Double)
+                    && context.is_aliphatic(a, 6)
+                    && context.is_aliphatic(b, 6)
+            }
+            RecapRule::QuaternaryNitrogen => {
+                single
+                    && context.is_aliphatic(a, 7)
+                    && context.charge(a) == 1
+                    && degree(a) == 4
+                    && context.atomic_number(b) == 6
+            }
+            RecapRule::AromaticNitrogenAliphaticCarbon => {
+                single
+                    && context.is_aromatic_element(a, 7)
+                    && neutral(a)
+                    && context.is_aliphatic(b, 6)
+            }
+            RecapRule::LactamNitrogenAliphaticCarbon => {
+                single
+                    && context.is_aliphatic(a, 7)
+                    && neutral(a)
+                    && context.neighbors(a).iter().any(|(n, e)| {
+                        context.is_bond_in_ring(*e)
+                            && context.has_order(*e, BondOrder::Single)
+                            && carbonyl(*n)
+                    })
+                    && context.is_aliphatic(b, 6)
+            }
+            RecapRule::AromaticCarbonAromaticCarbon => {
+                single && context.is_aromatic_element(a, 6) && context.is_aromatic_element(b, 6)
+            }
+        }
+    }
+}
+
+/// A fragment in a RECAP hierarchy, with the indexes of the fragments it splits into.
+#[derive(Clone)]
+pub struct RecapNode {
+    pub smiles: String,
+    pub molecule: Molecule,
+    pub children: Vec<usize>,
+}
+
+/// Fragments reachable by breaking RECAP bonds one at a time. The molecule itself is the
+/// first node; fragments are unique by canonical SMILES, so a node may have several parents.
+#[derive(Clone)]
+pub struct RecapHierarchy {
+    pub nodes: Vec<RecapNode>,
+}
+
+impl RecapHierarchy {
+    pub fn root(&self) -> &RecapNode {
+        &self.nodes[0]
+    }
+
+    /// Fragments without RECAP bonds left, empty if the molecule has none.
+    pub fn leaves(&self) -> Vec<&RecapNode> {
+        self.nodes[1..]
+            .iter()
+            .filter(|n| n.children.is_empty())
+            .collect()
+    }
+}
+
+impl Molecule {
+    /// Bonds broken by RECAP with the first rule matching them.
+    pub fn recap_bonds(&self) -> Vec<(EdgeIndex, RecapRule)> {
+        let context = RuleContext::new(self);
+        let mut bonds = Vec::new();
+        for edge in self.graph.edge_indices() {
+            let (a, b) = self.graph.edge_endpoints(edge).unwrap();
+            if context.is_bond_in_ring(edge)
+                || self.graph[a].is_wildcard()
+                || self.graph[b].is_wildcard()
+            {
+                continue;
+            }
+            let rule = RECAP_RULES.iter().find(|rule| {
+                rule.matches(&context, edge, a, b) || rule.matches(&context, edge, b, a)
+            });
+            if let Some(rule) = rule {
+                bonds.push((edge, *rule));
+            }
+        }
+        bonds
+    }
+
+    /// Breaks every RECAP bond on its own, then does the same on the resulting fragments
+    /// until none are left. Attachment points are unlabelled wildcards.
+    pub fn recap_hierarchy(&self) -> RecapHierarchy {
+        let root = RecapNode {
+            smiles: self.canonical_smiles(),
+            molecule: self.clone(),
+            children: Vec::new(),
+        };
+        let mut indexes: HashMap<String, usize> = HashMap::from([(root.smiles.clone(), 0)]);
+        let mut nodes = vec![root];
+        let mut next = 0;
+        while next < nodes.len() {
+            let molecule = nodes[next].molecule.clone();
+            for (bond, _) in molecule.recap_bonds() {
+                for fragment in molecule.fragment_on_bonds(&[(bond, [0, 0])]).fragments() {
+                    let smiles = fragment.canonical_smiles();
+                    let index = *indexes.entry(smiles.clone()).or_insert_with(|| {
+                        nodes.push(RecapNode {
+                            smiles,
+                            molecule: fragment,
+                            children: Vec::new(),
+                        });
+                        nodes.len() - 1
+                    });
+                    if !nodes[next].children.contains(&index) {
+                        nodes[next].children.push(index);
+                    }
+                }
+            }
+            next += 1;
+        }
+        RecapHierarchy { nodes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use std::collections::BTreeSet;
+
+    fn rules(smiles: &str) -> Vec<RecapRule> {
+        let mut rules: Vec<RecapRule> = parse_molecule(smiles)
+            .unwrap()
+            .1
+            .recap_bonds()
+            .into_iter()
+            .map(|(_, rule)| rule)
+            .collect();
+        rules.sort();
+        rules
+    }
+
+    #[test]
+    fn recap_rules() {
+        use RecapRule::*;
+        assert_eq!(rules("CC(=O)NC"), vec![Amide]);
+        assert_eq!(rules("CC(=O)O"), vec![]);
+        assert_eq!(rules("CC(=O)OC"), vec![Ester, Ether]);
+        assert_eq!(rules("CNC(=O)NC"), vec![Urea, Urea]);
+        assert_eq!(rules("CCN(C)C"), vec![Amine, Amine, Amine]);
+        assert_eq!(rules("CCOCC"), vec![Ether, Ether]);
+        assert_eq!(rules("CC=CC"), vec![Olefin]);
+        assert_eq!(rules("C1CC=CC1"), vec![]);
+        assert_eq!(rules("C[N+](C)(C)CC"), vec![QuaternaryNitrogen; 4]);
+        assert_eq!(rules("CCn1ccnc1"), vec![AromaticNitrogenAliphaticCarbon]);
+        assert_eq!(rules("CCN1CCCC1=O"), vec![LactamNitrogenAliphaticCarbon]);
+        assert_eq!(
+            rules("c1ccccc1-c1ccccc1"),
+            vec![AromaticCarbonAromaticCarbon]
+        );
+        assert_eq!(rules("CS(=O)(=O)NC"), vec![Sulfonamide, Amine]);
+    }
+
+    #[test]
+    fn recap_hierarchy() {
+        let canonical = |smiles: &[&str]| -> BTreeSet<String> {
+            smiles
+                .iter()
+                .map(|s| parse_molecule(s).unwrap().1.canonical_smiles())
+                .collect()
+        };
+        let m = parse_molecule("CC(=O)NCCOc1ccccc1").unwrap().1;
+        let hierarchy = m.recap_hierarchy();
+        assert_eq!(hierarchy.root().smiles, m.canonical_smiles());
+        let children: BTreeSet<String> = hierarchy
+            .root()
+            .children
+            .iter()
+            .map(|i| hierarchy.nodes[*i].smiles.clone())
+            .collect();
+        assert_eq!(
+            children,
+            canonical(&[
+                "CC(=O)*",
+                "*NCCOc1ccccc1",
+                "CC(=O)NCC*",
+                "*Oc1ccccc1",
+                "CC(=O)NCCO*",
+                "*c1ccccc1",
+            ]),
+        );
+        let leaves: BTreeSet<String> = hierarchy
+            .leaves()
+            .iter()
+            .map(|n| n.smiles.clone())
+            .collect();
+        // rules see attachment points as neither carbon nor a carbonyl, so the amine is
+        // broken once the amide is, and the ether only from the intact side
+        assert_eq!(
+            leaves,
+            canonical(&["CC(=O)*", "*N*", "*CC*", "*CCO*", "*Oc1ccccc1", "*c1ccccc1"]),
+        );
+
+        let m = parse_molecule("c1ccccc1").unwrap().1;
+        assert_eq!(m.recap_hierarchy().nodes.len(), 1);
+        assert!(m.recap_hierarchy().leaves().is_empty());
+    }
+}
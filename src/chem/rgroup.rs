@@ -0,0 +1,318 @@
+use crate::io::smiles::reader::molecule::parse_molecule;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Write;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RGroupOptions {
+    /// Report molecules with substituents at unlabelled core atoms as unmatched, instead of
+    /// giving those atoms new labels.
+    pub only_at_labels: bool,
+}
+
+/// A molecule split into the matched core and its substituents.
+#[derive(Clone)]
+pub struct RGroupRow {
+    /// Index of the molecule in the decomposed list.
+    pub record: usize,
+    /// Atoms matched by the core, with labelled attachment points where substituents were.
+    pub core: Molecule,
+    /// Substituents by label, attached at labelled wildcards. Hydrogen (`[1*][H]`) where a
+    /// labelled core atom has no substituent.
+    pub rgroups: BTreeMap<u16, Molecule>,
+}
+
+#[derive(Clone, Default)]
+pub struct RGroupDecomposition {
+    /// All labels: those of the core and those given to unlabelled core atoms.
+    pub labels: BTreeSet<u16>,
+    pub rows: Vec<RGroupRow>,
+    /// Indexes of molecules which do not contain the core.
+    pub unmatched: Vec<usize>,
+}
+
+impl RGroupDecomposition {
+    /// Writes tab separated `record`, `core` and `R<label>` columns as canonical SMILES,
+    /// after a header line.
+    pub fn write_table<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write!(writer, "record\tcore")?;
+        for label in &self.labels {
+            write!(writer, "\tR{}", label)?;
+        }
+        writeln!(writer)?;
+        for row in &self.rows {
+            write!(writer, "{}\t{}", row.record, row.core.canonical_smiles())?;
+            for label in &self.labels {
+                write!(writer, "\t{}", row.rgroups[label].canonical_smiles())?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decomposes the molecules into the core and R groups. The core is read from SMILES, not
+/// SMARTS. Attachment points of the core are wildcards labelled with an isotope like `[1*]`
+/// or an atom map number like `[*:1]`; R groups are labelled with the isotope in both cases.
+/// The other core atoms are matched as in `Molecule::substructure_matches`. Of several
+/// mappings, the first one with substituents only at labelled core atoms is used, then the
+/// first one with substituents only at those and at atoms labelled for earlier molecules.
+/// Substituents at unlabelled core atoms get new labels,
+/// the same for all molecules, unless `only_at_labels` is set. Components of a molecule
+/// which are not bonded to the core, like counterions, are left out.
+pub fn rgroup_decomposition(
+    core: &Molecule,
+    molecules: &[Molecule],
+    options: &RGroupOptions,
+) -> RGroupDecomposition {
+    let mut query = core.clone();
+    let mut labels: HashMap<NodeIndex, u16> = HashMap::new();
+    for atom in core.graph.node_indices() {
+        let a = &core.graph[atom];
+        let neighbors = core.get_neighbors_for_atom(atom);
+        let label = if a.map > 0 { a.map } else { a.isotope };
+        if a.is_wildcard() && label > 0 && neighbors.len() == 1 {
+            labels.insert(*neighbors.first().unwrap(), label);
+            query.remove_atom(atom);
+        }
+    }
+    query.invalidate_caches();
+    let core_labels: BTreeSet<NodeIndex> = labels.keys().copied().collect();
+
+    let mut decomposition = RGroupDecomposition {
+        labels: labels.values().copied().collect(),
+        ..Default::default()
+    };
+    for (record, molecule) in molecules.iter().enumerate() {
+        let matches = molecule.substructure_mappings(&query);
+        let substituted = |mapping: &HashMap<NodeIndex, NodeIndex>| -> Vec<NodeIndex> {
+            let matched: BTreeSet<NodeIndex> = mapping.values().copied().collect();
+            mapping
+                .iter()
+                .filter(|(_, image)| {
+                    molecule
+                        .get_neighbors_for_atom(**image)
+                        .iter()
+                        .any(|n| !matched.contains(n))
+                })
+                .map(|(atom, _)| *atom)
+                .collect()
+        };
+        let mapping = matches
+            .iter()
+            .find(|m| substituted(m).iter().all(|a| core_labels.contains(a)))
+            .or_else(|| {
+                matches
+                    .iter()
+                    .find(|m| substituted(m).iter().all(|a| labels.contains_key(a)))
+            })
+            .or_else(|| matches.first().filter(|_| !options.only_at_labels));
+        let Some(mapping) = mapping else {
+            decomposition.unmatched.push(record);
+            continue;
+        };
+        let mut atoms: Vec<NodeIndex> = substituted(mapping);
+        atoms.sort();
+        for atom in atoms {
+            labels.entry(atom).or_insert_with(|| {
+                let label = decomposition.labels.last().map_or(1, |l| l + 1);
+                decomposition.labels.insert(label);
+                label
+            });
+        }
+        decomposition
+            .rows
+            .push(decompose(molecule, mapping, &labels, record));
+    }
+
+    // labels given later are hydrogen in the earlier rows
+    for row in &mut decomposition.rows {
+        for label in &decomposition.labels {
+            row.rgroups
+                .entry(*label)
+                .or_insert_with(|| parse_molecule(&format!("[{}*][H]", label)).unwrap().1);
+        }
+    }
+    decomposition
+}
+
+/// Cuts the bonds between the matched core atoms and the other atoms of the molecule.
+fn decompose(
+    molecule: &Molecule,
+    mapping: &HashMap<NodeIndex, NodeIndex>,
+    labels: &HashMap<NodeIndex, u16>,
+    record: usize,
+) -> RGroupRow {
+    let core_atoms: BTreeSet<NodeIndex> = mapping.values().copied().collect();
+    let mut cuts: Vec<(EdgeIndex, [u16; 2])> = Vec::new();
+    for (atom, image) in mapping {
+        for edge in molecule.get_bonds_for_atom(*image) {
+            let (a, b) = molecule.graph.edge_endpoints(edge).unwrap();
+            if !core_atoms.contains(&a) || !core_atoms.contains(&b) {
+                cuts.push((edge, [labels[atom]; 2]));
+            }
+        }
+    }
+    let fragmented = molecule.fragment_on_bonds(&cuts);
+
+    let mut core = BTreeSet::new();
+    let mut groups: BTreeMap<u16, BTreeSet<NodeIndex>> = BTreeMap::new();
+    for component in fragmented.connected_components() {
+        if component.iter().any(|a| core_atoms.contains(a)) {
+            core.extend(component);
+            continue;
+        }
+        // a substituent bonded to several core atoms goes with the lowest label
+        let label = component
+            .iter()
+            .map(|a| &fragmented.graph[*a])
+            .filter(|a| a.is_wildcard() && a.isotope > 0)
+            .map(|a| a.isotope)
+            .min();
+        if let Some(label) = label {
+            groups.entry(label).or_default().extend(component);
+        }
+    }
+    RGroupRow {
+        record,
+        core: fragmented.submolecule(&core).0,
+        rgroups: groups
+            .into_iter()
+            .map(|(label, atoms)| (label, fragmented.submolecule(&atoms).0))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn molecules(smiles: &[&str]) -> Vec<Molecule> {
+        smiles
+            .iter()
+            .map(|s| parse_molecule(s).unwrap().1)
+            .collect()
+    }
+
+    fn canonical(smiles: &str) -> String {
+        parse_molecule(smiles).unwrap().1.canonical_smiles()
+    }
+
+    #[test]
+    fn rgroup_decomposition() {
+        let core = parse_molecule("[1*]c1ccc([2*])cc1").unwrap().1;
+        let decomposition = super::rgroup_decomposition(
+            &core,
+            &molecules(&["Cc1ccc(O)cc1", "Clc1ccccc1", "CCCC", "OC(=O)c1ccc(CN)cc1"]),
+            &RGroupOptions::default(),
+        );
+        assert_eq!(decomposition.unmatched, vec![2]);
+        assert_eq!(decomposition.labels, BTreeSet::from([1, 2]));
+        let rows: Vec<(usize, String, String)> = decomposition
+            .rows
+            .iter()
+            .map(|r| {
+                (
+                    r.record,
+                    r.rgroups[&1].canonical_smiles(),
+                    r.rgroups[&2].canonical_smiles(),
+                )
+            })
+            .collect();
+        // the first match is used for the symmetric core
+        assert_eq!(
+            rows,
+            vec![
+                (0, canonical("[1*]C"), canonical("[2*]O")),
+                (1, canonical("[1*]Cl"), canonical("[2*][H]")),
+                (3, canonical("[1*]C(=O)O"), canonical("[2*]CN")),
+            ]
+        );
+        assert_eq!(
+            decomposition.rows[0].core.canonical_smiles(),
+            canonical("[1*]c1ccc([2*])cc1")
+        );
+
+        let mut out = Vec::new();
+        decomposition.write_table(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().next().unwrap(), "record\tcore\tR1\tR2");
+        assert_eq!(out.lines().count(), 4);
+
+        // substituents are found at the labels whatever the atom order
+        let decomposition = super::rgroup_decomposition(
+            &core,
+            &molecules(&["c1cc(C)ccc1O", "c1c(O)ccc(C)c1", "Oc1ccc(C)cc1"]),
+            &RGroupOptions::default(),
+        );
+        assert_eq!(decomposition.labels, BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn rgroup_map_labels() {
+        let core = parse_molecule("[*:1]c1ccccc1").unwrap().1;
+        let decomposition = super::rgroup_decomposition(
+            &core,
+            &molecules(&["Cc1ccccc1"]),
+            &RGroupOptions::default(),
+        );
+        assert_eq!(decomposition.labels, BTreeSet::from([1]));
+        let row = &decomposition.rows[0];
+        assert_eq!(row.core.canonical_smiles(), canonical("[1*]c1ccccc1"));
+        assert_eq!(row.rgroups[&1].canonical_smiles(), canonical("[1*]C"));
+    }
+
+    #[test]
+    fn rgroup_core_labels_first() {
+        // the hydroxyl of phenol goes to the core label, not the one given for o-cresol
+        let core = parse_molecule("[*:1]c1ccccc1").unwrap().1;
+        let decomposition = super::rgroup_decomposition(
+            &core,
+            &molecules(&["Cc1ccccc1O", "c1ccccc1O"]),
+            &RGroupOptions::default(),
+        );
+        assert_eq!(decomposition.labels, BTreeSet::from([1, 2]));
+        let row = &decomposition.rows[1];
+        assert_eq!(row.rgroups[&1].canonical_smiles(), canonical("[1*]O"));
+        assert_eq!(row.rgroups[&2].canonical_smiles(), canonical("[2*][H]"));
+
+        // map numbers above 255 are labels too
+        let core = parse_molecule("[*:300]c1ccccc1").unwrap().1;
+        let decomposition = super::rgroup_decomposition(
+            &core,
+            &molecules(&["Cc1ccccc1"]),
+            &RGroupOptions::default(),
+        );
+        assert_eq!(decomposition.labels, BTreeSet::from([300]));
+    }
+
+    #[test]
+    fn rgroup_new_labels() {
+        let core = parse_molecule("[1*]C(=O)N").unwrap().1;
+        let molecules = molecules(&["CC(=O)N", "CC(=O)NC", "CC(=O)N(C)C.Cl"]);
+        let decomposition =
+            super::rgroup_decomposition(&core, &molecules, &RGroupOptions::default());
+        assert!(decomposition.unmatched.is_empty());
+        assert_eq!(decomposition.labels, BTreeSet::from([1, 2]));
+        assert_eq!(
+            decomposition.rows[0].rgroups[&2].canonical_smiles(),
+            canonical("[2*][H]")
+        );
+        assert_eq!(
+            decomposition.rows[1].rgroups[&2].canonical_smiles(),
+            canonical("[2*]C")
+        );
+        // both methyls are one R group, the counterion is left out
+        assert_eq!(
+            decomposition.rows[2].rgroups[&2].canonical_smiles(),
+            canonical("[2*]C.[2*]C")
+        );
+
+        let options = RGroupOptions {
+            only_at_labels: true,
+        };
+        let decomposition = super::rgroup_decomposition(&core, &molecules, &options);
+        assert_eq!(decomposition.unmatched, vec![1, 2]);
+    }
+}
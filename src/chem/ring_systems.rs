@@ -0,0 +1,196 @@
+use crate::chem::rings::Ring;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::BTreeSet;
+
+/// SSSR rings connected through shared atoms, whether fused (sharing a bond), spiro (sharing
+/// a single atom) or bridged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingSystem {
+    pub rings: Vec<Ring>,
+    pub atoms: BTreeSet<NodeIndex>,
+    pub bonds: BTreeSet<EdgeIndex>,
+}
+
+impl RingSystem {
+    pub fn len(&self) -> usize {
+        self.atoms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.atoms.is_empty()
+    }
+
+    pub fn ring_count(&self) -> usize {
+        self.rings.len()
+    }
+
+    pub fn contains_atom(&self, atom: NodeIndex) -> bool {
+        self.atoms.contains(&atom)
+    }
+}
+
+impl Molecule {
+    /// Groups the SSSR rings into ring systems, in the order of their first ring.
+    pub fn ring_systems(&self) -> Vec<RingSystem> {
+        let mut systems: Vec<RingSystem> = Vec::new();
+        for ring in &self.ring_info().rings {
+            let mut system = RingSystem {
+                rings: vec![ring.clone()],
+                atoms: ring.atoms.iter().copied().collect(),
+                bonds: ring.bonds.iter().copied().collect(),
+            };
+            // merge all systems sharing an atom with the ring, keeping the position of the first
+            let mut position = None;
+            let mut i = 0;
+            while i < systems.len() {
+                if systems[i].atoms.is_disjoint(&system.atoms) {
+                    i += 1;
+                    continue;
+                }
+                let other = systems.remove(i);
+                position.get_or_insert(i);
+                let mut rings = other.rings;
+                rings.append(&mut system.rings);
+                system.rings = rings;
+                system.atoms.extend(other.atoms);
+                system.bonds.extend(other.bonds);
+            }
+            systems.insert(position.unwrap_or(systems.len()), system);
+        }
+        systems
+    }
+
+    /// Copies the atoms and bonds of the ring system into a new molecule. Substituents are
+    /// dropped, atoms without a fixed hydrogen count get implicit hydrogens in their place.
+    pub fn ring_system_molecule(&self, system: &RingSystem) -> Molecule {
+        self.submolecule(&system.atoms).0
+    }
+
+    /// Atoms shared by two rings that have no bond in common.
+    pub fn spiro_atoms(&self) -> BTreeSet<NodeIndex> {
+        let mut atoms = BTreeSet::new();
+        for (a, b) in ring_pairs(&self.ring_info().rings) {
+            let shared = shared_atoms(a, b);
+            if shared.len() == 1 {
+                atoms.extend(shared);
+            }
+        }
+        atoms
+    }
+
+    /// Atoms at the ends of a path of two or more bonds shared by two rings.
+    pub fn bridgehead_atoms(&self) -> BTreeSet<NodeIndex> {
+        let mut atoms = BTreeSet::new();
+        for (a, b) in ring_pairs(&self.ring_info().rings) {
+            let shared_bonds: Vec<EdgeIndex> = a
+                .bonds
+                .iter()
+                .copied()
+                .filter(|e| b.contains_bond(*e))
+                .collect();
+            if shared_bonds.len() < 2 {
+                continue;
+            }
+            for atom in shared_atoms(a, b) {
+                let bonds = shared_bonds
+                    .iter()
+                    .filter(|e| {
+                        let (x, y) = self.graph.edge_endpoints(**e).unwrap();
+                        x == atom || y == atom
+                    })
+                    .count();
+                if bonds == 1 {
+                    atoms.insert(atom);
+                }
+            }
+        }
+        atoms
+    }
+
+    /// Number of rings sharing at least one bond with another ring.
+    pub fn fused_ring_count(&self) -> usize {
+        let rings = &self.ring_info().rings;
+        rings
+            .iter()
+            .enumerate()
+            .filter(|(i, ring)| {
+                rings
+                    .iter()
+                    .enumerate()
+                    .any(|(j, other)| *i != j && ring.bonds.iter().any(|e| other.contains_bond(*e)))
+            })
+            .count()
+    }
+}
+
+fn ring_pairs(rings: &[Ring]) -> impl Iterator<Item = (&Ring, &Ring)> {
+    rings
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, a)| rings[i + 1..].iter().map(move |b| (a, b)))
+}
+
+fn shared_atoms(a: &Ring, b: &Ring) -> Vec<NodeIndex> {
+    a.atoms
+        .iter()
+        .copied()
+        .filter(|n| b.contains_atom(*n))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::molecule::Molecule;
+
+    fn molecule(smiles: &str) -> Molecule {
+        parse_molecule(smiles).unwrap().1
+    }
+
+    #[test]
+    fn ring_systems() {
+        // cyclopropane linked to naphthalene
+        let m = molecule("C1CC1CC1=CC2=CC=CC=C2C=C1");
+        let systems = m.ring_systems();
+        assert_eq!(systems.len(), 2);
+        assert_eq!((systems[0].len(), systems[0].ring_count()), (3, 1));
+        assert_eq!((systems[1].len(), systems[1].ring_count()), (10, 2));
+        assert_eq!(systems[1].bonds.len(), 11);
+        assert!(molecule("CCO").ring_systems().is_empty());
+    }
+
+    #[test]
+    fn ring_systems_merged() {
+        // the rings are connected through the middle one
+        let m = molecule("C1CC2CC3CCCC3CC2C1");
+        let systems = m.ring_systems();
+        assert_eq!(systems.len(), 1);
+        assert_eq!(systems[0].ring_count(), 3);
+    }
+
+    #[test]
+    fn ring_system_molecule() {
+        let m = molecule("CC(=O)C1=CC=C(O)C=C1C1CCCCC1");
+        let systems = m.ring_systems();
+        assert_eq!(systems.len(), 2);
+        let benzene = m.ring_system_molecule(&systems[0]);
+        assert_eq!(benzene.formula().to_string(), "C6H6");
+    }
+
+    #[test]
+    fn spiro_and_bridgehead_atoms() {
+        let spiro = molecule("C1CC2(C1)CCCC2");
+        assert_eq!(spiro.spiro_atoms().len(), 1);
+        assert!(spiro.bridgehead_atoms().is_empty());
+        assert_eq!(spiro.fused_ring_count(), 0);
+
+        let norbornane = molecule("C1CC2CC1C2");
+        assert_eq!(norbornane.bridgehead_atoms().len(), 2);
+        assert!(norbornane.spiro_atoms().is_empty());
+
+        let naphthalene = molecule("C1=CC2=CC=CC=C2C=C1");
+        assert!(naphthalene.bridgehead_atoms().is_empty());
+        assert_eq!(naphthalene.fused_ring_count(), 2);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::model::molecule::Molecule;
+use fixedbitset::FixedBitSet;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// A simple cycle. `bonds[i]` connects `atoms[i]` and `atoms[(i + 1) % len]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ring {
+    pub atoms: Vec<NodeIndex>,
+    pub bonds: Vec<EdgeIndex>,
+}
+
+impl Ring {
+    pub fn len(&self) -> usize {
+        self.atoms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.atoms.is_empty()
+    }
+
+    pub fn contains_atom(&self, atom: NodeIndex) -> bool {
+        self.atoms.contains(&atom)
+    }
+
+    pub fn contains_bond(&self, bond: EdgeIndex) -> bool {
+        self.bonds.contains(&bond)
+    }
+}
+
+/// Smallest set of smallest rings (SSSR), sorted by ring size.
+#[derive(Debug, Clone, Default)]
+pub struct RingInfo {
+    pub rings: Vec<Ring>,
+}
+
+impl RingInfo {
+    pub fn count(&self) -> usize {
+        self.rings.len()
+    }
+
+    pub fn is_atom_in_ring(&self, atom: NodeIndex) -> bool {
+        self.rings.iter().any(|r| r.contains_atom(atom))
+    }
+
+    pub fn is_bond_in_ring(&self, bond: EdgeIndex) -> bool {
+        self.rings.iter().any(|r| r.contains_bond(bond))
+    }
+
+    pub fn atom_ring_count(&self, atom: NodeIndex) -> usize {
+        self.rings.iter().filter(|r| r.contains_atom(atom)).count()
+    }
+
+    pub fn bond_ring_count(&self, bond: EdgeIndex) -> usize {
+        self.rings.iter().filter(|r| r.contains_bond(bond)).count()
+    }
+
+    pub fn smallest_ring_size(&self, atom: NodeIndex) -> Option<usize> {
+        // rings are sorted by size
+        self.rings
+            .iter()
+            .find(|r| r.contains_atom(atom))
+            .map(Ring::len)
+    }
+}
+
+impl Molecule {
+    /// Returns ring information. It is computed on first use and cached until the
+    /// molecule topology is changed through `Molecule` methods.
+    pub fn ring_info(&self) -> &RingInfo {
+        self.ring_info.get_or_init(|| perceive_rings(self))
+    }
+}
+
+/// Computes a minimum cycle basis: Horton candidate cycles are sorted by size and
+/// the linearly independent ones (over GF(2) on bond sets) are taken.
+fn perceive_rings(molecule: &Molecule) -> RingInfo {
+    let graph = &molecule.graph;
+    let edges: Vec<EdgeIndex> = graph.edge_indices().collect();
+    let edge_bits: HashMap<EdgeIndex, usize> =
+        edges.iter().enumerate().map(|(i, e)| (*e, i)).collect();
+
+    let expected = edges.len() + count_components(molecule) - graph.node_count();
+    if expected == 0 {
+        return RingInfo::default();
+    }
+
+    let mut candidates: Vec<(FixedBitSet, Ring)> = Vec::new();
+    let mut seen: HashSet<FixedBitSet> = HashSet::new();
+    for root in graph.node_indices() {
+        let parents = bfs_parents(molecule, root);
+        for edge in graph.edge_references() {
+            let (x, y) = (edge.source(), edge.target());
+            if !parents.contains_key(&x) || !parents.contains_key(&y) {
+                continue;
+            }
+            if parents[&x] == Some((y, edge.id())) || parents[&y] == Some((x, edge.id())) {
+                continue;
+            }
+            let path_x = path_to_root(&parents, x);
+            let path_y = path_to_root(&parents, y);
+            let atoms_x: HashSet<NodeIndex> = path_x.iter().map(|(n, _)| *n).collect();
+            if path_y[..path_y.len() - 1]
+                .iter()
+                .any(|(n, _)| atoms_x.contains(n))
+            {
+                continue;
+            }
+
+            // root .. x, then y .. (child of root)
+            let mut atoms: Vec<NodeIndex> = path_x.iter().rev().map(|(n, _)| *n).collect();
+            let mut bonds: Vec<EdgeIndex> = path_x[..path_x.len() - 1]
+                .iter()
+                .rev()
+                .map(|(_, e)| e.unwrap())
+                .collect();
+            bonds.push(edge.id());
+            for (n, e) in &path_y[..path_y.len() - 1] {
+                atoms.push(*n);
+                bonds.push(e.unwrap());
+            }
+
+            let mut bits = FixedBitSet::with_capacity(edges.len());
+            for bond in &bonds {
+                bits.insert(edge_bits[bond]);
+            }
+            if seen.insert(bits.clone()) {
+                candidates.push((bits, Ring { atoms, bonds }));
+            }
+        }
+    }
+    candidates.sort_by_key(|(_, ring)| ring.len());
+
+    let mut basis: Vec<(usize, FixedBitSet)> = Vec::new();
+    let mut rings: Vec<Ring> = Vec::new();
+    for (bits, ring) in candidates {
+        let mut reduced = bits;
+        for (pivot, row) in &basis {
+            if reduced.contains(*pivot) {
+                reduced.symmetric_difference_with(row);
+            }
+        }
+        if let Some(pivot) = reduced.ones().next() {
+            for (_, row) in basis.iter_mut() {
+                if row.contains(pivot) {
+                    row.symmetric_difference_with(&reduced);
+                }
+            }
+            basis.push((pivot, reduced));
+            rings.push(ring);
+            if rings.len() == expected {
+                break;
+            }
+        }
+    }
+
+    RingInfo { rings }
+}
+
+type Parents = HashMap<NodeIndex, Option<(NodeIndex, EdgeIndex)>>;
+
+fn bfs_parents(molecule: &Molecule, root: NodeIndex) -> Parents {
+    let mut parents: Parents = HashMap::new();
+    parents.insert(root, None);
+    let mut queue = VecDeque::from([root]);
+    while let Some(node) = queue.pop_front() {
+        for edge in molecule.graph.edges(node) {
+            let next = if edge.source() == node {
+                edge.target()
+            } else {
+                edge.source()
+            };
+            if let std::collections::hash_map::Entry::Vacant(e) = parents.entry(next) {
+                e.insert(Some((node, edge.id())));
+                queue.push_back(next);
+            }
+        }
+    }
+    parents
+}
+
+/// Returns nodes from `node` up to the root with the bond leading to the parent.
+fn path_to_root(parents: &Parents, node: NodeIndex) -> Vec<(NodeIndex, Option<EdgeIndex>)> {
+    let mut path = Vec::new();
+    let mut current = node;
+    loop {
+        match parents[&current] {
+            Some((parent, edge)) => {
+                path.push((current, Some(edge)));
+                current = parent;
+            }
+            None => {
+                path.push((current, None));
+                return path;
+            }
+        }
+    }
+}
+
+impl Molecule {
+    /// Atoms of each connected component, in the order of their first atom.
+    pub fn connected_components(&self) -> Vec<BTreeSet<NodeIndex>> {
+        let mut components: Vec<BTreeSet<NodeIndex>> = Vec::new();
+        for node in self.graph.node_indices() {
+            if components.iter().any(|c| c.contains(&node)) {
+                continue;
+            }
+            components.push(bfs_parents(self, node).into_keys().collect());
+        }
+        components
+    }
+}
+
+pub(crate) fn count_components(molecule: &Molecule) -> usize {
+    let mut visited: HashSet<NodeIndex> = HashSet::new();
+    let mut components = 0;
+    for node in molecule.graph.node_indices() {
+        if visited.contains(&node) {
+            continue;
+        }
+        components += 1;
+        for n in bfs_parents(molecule, node).into_keys() {
+            visited.insert(n);
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use petgraph::stable_graph::NodeIndex;
+
+    fn ring_sizes(smiles: &str) -> Vec<usize> {
+        let m = parse_molecule(smiles).unwrap().1;
+        m.ring_info().rings.iter().map(|r| r.len()).collect()
+    }
+
+    #[test]
+    fn rings_acyclic() {
+        assert!(ring_sizes("CC(C)CO").is_empty());
+    }
+
+    #[test]
+    fn rings_benzene() {
+        assert_eq!(ring_sizes("C1=CC=CC=C1"), vec![6]);
+    }
+
+    #[test]
+    fn rings_naphthalene() {
+        assert_eq!(ring_sizes("C1=CC2=CC=CC=C2C=C1"), vec![6, 6]);
+    }
+
+    #[test]
+    fn rings_spiro() {
+        assert_eq!(ring_sizes("C1CC2(C1)CCCC2"), vec![4, 5]);
+    }
+
+    #[test]
+    fn connected_components() {
+        let m = parse_molecule("CC(=O)[O-].[NH4+].O").unwrap().1;
+        let sizes: Vec<usize> = m.connected_components().iter().map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![4, 1, 1]);
+    }
+
+    #[test]
+    fn rings_bridged() {
+        assert_eq!(ring_sizes("C1CC2CCC1CC2"), vec![6, 6]);
+    }
+
+    #[test]
+    fn ring_order() {
+        let m = parse_molecule("C1CCC1").unwrap().1;
+        let ring = &m.ring_info().rings[0];
+        for i in 0..ring.len() {
+            let a = ring.atoms[i];
+            let b = ring.atoms[(i + 1) % ring.len()];
+            let (edge, _) = m.graph.find_edge_undirected(a, b).unwrap();
+            assert_eq!(edge, ring.bonds[i]);
+        }
+    }
+
+    #[test]
+    fn ring_membership() {
+        let m = parse_molecule("C1CC1CO").unwrap().1;
+        let info = m.ring_info();
+        assert!(info.is_atom_in_ring(NodeIndex::new(0)));
+        assert!(!info.is_atom_in_ring(NodeIndex::new(3)));
+        assert_eq!(info.smallest_ring_size(NodeIndex::new(2)), Some(3));
+        assert_eq!(info.smallest_ring_size(NodeIndex::new(4)), None);
+    }
+
+    #[test]
+    fn ring_info_invalidated_on_edit() {
+        let mut m = parse_molecule("C1CCC1").unwrap().1;
+        assert_eq!(m.ring_info().count(), 1);
+        m.remove_bond_by_atoms(NodeIndex::new(0), NodeIndex::new(3));
+        assert_eq!(m.ring_info().count(), 0);
+    }
+}
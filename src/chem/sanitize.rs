@@ -0,0 +1,113 @@
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanitizeProblem {
+    /// Atom valence (bonds and hydrogens) exceeds the allowed valences of its element.
+    InvalidValence { atom: NodeIndex, valence: u8 },
+    /// Atom is labelled with an isotope missing from the isotope table.
+    UnknownIsotope { atom: NodeIndex, isotope: u16 },
+    /// Bond is marked aromatic but is not part of any ring.
+    AromaticBondOutsideRing { bond: EdgeIndex },
+    /// Bond is marked aromatic but its ring system does not satisfy the 4n+2 rule.
+    NonAromaticRingBond { bond: EdgeIndex },
+}
+
+impl Molecule {
+    /// Validates valences and isotopes, computes implicit hydrogens and perceives rings and aromaticity.
+    /// Returns the list of found problems, empty if the molecule is chemically sound.
+    pub fn sanitize(&self) -> Vec<SanitizeProblem> {
+        let mut problems: Vec<SanitizeProblem> = Vec::new();
+
+        for atom in self.graph.node_indices() {
+            if !self.has_valid_valence(atom) {
+                problems.push(SanitizeProblem::InvalidValence {
+                    atom,
+                    valence: self.explicit_valence(atom) + self.implicit_hydrogens(atom),
+                });
+            }
+            let a = &self.graph[atom];
+            if a.isotope != 0 && a.element.isotope(a.isotope).is_none() {
+                problems.push(SanitizeProblem::UnknownIsotope {
+                    atom,
+                    isotope: a.isotope,
+                });
+            }
+        }
+
+        let ring_info = self.ring_info();
+        let aromaticity = self.aromaticity();
+        for bond in self.graph.edge_indices() {
+            if self.graph[bond].order != BondOrder::Aromatic {
+                continue;
+            }
+            if !ring_info.is_bond_in_ring(bond) {
+                problems.push(SanitizeProblem::AromaticBondOutsideRing { bond });
+            } else if !aromaticity.is_bond_aromatic(bond) {
+                problems.push(SanitizeProblem::NonAromaticRingBond { bond });
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::sanitize::SanitizeProblem;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+
+    fn sanitize(smiles: &str) -> Vec<SanitizeProblem> {
+        parse_molecule(smiles).unwrap().1.sanitize()
+    }
+
+    #[test]
+    fn sanitize_valid() {
+        assert!(sanitize("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O").is_empty());
+        assert!(sanitize("C[N+](C)(C)C").is_empty());
+        assert!(sanitize("C1:C:C:C:C:C:1").is_empty());
+    }
+
+    #[test]
+    fn sanitize_hypervalent_carbon() {
+        assert_eq!(
+            sanitize("[CH7]"),
+            vec![SanitizeProblem::InvalidValence {
+                atom: NodeIndex::new(0),
+                valence: 7
+            }]
+        );
+        assert_eq!(
+            sanitize("CC(C)(C)(C)C"),
+            vec![SanitizeProblem::InvalidValence {
+                atom: NodeIndex::new(1),
+                valence: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn sanitize_isotopes() {
+        assert!(sanitize("[13CH3][2H]").is_empty());
+        assert_eq!(
+            sanitize("C[19C]"),
+            vec![SanitizeProblem::UnknownIsotope {
+                atom: NodeIndex::new(1),
+                isotope: 19
+            }]
+        );
+    }
+
+    #[test]
+    fn sanitize_aromatic_bonds() {
+        assert_eq!(
+            sanitize("C:C"),
+            vec![SanitizeProblem::AromaticBondOutsideRing {
+                bond: EdgeIndex::new(0)
+            }]
+        );
+        assert_eq!(sanitize("C1:C:C:C:1").len(), 4);
+    }
+}
@@ -0,0 +1,418 @@
+use crate::chem::rings::{count_components, Ring};
+use crate::model::atom::{Atom, Chirality};
+use crate::model::bond::{Bond, BondOrder};
+use crate::model::element::Element;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// Largest ring size that is not treated as a macrocycle by the scaffold tree rules.
+const MAX_REGULAR_RING_SIZE: usize = 12;
+
+/// Scaffolds identified by canonical SMILES, linked by ring removal. Built from the scaffold
+/// trees or networks of a set of molecules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScaffoldGraph {
+    /// Number of molecules each scaffold was derived from.
+    pub counts: BTreeMap<String, usize>,
+    /// `(parent, child)` edges; the parent is the child with one ring removed.
+    pub edges: BTreeSet<(String, String)>,
+}
+
+impl ScaffoldGraph {
+    /// Adds the scaffold tree of the molecule (see `Molecule::scaffold_tree`).
+    pub fn add_tree(&mut self, molecule: &Molecule) {
+        let smiles: Vec<String> = molecule
+            .scaffold_tree()
+            .iter()
+            .map(Molecule::canonical_smiles)
+            .collect();
+        for pair in smiles.windows(2) {
+            self.edges.insert((pair[1].clone(), pair[0].clone()));
+        }
+        self.count(smiles.into_iter().collect());
+    }
+
+    /// Adds the scaffold network of the molecule: every scaffold reachable by removing
+    /// terminal rings one at a time, in any order.
+    pub fn add_network(&mut self, molecule: &Molecule) {
+        let mut scaffold = molecule.murcko_scaffold(true);
+        if scaffold.count_atoms() == 0 {
+            return;
+        }
+        scaffold.aromatize();
+
+        let mut seen = BTreeSet::from([scaffold.canonical_smiles()]);
+        let mut pending = vec![scaffold];
+        while let Some(child) = pending.pop() {
+            let child_smiles = child.canonical_smiles();
+            for removal in child.ring_removals() {
+                self.edges
+                    .insert((removal.smiles.clone(), child_smiles.clone()));
+                if seen.insert(removal.smiles) {
+                    pending.push(removal.parent);
+                }
+            }
+        }
+        self.count(seen);
+    }
+
+    fn count(&mut self, scaffolds: BTreeSet<String>) {
+        for smiles in scaffolds {
+            *self.counts.entry(smiles).or_default() += 1;
+        }
+    }
+
+    /// Scaffolds without a parent, i.e. single ring systems that cannot be reduced further.
+    pub fn roots(&self) -> Vec<&str> {
+        let children: BTreeSet<&str> = self.edges.iter().map(|(_, c)| c.as_str()).collect();
+        self.counts
+            .keys()
+            .map(String::as_str)
+            .filter(|s| !children.contains(s))
+            .collect()
+    }
+
+    /// Writes the edge list as tab separated `parent`, `child`, `parent count` and
+    /// `child count` lines. Root scaffolds get a line with an empty parent and a count of 0.
+    pub fn write_edges<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        for root in self.roots() {
+            writeln!(writer, "\t{}\t0\t{}", root, self.counts[root])?;
+        }
+        for (parent, child) in &self.edges {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}",
+                parent, child, self.counts[parent], self.counts[child]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A parent scaffold obtained by removing one ring.
+struct RingRemoval {
+    parent: Molecule,
+    smiles: String,
+    ring_size: usize,
+    heteroatoms: usize,
+    aromatic: bool,
+}
+
+impl RingRemoval {
+    /// Sort key of the scaffold tree prioritisation rules, the ring with the smallest key is
+    /// removed first:
+    /// 1. three-membered heterocycles (epoxides, aziridines) are removed first,
+    /// 2. macrocycles are retained,
+    /// 3. aromatic rings are retained,
+    /// 4. rings with fewer heteroatoms are removed first,
+    /// 5. smaller rings are removed first,
+    /// 6. ties are broken by the canonical SMILES of the parent.
+    fn priority(&self) -> (bool, bool, bool, usize, usize, &str) {
+        (
+            !(self.ring_size == 3 && self.heteroatoms > 0),
+            self.ring_size > MAX_REGULAR_RING_SIZE,
+            self.aromatic,
+            self.heteroatoms,
+            self.ring_size,
+            &self.smiles,
+        )
+    }
+}
+
+impl Molecule {
+    /// Bemis–Murcko scaffold: ring atoms and the linker atoms on paths between rings, with
+    /// all side chains removed. Atoms bonded to the scaffold only by a double bond (e.g.
+    /// carbonyl oxygens) are kept if `keep_exocyclic_double_bonds` is set.
+    /// Returns an empty molecule for acyclic molecules.
+    pub fn murcko_scaffold(&self, keep_exocyclic_double_bonds: bool) -> Molecule {
+        let ring_info = self.ring_info();
+        let mut atoms: BTreeSet<NodeIndex> = self.graph.node_indices().collect();
+
+        // strip side chains from their ends until only rings and linkers are left
+        loop {
+            let terminal: Vec<NodeIndex> = atoms
+                .iter()
+                .copied()
+                .filter(|a| !ring_info.is_atom_in_ring(*a))
+                .filter(|a| {
+                    self.graph
+                        .neighbors(*a)
+                        .filter(|n| atoms.contains(n))
+                        .count()
+                        <= 1
+                })
+                .collect();
+            if terminal.is_empty() {
+                break;
+            }
+            for atom in terminal {
+                atoms.remove(&atom);
+            }
+        }
+
+        if keep_exocyclic_double_bonds {
+            let exocyclic: Vec<NodeIndex> = self
+                .graph
+                .node_indices()
+                .filter(|a| !atoms.contains(a) && self.graph.neighbors(*a).count() == 1)
+                .filter(|a| {
+                    self.graph.edges(*a).any(|e| {
+                        e.weight().order == BondOrder::Double
+                            && atoms.contains(&self.graph.neighbors(*a).next().unwrap())
+                    })
+                })
+                .collect();
+            atoms.extend(exocyclic);
+        }
+
+        self.submolecule(&atoms).0
+    }
+
+    /// Scaffold tree (Schuffenhauer et al., 2007) of the molecule: the Murcko scaffold with
+    /// exocyclic double bonds, followed by its parents down to a single ring. Each parent has
+    /// one terminal ring of its child removed, chosen by the prioritisation rules of
+    /// `RingRemoval::priority`. Scaffolds are in aromatic form. Returns an empty vector for
+    /// acyclic molecules.
+    pub fn scaffold_tree(&self) -> Vec<Molecule> {
+        let mut scaffold = self.murcko_scaffold(true);
+        if scaffold.count_atoms() == 0 {
+            return Vec::new();
+        }
+        scaffold.aromatize();
+
+        let mut tree = Vec::new();
+        loop {
+            let parent = scaffold
+                .ring_removals()
+                .into_iter()
+                .min_by(|a, b| a.priority().cmp(&b.priority()))
+                .map(|removal| removal.parent);
+            tree.push(scaffold);
+            match parent {
+                Some(parent) => scaffold = parent,
+                None => return tree,
+            }
+        }
+    }
+
+    /// All parents of a scaffold with one terminal ring removed. A ring is removed with its
+    /// atoms that are not shared with other rings, followed by the linkers left dangling.
+    /// Rings without such atoms and rings whose removal would split the scaffold are kept.
+    fn ring_removals(&self) -> Vec<RingRemoval> {
+        let ring_info = self.ring_info();
+        if ring_info.count() <= 1 {
+            return Vec::new();
+        }
+
+        let mut removals = Vec::new();
+        for ring in &ring_info.rings {
+            let own_atoms: BTreeSet<NodeIndex> = ring
+                .atoms
+                .iter()
+                .copied()
+                .filter(|a| ring_info.atom_ring_count(*a) == 1)
+                .collect();
+            if own_atoms.is_empty() {
+                continue;
+            }
+
+            let remaining: BTreeSet<NodeIndex> = self
+                .graph
+                .node_indices()
+                .filter(|a| !own_atoms.contains(a))
+                .collect();
+            let parent = self.submolecule(&remaining).0.murcko_scaffold(true);
+            if count_components(&parent) != 1 || parent.ring_info().count() != ring_info.count() - 1
+            {
+                continue;
+            }
+
+            removals.push(RingRemoval {
+                smiles: parent.canonical_smiles(),
+                parent,
+                ring_size: ring.len(),
+                heteroatoms: ring
+                    .atoms
+                    .iter()
+                    .filter(|a| self.graph[**a].element.atomic_number != 6)
+                    .count(),
+                aromatic: self.is_aromatic_ring(ring),
+            });
+        }
+        removals
+    }
+
+    fn is_aromatic_ring(&self, ring: &Ring) -> bool {
+        ring.bonds
+            .iter()
+            .all(|b| self.graph[*b].order == BondOrder::Aromatic)
+    }
+
+    /// Generic framework: the Murcko scaffold with all atoms turned into uncharged carbons and
+    /// all bonds into single bonds.
+    pub fn generic_framework(&self) -> Molecule {
+        let mut framework = self.murcko_scaffold(false);
+        for atom in framework.graph.node_weights_mut() {
+            *atom = Atom {
+                element: Element { atomic_number: 6 },
+                isotope: 0,
+                charge: 0,
+                hs: 0,
+                no_implicit_hs: false,
+                aromatic: false,
+                coords: atom.coords,
+                chirality: Chirality::None,
+                map: 0,
+            };
+        }
+        for bond in framework.graph.edge_weights_mut() {
+            *bond = Bond::new(BondOrder::Single);
+        }
+        framework
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::scaffold::ScaffoldGraph;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::bond::BondOrder;
+
+    fn scaffold(smiles: &str, keep_exocyclic_double_bonds: bool) -> (usize, usize, String) {
+        let s = parse_molecule(smiles)
+            .unwrap()
+            .1
+            .murcko_scaffold(keep_exocyclic_double_bonds);
+        (s.count_atoms(), s.count_bonds(), s.formula().to_string())
+    }
+
+    #[test]
+    fn test_side_chains_removed() {
+        assert_eq!(
+            scaffold("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O", false),
+            (6, 6, "C6H6".to_owned())
+        );
+        assert_eq!(
+            scaffold("CC(C1=CC2=C(C=C1)C=C(C=C2)OC)C(=O)O", true),
+            (10, 11, "C10H8".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_linkers() {
+        assert_eq!(
+            scaffold("CC1=CC=CC=C1CCC1=CC=CC=C1CC", false),
+            (14, 15, "C14H14".to_owned())
+        );
+        assert_eq!(
+            scaffold("C1=CC=CC=C1C(=O)NC1CC1", false),
+            (11, 12, "C10H13N".to_owned())
+        );
+        assert_eq!(
+            scaffold("C1=CC=CC=C1C(=O)NC1CC1", true),
+            (12, 13, "C10H11NO".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_exocyclic_double_bonds() {
+        assert_eq!(
+            scaffold("O=C1CCCCC1CC=O", false),
+            (6, 6, "C6H12".to_owned())
+        );
+        assert_eq!(
+            scaffold("O=C1CCCCC1CC=O", true),
+            (7, 7, "C6H10O".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_acyclic() {
+        assert_eq!(scaffold("CCCC(=O)O", true), (0, 0, "".to_owned()));
+    }
+
+    fn tree(smiles: &str) -> Vec<String> {
+        parse_molecule(smiles)
+            .unwrap()
+            .1
+            .scaffold_tree()
+            .iter()
+            .map(|s| s.canonical_smiles())
+            .collect()
+    }
+
+    #[test]
+    fn test_scaffold_tree() {
+        assert_eq!(
+            tree("CC(C1=CC2=C(C=C1)C=C(C=C2)OC)C(=O)O"),
+            vec!["c1ccc2ccccc2c1", "c1ccccc1"]
+        );
+        assert_eq!(
+            tree("C1=CC=C2C(=C1)C=CN2"),
+            vec!["c1ccc2[nH]ccc2c1", "c1cc[nH]c1"]
+        );
+        assert!(tree("CCCC(=O)O").is_empty());
+    }
+
+    #[test]
+    fn test_scaffold_tree_rules() {
+        // non-aromatic rings are removed before aromatic ones
+        assert_eq!(tree("C1=CC=NC=C1C(=O)NC1CC1")[1], "c1ccncc1");
+        // three-membered heterocycles go first
+        assert_eq!(tree("C1CCCCC1C1OC1")[1], "C1CCCCC1");
+        // then rings with fewer heteroatoms
+        assert_eq!(tree("C1CCCCC1C1CCNCC1")[1], "C1CCNCC1");
+        // fused atoms shared with the kept ring stay
+        assert_eq!(tree("O=C1NC2=CC=CC=C2C1")[1], "c1ccccc1");
+    }
+
+    #[test]
+    fn test_bridged_ring() {
+        assert_eq!(tree("C1CC2CCC1CC2"), vec!["C1CC2CCC1CC2", "C1CCCCC1"]);
+    }
+
+    #[test]
+    fn test_scaffold_network() {
+        let m = parse_molecule("C1CCCCC1C1=CC=C(C=C1)C1=CC=CC=C1")
+            .unwrap()
+            .1;
+        let mut network = ScaffoldGraph::default();
+        network.add_network(&m);
+        network.add_network(&parse_molecule("CC1=CC=CC=C1").unwrap().1);
+        assert_eq!(network.counts.len(), 5);
+        assert_eq!(network.counts["c1ccccc1"], 2);
+        assert_eq!(network.counts["C1CCCCC1"], 1);
+        assert_eq!(network.edges.len(), 5);
+        assert!(network.edges.contains(&(
+            "c1ccc(cc1)-c1ccccc1".to_owned(),
+            "c1ccc(cc1)-c1ccc(cc1)C1CCCCC1".to_owned()
+        )));
+        assert_eq!(network.roots(), vec!["C1CCCCC1", "c1ccccc1"]);
+
+        let mut tree = ScaffoldGraph::default();
+        tree.add_tree(&m);
+        assert_eq!(tree.counts.len(), 3);
+        assert_eq!(tree.edges.len(), 2);
+        assert_eq!(tree.roots(), vec!["c1ccccc1"]);
+
+        let mut out = Vec::new();
+        tree.write_edges(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 3);
+        assert_eq!(out.lines().next().unwrap(), "\tc1ccccc1\t0\t1");
+    }
+
+    #[test]
+    fn test_generic_framework() {
+        let m = parse_molecule("CC1=CC=NC=C1C(=O)NC1CC1").unwrap().1;
+        let framework = m.generic_framework();
+        assert_eq!(framework.count_atoms(), 11);
+        assert_eq!(framework.formula().to_string(), "C11H20");
+        assert!(framework
+            .graph
+            .edge_weights()
+            .all(|b| b.order == BondOrder::Single));
+    }
+}
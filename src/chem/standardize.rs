@@ -0,0 +1,527 @@
+use crate::io::smiles::reader::molecule::parse_molecule;
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use bincode::{Decode, Encode};
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Elements which are not disconnected as metals.
+const NON_METALS: [u8; 22] = [
+    1, 2, 5, 6, 7, 8, 9, 10, 14, 15, 16, 17, 18, 33, 34, 35, 36, 52, 53, 54, 85, 86,
+];
+
+/// Elements whose bonds to metals are broken by metal disconnection.
+const METAL_LIGANDS: [u8; 9] = [7, 8, 9, 15, 16, 17, 34, 35, 53];
+
+/// Counterions and solvents removed by salt stripping. Components are compared after group
+/// normalization and neutralization, so each entry covers its ionized forms too.
+const SALTS: [&str; 39] = [
+    "[Li+]",
+    "[Na+]",
+    "[K+]",
+    "[Rb+]",
+    "[Cs+]",
+    "[Mg+2]",
+    "[Ca+2]",
+    "[Sr+2]",
+    "[Ba+2]",
+    "[Zn+2]",
+    "[Al+3]",
+    "[Fe+2]",
+    "[Fe+3]",
+    "F",
+    "Cl",
+    "Br",
+    "I",
+    "N",
+    "O",
+    "ON(=O)=O",
+    "OS(=O)(=O)O",
+    "OP(=O)(O)O",
+    "OC(=O)O",
+    "CS(=O)(=O)O",
+    "CC1=CC=C(C=C1)S(=O)(=O)O",
+    "C1=CC=C(C=C1)S(=O)(=O)O",
+    "OC(=O)C(F)(F)F",
+    "CC(=O)O",
+    "OC(=O)C=CC(=O)O",
+    "OC(=O)C(=O)O",
+    "OC(=O)CCC(=O)O",
+    "OC(C(O)C(=O)O)C(=O)O",
+    "OC(CC(=O)O)(CC(=O)O)C(=O)O",
+    "CO",
+    "CCO",
+    "CS(C)=O",
+    "CN(C)C=O",
+    "CC#N",
+    "ClCCl",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct StandardizeOptions {
+    /// Break bonds between metals and N, O, P, S, Se and halogens, moving the bond electrons
+    /// to the nonmetal as a negative charge.
+    pub disconnect_metals: bool,
+    /// Rewrite nitro groups, N-oxides, azides and diazo groups to charge-separated form and
+    /// sulfoxides and sulfones to the uncharged one.
+    pub normalize_groups: bool,
+    /// Remove counterion and solvent components, unless nothing else would be left.
+    pub strip_salts: bool,
+    /// Neutralize charged atoms by adding or removing hydrogens, keeping zwitterionic groups
+    /// and the anions needed to balance charges that can not be removed.
+    pub neutralize: bool,
+    pub remove_isotopes: bool,
+}
+
+impl Default for StandardizeOptions {
+    fn default() -> Self {
+        StandardizeOptions {
+            disconnect_metals: true,
+            normalize_groups: true,
+            strip_salts: true,
+            neutralize: true,
+            remove_isotopes: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionalGroup {
+    Nitro,
+    NOxide,
+    Azide,
+    Diazo,
+    /// Sulfoxides and sulfones.
+    Sulfoxide,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandardizeChange {
+    MetalDisconnected {
+        metal: NodeIndex,
+        ligand: NodeIndex,
+    },
+    /// Functional group was rewritten, `atom` is its central atom.
+    GroupNormalized {
+        group: FunctionalGroup,
+        atom: NodeIndex,
+    },
+    /// Component was removed as a counterion or solvent.
+    ComponentRemoved {
+        smiles: String,
+    },
+    /// Atom charge was removed by adding or removing hydrogens.
+    ChargeNeutralized {
+        atom: NodeIndex,
+        charge: i8,
+    },
+    IsotopeRemoved {
+        atom: NodeIndex,
+        isotope: u16,
+    },
+}
+
+impl Molecule {
+    /// Brings the molecule to a standard form, so that the same compound given as a salt, as
+    /// a free base or with a different group representation gets the same structure.
+    /// Steps run in the order of `StandardizeOptions` fields. Returns the changes made,
+    /// atom indices refer to the standardized molecule.
+    pub fn standardize(&mut self, options: &StandardizeOptions) -> Vec<StandardizeChange> {
+        let mut changes = Vec::new();
+        if options.disconnect_metals {
+            self.disconnect_metals(&mut changes);
+        }
+        if options.normalize_groups {
+            self.normalize_groups(&mut changes);
+        }
+        if options.strip_salts {
+            self.strip_salts(&mut changes);
+        }
+        if options.neutralize {
+            self.neutralize(&mut changes);
+        }
+        if options.remove_isotopes {
+            self.remove_isotopes(&mut changes);
+        }
+        changes
+    }
+
+    fn disconnect_metals(&mut self, changes: &mut Vec<StandardizeChange>) {
+        let bonds: Vec<(EdgeIndex, NodeIndex, NodeIndex)> = (&self.graph)
+            .edge_references()
+            .filter_map(|e| {
+                let (a, b) = (e.source(), e.target());
+                if self.is_metal(a) && self.is_metal_ligand(b) {
+                    Some((e.id(), a, b))
+                } else if self.is_metal(b) && self.is_metal_ligand(a) {
+                    Some((e.id(), b, a))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        for (edge, metal, ligand) in bonds {
+            let electrons = match self.graph[edge].order {
+                BondOrder::Double => 2,
+                BondOrder::Triple => 3,
+                _ => 1,
+            };
+            let hydrogens = self.total_hydrogens(ligand);
+            self.remove_bond(edge);
+            self.graph[metal].charge += electrons;
+            self.graph[ligand].charge -= electrons;
+            self.set_total_hydrogens(ligand, hydrogens);
+            changes.push(StandardizeChange::MetalDisconnected { metal, ligand });
+        }
+    }
+
+    fn is_metal(&self, atom: NodeIndex) -> bool {
+        !NON_METALS.contains(&self.graph[atom].element.atomic_number)
+    }
+
+    fn is_metal_ligand(&self, atom: NodeIndex) -> bool {
+        METAL_LIGANDS.contains(&self.graph[atom].element.atomic_number)
+    }
+
+    fn normalize_groups(&mut self, changes: &mut Vec<StandardizeChange>) {
+        let atoms: Vec<NodeIndex> = self.graph.node_indices().collect();
+        for atom in atoms {
+            let group = match self.graph[atom].element.atomic_number {
+                7 => self.normalize_nitrogen(atom),
+                16 => self
+                    .normalize_sulfur(atom)
+                    .then_some(FunctionalGroup::Sulfoxide),
+                _ => None,
+            };
+            if let Some(group) = group {
+                changes.push(StandardizeChange::GroupNormalized { group, atom });
+            }
+        }
+    }
+
+    /// `N(=O)=O`, `N=O` on pentavalent nitrogen, `N=N#N` and `C=N#N` to charge-separated
+    /// form; `[N-][N+]#N` to `N=[N+]=[N-]`.
+    fn normalize_nitrogen(&mut self, atom: NodeIndex) -> Option<FunctionalGroup> {
+        let charge = self.graph[atom].charge;
+        if charge == 0 && self.explicit_valence(atom) == 5 {
+            if let Some((edge, oxygen)) = self.terminal_neighbor(atom, 8, BondOrder::Double, 0) {
+                let nitro = self
+                    .graph
+                    .neighbors(atom)
+                    .any(|n| n != oxygen && self.is_terminal(n, 8));
+                self.separate_charges(edge, atom, oxygen, BondOrder::Single);
+                return Some(if nitro {
+                    FunctionalGroup::Nitro
+                } else {
+                    FunctionalGroup::NOxide
+                });
+            }
+            if let Some((edge, end)) = self.terminal_neighbor(atom, 7, BondOrder::Triple, 0) {
+                let azide = self
+                    .graph
+                    .neighbors(atom)
+                    .any(|n| n != end && self.graph[n].element.atomic_number == 7);
+                self.separate_charges(edge, atom, end, BondOrder::Double);
+                return Some(if azide {
+                    FunctionalGroup::Azide
+                } else {
+                    FunctionalGroup::Diazo
+                });
+            }
+        }
+        if charge == 1 {
+            let (triple, end) = self.terminal_neighbor(atom, 7, BondOrder::Triple, 0)?;
+            let (single, start) = self.graph.edges(atom).find_map(|e| {
+                let n = if e.source() == atom {
+                    e.target()
+                } else {
+                    e.source()
+                };
+                (e.weight().order == BondOrder::Single
+                    && self.graph[n].element.atomic_number == 7
+                    && self.graph[n].charge == -1)
+                    .then_some((e.id(), n))
+            })?;
+            let hydrogens = [start, end].map(|a| self.total_hydrogens(a));
+            self.graph[single].order = BondOrder::Double;
+            self.graph[triple].order = BondOrder::Double;
+            self.graph[start].charge = 0;
+            self.graph[end].charge = -1;
+            self.set_total_hydrogens(start, hydrogens[0]);
+            self.set_total_hydrogens(end, hydrogens[1]);
+            return Some(FunctionalGroup::Azide);
+        }
+        None
+    }
+
+    /// `[S+][O-]` to `S=O`, repeated for sulfones.
+    fn normalize_sulfur(&mut self, atom: NodeIndex) -> bool {
+        let mut changed = false;
+        while self.graph[atom].charge > 0 {
+            let Some((edge, oxygen)) = self.terminal_neighbor(atom, 8, BondOrder::Single, -1)
+            else {
+                break;
+            };
+            let hydrogens = self.total_hydrogens(atom);
+            self.graph[edge].order = BondOrder::Double;
+            self.graph[atom].charge -= 1;
+            self.graph[oxygen].charge = 0;
+            self.set_total_hydrogens(atom, hydrogens);
+            self.set_total_hydrogens(oxygen, 0);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Neighbor of the given element, bonded only to `atom` with the given bond order.
+    fn terminal_neighbor(
+        &self,
+        atom: NodeIndex,
+        atomic_number: u8,
+        order: BondOrder,
+        charge: i8,
+    ) -> Option<(EdgeIndex, NodeIndex)> {
+        self.graph.edges(atom).find_map(|e| {
+            let n = if e.source() == atom {
+                e.target()
+            } else {
+                e.source()
+            };
+            (e.weight().order == order
+                && self.is_terminal(n, atomic_number)
+                && self.graph[n].charge == charge)
+                .then_some((e.id(), n))
+        })
+    }
+
+    fn is_terminal(&self, atom: NodeIndex, atomic_number: u8) -> bool {
+        self.graph[atom].element.atomic_number == atomic_number
+            && self.graph.neighbors(atom).count() == 1
+    }
+
+    /// Lowers the bond order to `order`, moving a positive charge to `positive` and a
+    /// negative one to `negative`. Hydrogen counts are kept.
+    fn separate_charges(
+        &mut self,
+        edge: EdgeIndex,
+        positive: NodeIndex,
+        negative: NodeIndex,
+        order: BondOrder,
+    ) {
+        let hydrogens = [positive, negative].map(|a| self.total_hydrogens(a));
+        self.graph[edge].order = order;
+        self.graph[positive].charge += 1;
+        self.graph[negative].charge -= 1;
+        self.set_total_hydrogens(positive, hydrogens[0]);
+        self.set_total_hydrogens(negative, hydrogens[1]);
+    }
+
+    fn strip_salts(&mut self, changes: &mut Vec<StandardizeChange>) {
+        let components = self.connected_components();
+        if components.len() < 2 {
+            return;
+        }
+        let salts: Vec<(Vec<NodeIndex>, String)> = components
+            .iter()
+            .filter_map(|atoms| {
+                let key = salt_key(self.submolecule(atoms).0);
+                salt_keys()
+                    .contains(&key)
+                    .then(|| (atoms.iter().copied().collect(), key))
+            })
+            .collect();
+        if salts.len() == components.len() {
+            return;
+        }
+        for (atoms, smiles) in salts {
+            for atom in atoms {
+                self.remove_atom(atom);
+            }
+            changes.push(StandardizeChange::ComponentRemoved { smiles });
+        }
+    }
+
+    fn neutralize(&mut self, changes: &mut Vec<StandardizeChange>) {
+        let atoms: Vec<NodeIndex> = self.graph.node_indices().collect();
+        for &atom in &atoms {
+            let charge = self.graph[atom].charge;
+            let hydrogens = self.total_hydrogens(atom);
+            if charge <= 0 || hydrogens == 0 || self.has_charged_neighbor(atom, -1) {
+                continue;
+            }
+            let removed = (charge as u8).min(hydrogens);
+            self.graph[atom].charge -= removed as i8;
+            self.set_total_hydrogens(atom, hydrogens - removed);
+            changes.push(StandardizeChange::ChargeNeutralized { atom, charge });
+        }
+
+        // anions balancing the positive charges left are kept
+        let mut balance: i32 = atoms
+            .iter()
+            .filter(|a| self.graph[**a].charge > 0 && !self.has_charged_neighbor(**a, -1))
+            .map(|a| self.graph[*a].charge as i32)
+            .sum();
+        for &atom in &atoms {
+            let charge = self.graph[atom].charge;
+            if charge >= 0 || self.has_charged_neighbor(atom, 1) {
+                continue;
+            }
+            if balance >= -charge as i32 {
+                balance += charge as i32;
+                continue;
+            }
+            let hydrogens = self.total_hydrogens(atom);
+            self.graph[atom].charge = 0;
+            self.set_total_hydrogens(atom, hydrogens + (-charge) as u8);
+            if !self.has_valid_valence(atom) {
+                self.graph[atom].charge = charge;
+                self.set_total_hydrogens(atom, hydrogens);
+                continue;
+            }
+            changes.push(StandardizeChange::ChargeNeutralized { atom, charge });
+        }
+    }
+
+    /// Returns true if a neighbor has a charge of the given sign.
+    fn has_charged_neighbor(&self, atom: NodeIndex, sign: i8) -> bool {
+        self.graph
+            .neighbors(atom)
+            .any(|n| self.graph[n].charge.signum() == sign)
+    }
+
+    /// Removes isotope labels. Hydrogens that were labelled, like `[2H]`, become plain
+    /// hydrogens and are folded into the hydrogen count of their neighbor.
+    fn remove_isotopes(&mut self, changes: &mut Vec<StandardizeChange>) {
+        let atoms: Vec<NodeIndex> = self.graph.node_indices().collect();
+        let mut hydrogens = false;
+        for atom in atoms {
+            let isotope = self.graph[atom].isotope;
+            if isotope != 0 {
+                self.graph[atom].isotope = 0;
+                hydrogens |= self.graph[atom].element.atomic_number == 1;
+                changes.push(StandardizeChange::IsotopeRemoved { atom, isotope });
+            }
+        }
+        if hydrogens {
+            self.remove_explicit_hydrogens();
+        }
+    }
+}
+
+/// Canonical SMILES of the normalized and neutralized component.
+fn salt_key(mut component: Molecule) -> String {
+    let mut changes = Vec::new();
+    component.normalize_groups(&mut changes);
+    component.neutralize(&mut changes);
+    component.canonical_smiles()
+}
+
+fn salt_keys() -> &'static HashSet<String> {
+    static KEYS: OnceLock<HashSet<String>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        SALTS
+            .iter()
+            .map(|smiles| salt_key(parse_molecule(smiles).unwrap().1))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::standardize::{FunctionalGroup, StandardizeChange, StandardizeOptions};
+    use crate::io::smiles::reader::molecule::parse_molecule;
+
+    fn standardize(smiles: &str) -> (String, Vec<StandardizeChange>) {
+        let mut m = parse_molecule(smiles).unwrap().1;
+        let changes = m.standardize(&StandardizeOptions::default());
+        assert!(m.sanitize().is_empty());
+        (m.canonical_smiles(), changes)
+    }
+
+    #[test]
+    fn salt_forms_agree() {
+        let free = standardize("CC(=O)OC1=CC=CC=C1C(=O)O").0;
+        assert_eq!(standardize("CC(=O)OC1=CC=CC=C1C(=O)[O-].[Na+]").0, free);
+        assert_eq!(standardize("CC(=O)OC1=CC=CC=C1C(=O)O[Na]").0, free);
+        assert_eq!(standardize("CC(=O)OC1=CC=CC=C1C(=O)O.O.O").0, free);
+
+        let base = standardize("CN1CCCC1C1=CN=CC=C1").0;
+        assert_eq!(standardize("C[NH+]1CCCC1C1=CN=CC=C1.[Cl-]").0, base);
+        assert_eq!(standardize("CN1CCCC1C1=CN=CC=C1.OC(=O)C(F)(F)F").0, base);
+    }
+
+    #[test]
+    fn strip_salts_keeps_salt_only_molecules() {
+        // sodium cation balances the chloride, so neither is neutralized
+        let (smiles, changes) = standardize("[Na+].[Cl-]");
+        assert_eq!(smiles, "[Na+].[Cl-]");
+        assert!(changes
+            .iter()
+            .all(|c| !matches!(c, StandardizeChange::ComponentRemoved { .. })));
+    }
+
+    #[test]
+    fn metal_disconnection() {
+        let (smiles, changes) = standardize("CC(=O)O[Zn]OC(C)=O");
+        assert_eq!(smiles, "[Zn+2].CC([O-])=O.CC([O-])=O");
+        assert_eq!(
+            changes
+                .iter()
+                .filter(|c| matches!(c, StandardizeChange::MetalDisconnected { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn group_normalization() {
+        let nitro = standardize("C[N+](=O)[O-]").0;
+        let (smiles, changes) = standardize("CN(=O)=O");
+        assert_eq!(smiles, nitro);
+        assert!(changes.contains(&StandardizeChange::GroupNormalized {
+            group: FunctionalGroup::Nitro,
+            atom: petgraph::stable_graph::NodeIndex::new(1),
+        }));
+
+        assert_eq!(standardize("CN=N#N").0, standardize("CN=[N+]=[N-]").0);
+        assert_eq!(standardize("C[N-][N+]#N").0, standardize("CN=[N+]=[N-]").0);
+        assert_eq!(standardize("C=N#N").0, "C=[N+]=[N-]");
+        assert_eq!(standardize("C[N](C)(C)=O").0, "C[N+](C)(C)[O-]");
+        assert_eq!(standardize("O=N1=CC=CC=C1").0, "[O-][n+]1ccccc1");
+        assert_eq!(standardize("C[S+](C)[O-]").0, "CS(C)=O");
+        assert_eq!(standardize("C[S+2]([O-])([O-])C").0, "CS(C)(=O)=O");
+    }
+
+    #[test]
+    fn neutralization() {
+        assert_eq!(standardize("CC[NH3+]").0, "CCN");
+        assert_eq!(standardize("[NH3+]CCS(=O)(=O)[O-]").0, "NCCS(=O)(=O)O");
+        // quaternary ammonium keeps its carboxylate
+        assert_eq!(
+            standardize("C[N+](C)(C)CC(=O)[O-]").0,
+            "C[N+](C)(C)CC([O-])=O"
+        );
+        // nitro zwitterion is not touched
+        let (_, changes) = standardize("C[N+](=O)[O-]");
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn isotopes_and_options() {
+        let (smiles, changes) = standardize("[13CH3]C([2H])O");
+        assert_eq!(smiles, "CCO");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(standardize("[2H]OC").0, "CO");
+
+        let mut m = parse_molecule("[13CH3]CC(=O)[O-].[Na+]").unwrap().1;
+        let options = StandardizeOptions {
+            strip_salts: false,
+            remove_isotopes: false,
+            ..StandardizeOptions::default()
+        };
+        assert_eq!(m.standardize(&options), vec![]);
+        assert_eq!(m.count_atoms(), 6);
+    }
+}
@@ -0,0 +1,495 @@
+use crate::model::atom::Chirality;
+use crate::model::bond::{BondOrder, DoubleBondStereo};
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Smallest ring in which a double bond can have a stable trans configuration.
+const MIN_STEREO_RING_SIZE: usize = 8;
+/// Elements which may carry a tetrahedral stereocenter: C, N (as ammonium), Si, P, S, Ge,
+/// As, Se, Sn.
+const STEREOCENTER_ELEMENTS: [u8; 9] = [6, 7, 14, 15, 16, 32, 33, 34, 50];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StereoisomerOptions {
+    /// Keep the configuration of stereocenters and double bonds which are already set, and
+    /// enumerate only the unassigned ones.
+    pub only_unassigned: bool,
+    /// Enumeration stops once this many stereoisomers are found.
+    pub max_isomers: usize,
+    /// Skip stereoisomers with the same canonical SMILES as an earlier one, e.g. the second
+    /// form of a meso compound.
+    pub unique: bool,
+}
+
+impl Default for StereoisomerOptions {
+    fn default() -> Self {
+        StereoisomerOptions {
+            only_unassigned: true,
+            max_isomers: 1024,
+            unique: true,
+        }
+    }
+}
+
+impl Molecule {
+    /// Whether any atom or bond has a stereo configuration.
+    pub fn has_stereo(&self) -> bool {
+        self.graph
+            .node_weights()
+            .any(|a| a.chirality != Chirality::None)
+            || self
+                .graph
+                .edge_weights()
+                .any(|b| b.double_bond_stereo != DoubleBondStereo::None)
+    }
+
+    /// Neighbors in the order the chirality of the atom refers to: the implicit hydrogen
+    /// (`None`) if the atom has exactly one, then bonded atoms by increasing index.
+    pub fn stereo_neighbors(&self, atom: NodeIndex) -> Vec<Option<NodeIndex>> {
+        let mut neighbors = Vec::new();
+        if self.graph[atom].hs + self.implicit_hydrogens(atom) == 1 {
+            neighbors.push(None);
+        }
+        neighbors.extend(self.get_neighbors_for_atom(atom).into_iter().map(Some));
+        neighbors
+    }
+
+    /// Neighbors of `atom` other than its double bond `partner`, by increasing index. The
+    /// configuration of the double bond refers to the first of them on each side.
+    pub fn double_bond_neighbors(&self, atom: NodeIndex, partner: NodeIndex) -> Vec<NodeIndex> {
+        self.get_neighbors_for_atom(atom)
+            .into_iter()
+            .filter(|n| *n != partner)
+            .collect()
+    }
+
+    /// Atoms which are tetrahedral stereocenters by constitution: four different neighbors,
+    /// counting an implicit hydrogen, over single bonds. Ring atoms with two equivalent ring
+    /// neighbors also count if another such atom is in the same ring, as in
+    /// 1,4-dimethylcyclohexane.
+    pub fn potential_stereocenters(&self) -> Vec<NodeIndex> {
+        let classes = self.symmetry_classes();
+        let ring_info = self.ring_info();
+        let mut centers = Vec::new();
+        let mut ring_candidates = Vec::new();
+        for atom in self.graph.node_indices() {
+            let a = &self.graph[atom];
+            if !STEREOCENTER_ELEMENTS.contains(&a.element.atomic_number)
+                || self
+                    .graph
+                    .edges(atom)
+                    .any(|e| e.weight().order != BondOrder::Single)
+            {
+                continue;
+            }
+            let neighbors = self.stereo_neighbors(atom);
+            if neighbors.len() != 4 {
+                continue;
+            }
+            let keys: Vec<Option<usize>> =
+                neighbors.iter().map(|n| n.map(|n| classes[&n])).collect();
+            let distinct: BTreeSet<Option<usize>> = keys.iter().copied().collect();
+            if distinct.len() == 4 {
+                centers.push(atom);
+            } else if distinct.len() == 3 {
+                let tied: Vec<NodeIndex> = neighbors
+                    .iter()
+                    .zip(&keys)
+                    .filter(|(_, k)| keys.iter().filter(|other| other == k).count() == 2)
+                    .filter_map(|(n, _)| *n)
+                    .collect();
+                let ring = ring_info.rings.iter().find(|r| {
+                    r.contains_atom(atom)
+                        && tied.len() == 2
+                        && tied.iter().all(|n| r.contains_atom(*n))
+                });
+                if let Some(ring) = ring {
+                    ring_candidates.push((atom, ring.clone()));
+                }
+            }
+        }
+        for (atom, ring) in &ring_candidates {
+            let partner = ring_candidates
+                .iter()
+                .any(|(other, r)| other != atom && r == ring)
+                || centers.iter().any(|c| ring.contains_atom(*c));
+            if partner {
+                centers.push(*atom);
+            }
+        }
+        centers.sort();
+        centers
+    }
+
+    /// Double bonds with a cis/trans configuration by constitution: both ends are C or N with
+    /// different substituents, and the bond is not in a ring smaller than eight atoms.
+    pub fn potential_stereo_bonds(&self) -> Vec<EdgeIndex> {
+        let classes = self.symmetry_classes();
+        let ring_info = self.ring_info();
+        let mut bonds = Vec::new();
+        for edge in (&self.graph).edge_references() {
+            if edge.weight().order != BondOrder::Double {
+                continue;
+            }
+            let small_ring = ring_info
+                .rings
+                .iter()
+                .any(|r| r.contains_bond(edge.id()) && r.len() < MIN_STEREO_RING_SIZE);
+            if small_ring {
+                continue;
+            }
+            let sides = [
+                (edge.source(), edge.target()),
+                (edge.target(), edge.source()),
+            ];
+            let stereo = sides.iter().all(|(atom, partner)| {
+                if ![6, 7].contains(&self.graph[*atom].element.atomic_number) {
+                    return false;
+                }
+                let neighbors = self.double_bond_neighbors(*atom, *partner);
+                let single = self
+                    .graph
+                    .edges(*atom)
+                    .all(|e| e.id() == edge.id() || e.weight().order == BondOrder::Single);
+                match neighbors.len() {
+                    1 => single && self.total_hydrogens(*atom) <= 1,
+                    2 => single && classes[&neighbors[0]] != classes[&neighbors[1]],
+                    _ => false,
+                }
+            });
+            if stereo {
+                bonds.push(edge.id());
+            }
+        }
+        bonds.sort();
+        bonds
+    }
+
+    /// Stereoisomers for all combinations of configurations of the potential stereocenters
+    /// and stereo double bonds, in a fixed order starting with the all-anticlockwise/cis form.
+    pub fn enumerate_stereoisomers(&self, options: &StereoisomerOptions) -> Vec<Molecule> {
+        let centers: Vec<NodeIndex> = self
+            .potential_stereocenters()
+            .into_iter()
+            .filter(|a| !options.only_unassigned || self.graph[*a].chirality == Chirality::None)
+            .collect();
+        let bonds: Vec<EdgeIndex> = self
+            .potential_stereo_bonds()
+            .into_iter()
+            .filter(|b| {
+                !options.only_unassigned
+                    || self.graph[*b].double_bond_stereo == DoubleBondStereo::None
+            })
+            .collect();
+        let count = centers.len() + bonds.len();
+        let mut isomers = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let combinations = 1u128.checked_shl(count as u32).unwrap_or(u128::MAX);
+        let mut combination = 0u128;
+        while combination < combinations && isomers.len() < options.max_isomers {
+            let mut isomer = self.clone();
+            for (i, atom) in centers.iter().enumerate() {
+                isomer.graph[*atom].chirality = if bit(combination, i) {
+                    Chirality::AntiClockwise
+                } else {
+                    Chirality::Clockwise
+                };
+            }
+            for (i, bond) in bonds.iter().enumerate() {
+                isomer.graph[*bond].double_bond_stereo = if bit(combination, centers.len() + i) {
+                    DoubleBondStereo::Cis
+                } else {
+                    DoubleBondStereo::Trans
+                };
+            }
+            if !options.unique || seen.insert(isomer.canonical_smiles()) {
+                isomers.push(isomer);
+            }
+            combination += 1;
+        }
+        isomers
+    }
+
+    /// Records the neighbors of all stereo atoms and double bonds before the atoms around
+    /// them change, so that `restore_stereo` can keep their configuration.
+    pub(crate) fn stereo_snapshot(&self) -> StereoSnapshot {
+        let atoms = self
+            .graph
+            .node_indices()
+            .filter(|a| self.graph[*a].chirality != Chirality::None)
+            .map(|a| (a, self.stereo_neighbors(a)))
+            .collect();
+        let bonds = (&self.graph)
+            .edge_references()
+            .filter(|e| e.weight().double_bond_stereo != DoubleBondStereo::None)
+            .map(|e| {
+                let (a, b) = (e.source(), e.target());
+                (
+                    e.id(),
+                    [
+                        self.double_bond_neighbors(a, b).first().copied(),
+                        self.double_bond_neighbors(b, a).first().copied(),
+                    ],
+                )
+            })
+            .collect();
+        StereoSnapshot { atoms, bonds }
+    }
+
+    /// Adjusts the stereo of the atoms and bonds in `snapshot` to their new neighbors.
+    /// `replaced` maps atoms of the snapshot, or the implicit hydrogen (`None`) of an atom,
+    /// to the atom now at their place. Stereo which can not be carried over is cleared.
+    pub(crate) fn restore_stereo(
+        &mut self,
+        snapshot: StereoSnapshot,
+        replaced: &HashMap<(NodeIndex, Option<NodeIndex>), Option<NodeIndex>>,
+    ) {
+        let replace = |center: NodeIndex, n: Option<NodeIndex>| {
+            replaced.get(&(center, n)).copied().unwrap_or(n)
+        };
+        for (atom, before) in snapshot.atoms {
+            if self.graph.node_weight(atom).is_none() {
+                continue;
+            }
+            let before: Vec<Option<NodeIndex>> =
+                before.into_iter().map(|n| replace(atom, n)).collect();
+            let after = self.stereo_neighbors(atom);
+            self.graph[atom].chirality = match permutation_parity(&before, &after) {
+                Some(false) => self.graph[atom].chirality,
+                Some(true) => self.graph[atom].chirality.inverted(),
+                None => Chirality::None,
+            };
+        }
+        for (bond, [reference_a, reference_b]) in snapshot.bonds {
+            let Some((a, b)) = self.graph.edge_endpoints(bond) else {
+                continue;
+            };
+            let mut flip = false;
+            for (atom, partner, reference) in [(a, b, reference_a), (b, a, reference_b)] {
+                let neighbors = self.double_bond_neighbors(atom, partner);
+                match reference.map(|r| replace(atom, Some(r))) {
+                    Some(Some(r)) if neighbors.first() == Some(&r) => {}
+                    Some(Some(r)) if neighbors.len() == 2 && neighbors.contains(&r) => flip = !flip,
+                    // the reference became an implicit hydrogen opposite the other neighbor
+                    Some(None) if neighbors.len() == 1 => flip = !flip,
+                    _ => {
+                        self.graph[bond].double_bond_stereo = DoubleBondStereo::None;
+                    }
+                }
+            }
+            if flip {
+                self.graph[bond].double_bond_stereo =
+                    self.graph[bond].double_bond_stereo.inverted();
+            }
+        }
+    }
+}
+
+/// Stereo neighbors of stereo atoms and reference neighbors of stereo double bonds, taken
+/// by `Molecule::stereo_snapshot`.
+pub(crate) struct StereoSnapshot {
+    atoms: Vec<(NodeIndex, Vec<Option<NodeIndex>>)>,
+    bonds: Vec<(EdgeIndex, [Option<NodeIndex>; 2])>,
+}
+
+/// Whether bit `i` of the combination is unset.
+fn bit(combination: u128, i: usize) -> bool {
+    combination.checked_shr(i as u32).unwrap_or(0) & 1 == 0
+}
+
+/// Parity of the permutation turning `from` into `to`: `Some(true)` if it is odd, `None` if
+/// `to` is not a permutation of `from`.
+pub(crate) fn permutation_parity<T: PartialEq>(from: &[T], to: &[T]) -> Option<bool> {
+    if from.len() != to.len() {
+        return None;
+    }
+    let mut positions = Vec::with_capacity(from.len());
+    for item in from {
+        let position = to.iter().position(|t| t == item)?;
+        if positions.contains(&position) {
+            return None;
+        }
+        positions.push(position);
+    }
+    let mut odd = false;
+    for i in 0..positions.len() {
+        for j in i + 1..positions.len() {
+            if positions[i] > positions[j] {
+                odd = !odd;
+            }
+        }
+    }
+    Some(odd)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::stereo::StereoisomerOptions;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::atom::Chirality;
+    use crate::model::bond::DoubleBondStereo;
+    use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+    use std::collections::BTreeSet;
+
+    fn canonical(smiles: &str) -> String {
+        parse_molecule(smiles).unwrap().1.canonical_smiles()
+    }
+
+    fn stereoisomers(smiles: &str, options: &StereoisomerOptions) -> BTreeSet<String> {
+        let m = parse_molecule(smiles).unwrap().1;
+        m.enumerate_stereoisomers(options)
+            .iter()
+            .map(|isomer| isomer.canonical_smiles())
+            .collect()
+    }
+
+    #[test]
+    fn chirality_from_smiles() {
+        // L-alanine written from different atoms
+        let alanine = canonical("N[C@@H](C)C(=O)O");
+        assert_eq!(canonical("C[C@H](N)C(O)=O"), alanine);
+        assert_eq!(canonical("C[C@@H](C(=O)O)N"), alanine);
+        assert_eq!(canonical("OC(=O)[C@@H](N)C"), alanine);
+        assert_ne!(canonical("N[C@H](C)C(=O)O"), alanine);
+        assert_eq!(canonical("C[C@H](N)O"), canonical("[C@@H](C)(N)O"));
+        assert_eq!(canonical("C[C@H](N)O"), canonical("O[C@@H](N)C"));
+        // ring closures take the place of their digit
+        assert_eq!(canonical("C[C@H]1CCCCO1"), canonical("C[C@@H](CCCC1)O1"));
+        assert_ne!(canonical("C[C@H]1CCCCO1"), canonical("C[C@@H]1CCCCO1"));
+
+        let m = parse_molecule("C[C@H](N)O").unwrap().1;
+        assert_eq!(
+            m.stereo_neighbors(NodeIndex::new(1)),
+            vec![
+                None,
+                Some(NodeIndex::new(0)),
+                Some(NodeIndex::new(2)),
+                Some(NodeIndex::new(3))
+            ]
+        );
+        // (C, H, N, O) to (H, C, N, O) is an odd permutation
+        assert_eq!(m.graph[NodeIndex::new(1)].chirality, Chirality::Clockwise);
+    }
+
+    #[test]
+    fn double_bond_stereo_from_smiles() {
+        let bond = |smiles: &str| {
+            let m = parse_molecule(smiles).unwrap().1;
+            m.graph[EdgeIndex::new(1)].double_bond_stereo
+        };
+        assert_eq!(bond("F/C=C/F"), DoubleBondStereo::Trans);
+        assert_eq!(bond("F\\C=C\\F"), DoubleBondStereo::Trans);
+        assert_eq!(bond("F/C=C\\F"), DoubleBondStereo::Cis);
+        assert_eq!(bond("FC=CF"), DoubleBondStereo::None);
+        assert_eq!(canonical("C(\\F)=C/F"), canonical("F/C=C/F"));
+        assert_eq!(canonical("F/C=C/F"), "F/C=C/F");
+        assert_eq!(canonical("F/C=C\\F"), "F/C=C\\F");
+        // relative to the chlorine, which has a lower index than the methyl group
+        let m = parse_molecule("F/C=C(/Cl)C").unwrap().1;
+        assert_eq!(
+            m.graph[EdgeIndex::new(1)].double_bond_stereo,
+            DoubleBondStereo::Trans
+        );
+        assert_eq!(canonical("F/C=C(/Cl)C"), canonical("C/C(Cl)=C\\F"));
+        // conjugated double bonds share the directional bond between them
+        let diene = canonical("C/C=C/C=C\\C");
+        assert_eq!(canonical("C\\C=C/C=C\\C"), canonical("C/C=C\\C=C/C"));
+        assert_eq!(parse_molecule(&diene).unwrap().1.canonical_smiles(), diene);
+        assert_ne!(diene, canonical("C/C=C/C=C/C"));
+        // ring closure bonds
+        assert_eq!(canonical("F/C=C/1.C1"), canonical("F/C=C/C"));
+        assert_eq!(canonical("C1.F/C=C\\1"), canonical("F/C=C\\C"));
+    }
+
+    #[test]
+    fn potential_stereo() {
+        let m = parse_molecule("CC(O)CC").unwrap().1;
+        assert_eq!(m.potential_stereocenters(), vec![NodeIndex::new(1)]);
+        let m = parse_molecule("CC(C)O").unwrap().1;
+        assert!(m.potential_stereocenters().is_empty());
+        let m = parse_molecule("CC1CCC(C)CC1").unwrap().1;
+        assert_eq!(
+            m.potential_stereocenters(),
+            vec![NodeIndex::new(1), NodeIndex::new(4)]
+        );
+        let m = parse_molecule("CC1CCCCC1").unwrap().1;
+        assert!(m.potential_stereocenters().is_empty());
+        let m = parse_molecule("CC=CC(C)=C(C)C").unwrap().1;
+        assert_eq!(m.potential_stereo_bonds(), vec![EdgeIndex::new(1)]);
+        let m = parse_molecule("C1=CCCCC1.C1=CCCCCCC1.CC=NO").unwrap().1;
+        assert_eq!(m.potential_stereo_bonds().len(), 2);
+    }
+
+    #[test]
+    fn enumerate_stereoisomers() {
+        let options = StereoisomerOptions::default();
+        assert_eq!(stereoisomers("CC(O)CC", &options).len(), 2);
+        assert_eq!(stereoisomers("CC(C)O", &options).len(), 1);
+        assert_eq!(stereoisomers("CC=CC", &options).len(), 2);
+        // meso tartaric acid and the two enantiomers
+        let tartaric = stereoisomers("OC(=O)C(O)C(O)C(=O)O", &options);
+        assert_eq!(tartaric.len(), 3);
+        assert!(tartaric.contains(&canonical("OC(=O)[C@H](O)[C@@H](O)C(=O)O")));
+        assert!(tartaric.contains(&canonical("OC(=O)[C@@H](O)[C@@H](O)C(=O)O")));
+        // cis and trans
+        assert_eq!(
+            stereoisomers("CC1CCC(C)CC1", &options),
+            BTreeSet::from([
+                canonical("C[C@H]1CC[C@@H](C)CC1"),
+                canonical("C[C@H]1CC[C@H](C)CC1")
+            ])
+        );
+        assert_eq!(
+            canonical("C[C@H]1CC[C@H](C)CC1"),
+            canonical("C[C@@H]1CC[C@@H](C)CC1")
+        );
+        assert_eq!(stereoisomers("CC(O)C=CC", &options).len(), 4);
+
+        let all = StereoisomerOptions {
+            unique: false,
+            ..Default::default()
+        };
+        let m = parse_molecule("OC(=O)C(O)C(O)C(=O)O").unwrap().1;
+        assert_eq!(m.enumerate_stereoisomers(&all).len(), 4);
+    }
+
+    #[test]
+    fn enumerate_stereoisomers_options() {
+        let options = StereoisomerOptions::default();
+        let isomers = stereoisomers("C[C@H](O)C(O)CC", &options);
+        assert_eq!(isomers.len(), 2);
+        assert!(isomers
+            .iter()
+            .all(|s| s.contains("[C@H](O)") || s.contains("[C@@H](O)")));
+        let reassigned = StereoisomerOptions {
+            only_unassigned: false,
+            ..Default::default()
+        };
+        assert_eq!(stereoisomers("C[C@H](O)C(O)CC", &reassigned).len(), 4);
+        let limited = StereoisomerOptions {
+            max_isomers: 3,
+            ..Default::default()
+        };
+        assert_eq!(stereoisomers("CC(O)C(O)C(O)CC", &limited).len(), 3);
+    }
+
+    #[test]
+    fn explicit_hydrogens_keep_stereo() {
+        for (smiles, explicit) in [
+            (
+                "N[C@@H](C)C(=O)O",
+                "[H]N([H])[C@@]([H])(C([H])([H])[H])C(=O)O[H]",
+            ),
+            ("F/[CH]=[CH]/F", "[H]/C(F)=C(/[H])F"),
+        ] {
+            let expected = canonical(smiles);
+            let mut m = parse_molecule(smiles).unwrap().1;
+            m.add_explicit_hydrogens();
+            assert_eq!(m.canonical_smiles(), canonical(explicit), "{}", smiles);
+            m.remove_explicit_hydrogens();
+            assert_eq!(m.canonical_smiles(), expected, "{}", smiles);
+        }
+    }
+}
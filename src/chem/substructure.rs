@@ -0,0 +1,234 @@
+use crate::chem::fragment::RuleContext;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Substructure search stops after this many matches.
+const MAX_SUBSTRUCTURE_MATCHES: usize = 1000;
+
+impl Molecule {
+    /// Whether the query is a substructure of this molecule (see
+    /// `Molecule::substructure_matches`).
+    pub fn has_substructure(&self, query: &Molecule) -> bool {
+        !Matcher::new(query, self, 1).run().is_empty()
+    }
+
+    /// Mappings from query atoms to atoms of this molecule, one per distinct set of matched
+    /// atoms. Atoms match on element, charge, isotope if the query gives one, and perceived
+    /// aromaticity, which query atoms written lowercase like `c` always have; query wildcards
+    /// match any atom. Bonds match on order, or on perceived
+    /// aromaticity. Hydrogen counts are not compared, so the query may have fewer substituents.
+    pub fn substructure_matches(&self, query: &Molecule) -> Vec<HashMap<NodeIndex, NodeIndex>> {
+        let mut seen = HashSet::new();
+        self.substructure_mappings(query)
+            .into_iter()
+            .filter(|m| seen.insert(m.values().copied().collect::<BTreeSet<NodeIndex>>()))
+            .collect()
+    }
+
+    /// Like `Molecule::substructure_matches`, but with every mapping of the query, including
+    /// those which only differ by its symmetry.
+    pub fn substructure_mappings(&self, query: &Molecule) -> Vec<HashMap<NodeIndex, NodeIndex>> {
+        Matcher::new(query, self, MAX_SUBSTRUCTURE_MATCHES).run()
+    }
+}
+
+/// Backtracking search of query atoms, in an order where each atom is bonded to an earlier
+/// one unless it starts a new component.
+struct Matcher<'a> {
+    query: RuleContext<'a>,
+    target: RuleContext<'a>,
+    order: Vec<NodeIndex>,
+    limit: usize,
+    mapping: HashMap<NodeIndex, NodeIndex>,
+    used: HashSet<NodeIndex>,
+    matches: Vec<HashMap<NodeIndex, NodeIndex>>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(query: &'a Molecule, target: &'a Molecule, limit: usize) -> Matcher<'a> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        for start in query.graph.node_indices() {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut queue = VecDeque::from([start]);
+            while let Some(atom) = queue.pop_front() {
+                order.push(atom);
+                for neighbor in query.get_neighbors_for_atom(atom) {
+                    if visited.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        Matcher {
+            query: RuleContext::new(query),
+            target: RuleContext::new(target),
+            order,
+            limit,
+            mapping: HashMap::new(),
+            used: HashSet::new(),
+            matches: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<HashMap<NodeIndex, NodeIndex>> {
+        if !self.order.is_empty() {
+            self.extend(0);
+        }
+        self.matches
+    }
+
+    fn extend(&mut self, depth: usize) {
+        if depth == self.order.len() {
+            self.matches.push(self.mapping.clone());
+            return;
+        }
+        let atom = self.order[depth];
+        let mapped_neighbor = self
+            .query
+            .neighbors(atom)
+            .into_iter()
+            .find_map(|(n, _)| self.mapping.get(&n).copied());
+        let candidates: Vec<NodeIndex> = match mapped_neighbor {
+            Some(image) => self
+                .target
+                .neighbors(image)
+                .into_iter()
+                .map(|(n, _)| n)
+                .collect(),
+            None => self.target.molecule.graph.node_indices().collect(),
+        };
+        for candidate in candidates {
+            if self.matches.len() >= self.limit {
+                return;
+            }
+            if self.used.contains(&candidate) || !self.feasible(atom, candidate) {
+                continue;
+            }
+            self.mapping.insert(atom, candidate);
+            self.used.insert(candidate);
+            self.extend(depth + 1);
+            self.mapping.remove(&atom);
+            self.used.remove(&candidate);
+        }
+    }
+
+    /// Whether the atoms match and every bond to an already mapped query atom is present.
+    fn feasible(&self, atom: NodeIndex, candidate: NodeIndex) -> bool {
+        if !self.atoms_match(atom, candidate) {
+            return false;
+        }
+        self.query.neighbors(atom).into_iter().all(|(n, bond)| {
+            let Some(image) = self.mapping.get(&n) else {
+                return true;
+            };
+            let target_bond = self
+                .target
+                .neighbors(candidate)
+                .into_iter()
+                .find(|(t, _)| t == image)
+                .map(|(_, e)| e);
+            target_bond.is_some_and(|t| self.bonds_match(bond, t, atom, n))
+        })
+    }
+
+    fn atoms_match(&self, atom: NodeIndex, candidate: NodeIndex) -> bool {
+        let q = &self.query.molecule.graph[atom];
+        if q.is_wildcard() {
+            return true;
+        }
+        let t = &self.target.molecule.graph[candidate];
+        q.element == t.element
+            && q.charge == t.charge
+            && (q.isotope == 0 || q.isotope == t.isotope)
+            && (q.aromatic || is_aromatic(&self.query, atom))
+                == is_aromatic(&self.target, candidate)
+    }
+
+    fn bonds_match(&self, bond: EdgeIndex, target: EdgeIndex, a: NodeIndex, b: NodeIndex) -> bool {
+        let query_graph = &self.query.molecule.graph;
+        if query_graph[a].is_wildcard() || query_graph[b].is_wildcard() {
+            return true;
+        }
+        match (
+            self.query.is_aromatic_bond(bond),
+            self.target.is_aromatic_bond(target),
+        ) {
+            (true, true) => true,
+            (false, false) => query_graph[bond].order == self.target.molecule.graph[target].order,
+            _ => false,
+        }
+    }
+}
+
+/// Whether the atom is perceived as aromatic or written with aromatic bonds, as in a query
+/// fragment of a ring like `cc`.
+fn is_aromatic(context: &RuleContext, atom: NodeIndex) -> bool {
+    context.is_aromatic(atom)
+        || context
+            .neighbors(atom)
+            .iter()
+            .any(|(_, e)| context.is_aromatic_bond(*e))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+
+    #[test]
+    fn substructure_aromatic_atoms() {
+        let benzene = parse_molecule("c1ccccc1").unwrap().1;
+        let pyridine = parse_molecule("c1ccncc1").unwrap().1;
+        let bromobenzene = parse_molecule("Brc1ccccc1").unwrap().1;
+        let cyclohexane = parse_molecule("C1CCCCC1").unwrap().1;
+        let query = |smiles: &str| parse_molecule(smiles).unwrap().1;
+        assert_eq!(benzene.substructure_matches(&query("c")).len(), 6);
+        assert!(!cyclohexane.has_substructure(&query("c")));
+        assert!(!benzene.has_substructure(&query("C")));
+        assert!(pyridine.has_substructure(&query("n")));
+        assert!(!pyridine.has_substructure(&query("N")));
+        assert!(bromobenzene.has_substructure(&query("cBr")));
+        assert!(bromobenzene.has_substructure(&query("Brccc")));
+        assert!(!benzene.has_substructure(&query("cBr")));
+    }
+
+    #[test]
+    fn substructure_matches() {
+        let benzene = parse_molecule("c1ccccc1").unwrap().1;
+        let toluene = parse_molecule("Cc1ccccc1").unwrap().1;
+        let kekule = parse_molecule("CC1=CC=CC=C1").unwrap().1;
+        assert!(toluene.has_substructure(&benzene));
+        assert!(kekule.has_substructure(&benzene));
+        assert!(!benzene.has_substructure(&toluene));
+        assert_eq!(toluene.substructure_matches(&benzene).len(), 1);
+        assert_eq!(toluene.substructure_mappings(&benzene).len(), 12);
+
+        let acid = parse_molecule("C(=O)O").unwrap().1;
+        let ibuprofen = parse_molecule("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O").unwrap().1;
+        assert_eq!(ibuprofen.substructure_matches(&acid).len(), 1);
+        // there is no ether oxygen
+        let ester = parse_molecule("COC").unwrap().1;
+        assert!(!ibuprofen.has_substructure(&ester));
+        assert!(!ibuprofen.has_substructure(&parse_molecule("N").unwrap().1));
+
+        // wildcards match any atom
+        let query = parse_molecule("*C(=O)O").unwrap().1;
+        assert!(ibuprofen.has_substructure(&query));
+        let ethanol = parse_molecule("CCO").unwrap().1;
+        assert_eq!(
+            ethanol
+                .substructure_matches(&parse_molecule("*").unwrap().1)
+                .len(),
+            3
+        );
+        // charges must be equal
+        let acetate = parse_molecule("CC(=O)[O-]").unwrap().1;
+        assert!(!acetate.has_substructure(&acid));
+        // aromatic query fragments which are not rings by themselves
+        assert!(toluene.has_substructure(&parse_molecule("Ccc").unwrap().1));
+        assert!(!toluene.has_substructure(&parse_molecule("CCC").unwrap().1));
+    }
+}
@@ -0,0 +1,343 @@
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use bincode::{Decode, Encode};
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+/// Score of an aromatic ring in the canonical tautomer choice.
+const AROMATIC_RING_SCORE: i32 = 100;
+/// Score of a carbonyl group in the canonical tautomer choice.
+const CARBONYL_SCORE: i32 = 2;
+/// Limit of resonance forms searched for hydrogen shifts of one tautomer.
+const MAX_KEKULE_FORMS: usize = 64;
+
+/// Hydrogen shifts used to generate tautomers. Shifts never open or close rings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub enum TautomerRule {
+    /// `HC-C=O` and `C=C-OH`, also for thiocarbonyls.
+    KetoEnol,
+    /// `HN-C=O` and `N=C-OH`, including lactam-lactim pairs like 2-pyridone.
+    AmideImidicAcid,
+    /// 1,3 shifts between other heteroatoms, e.g. in amidines.
+    HeteroatomShift13,
+    /// 1,5 shifts between heteroatoms over two conjugated double bonds.
+    HeteroatomShift15,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TautomerOptions {
+    pub rules: BTreeSet<TautomerRule>,
+    /// Enumeration stops once this many tautomers are found.
+    pub max_tautomers: usize,
+}
+
+impl Default for TautomerOptions {
+    fn default() -> Self {
+        TautomerOptions {
+            rules: BTreeSet::from([
+                TautomerRule::KetoEnol,
+                TautomerRule::AmideImidicAcid,
+                TautomerRule::HeteroatomShift13,
+                TautomerRule::HeteroatomShift15,
+            ]),
+            max_tautomers: 1000,
+        }
+    }
+}
+
+/// A hydrogen moving from the first to the last atom of `atoms`, swapping the orders of the
+/// alternating single and double `bonds` between them.
+struct HydrogenShift {
+    atoms: Vec<NodeIndex>,
+    bonds: Vec<EdgeIndex>,
+}
+
+impl Molecule {
+    /// Tautomers reachable from the molecule by the enabled hydrogen shifts, starting with the
+    /// molecule itself. Tautomers are in Kekulé form and differ in canonical SMILES.
+    pub fn enumerate_tautomers(&self, options: &TautomerOptions) -> Vec<Molecule> {
+        let mut start = self.clone();
+        start.kekulize();
+
+        let mut seen = HashSet::from([start.canonical_smiles()]);
+        let mut tautomers = vec![start.clone()];
+        let mut queue = VecDeque::from([start]);
+        while let Some(molecule) = queue.pop_front() {
+            // a shift may only be possible in another resonance form, e.g. lactim to lactam
+            for form in molecule.kekule_forms() {
+                for shift in form.hydrogen_shifts(&options.rules) {
+                    if tautomers.len() >= options.max_tautomers {
+                        return tautomers;
+                    }
+                    let tautomer = form.shift_hydrogen(&shift);
+                    if seen.insert(tautomer.canonical_smiles()) {
+                        tautomers.push(tautomer.clone());
+                        queue.push_back(tautomer);
+                    }
+                }
+            }
+        }
+        tautomers
+    }
+
+    /// Kekulé structures reachable by flipping even rings of alternating single and double
+    /// bonds, starting with the molecule itself.
+    fn kekule_forms(&self) -> Vec<Molecule> {
+        let key =
+            |m: &Molecule| -> Vec<u8> { m.graph.edge_weights().map(|b| b.order as u8).collect() };
+        let mut seen = HashSet::from([key(self)]);
+        let mut forms = vec![self.clone()];
+        let mut i = 0;
+        while i < forms.len() && forms.len() < MAX_KEKULE_FORMS {
+            for ring in &self.ring_info().rings {
+                let orders: Vec<BondOrder> = ring
+                    .bonds
+                    .iter()
+                    .map(|b| forms[i].graph[*b].order)
+                    .collect();
+                let alternating = ring.len() % 2 == 0
+                    && orders
+                        .iter()
+                        .all(|o| matches!(o, BondOrder::Single | BondOrder::Double))
+                    && orders.windows(2).all(|w| w[0] != w[1]);
+                if !alternating {
+                    continue;
+                }
+                let mut form = forms[i].clone();
+                for (bond, order) in ring.bonds.iter().zip(orders) {
+                    form.graph[*bond].order = match order {
+                        BondOrder::Single => BondOrder::Double,
+                        _ => BondOrder::Single,
+                    };
+                }
+                if seen.insert(key(&form)) {
+                    forms.push(form);
+                }
+            }
+            i += 1;
+        }
+        forms
+    }
+
+    /// Deterministic representative of the enumerated tautomers: the one with the highest
+    /// score (100 per aromatic ring, 2 per C=O bond) and the smallest canonical SMILES among
+    /// equal scores. All tautomers of a compound give the same result as long as the
+    /// enumeration is not cut by `max_tautomers`.
+    pub fn canonical_tautomer(&self, options: &TautomerOptions) -> Molecule {
+        self.enumerate_tautomers(options)
+            .into_iter()
+            .map(|t| (-t.tautomer_score(), t.canonical_smiles(), t))
+            .min_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)))
+            .map(|(_, _, t)| t)
+            .unwrap()
+    }
+
+    fn tautomer_score(&self) -> i32 {
+        let aromaticity = self.aromaticity();
+        let aromatic_rings = self
+            .ring_info()
+            .rings
+            .iter()
+            .filter(|r| r.bonds.iter().all(|b| aromaticity.is_bond_aromatic(*b)))
+            .count() as i32;
+        let carbonyls = (&self.graph)
+            .edge_references()
+            .filter(|e| e.weight().order == BondOrder::Double)
+            .filter(|e| {
+                let elements =
+                    [e.source(), e.target()].map(|a| self.graph[a].element.atomic_number);
+                elements == [6, 8] || elements == [8, 6]
+            })
+            .count() as i32;
+        aromatic_rings * AROMATIC_RING_SCORE + carbonyls * CARBONYL_SCORE
+    }
+
+    /// All hydrogen shifts allowed by the rules, from uncharged donors to uncharged acceptors.
+    fn hydrogen_shifts(&self, rules: &BTreeSet<TautomerRule>) -> Vec<HydrogenShift> {
+        let mut shifts = Vec::new();
+        for donor in self.graph.node_indices() {
+            // the donor gets a double bond, so it must be saturated
+            if self.graph[donor].charge != 0
+                || self.total_hydrogens(donor) == 0
+                || self
+                    .graph
+                    .edges(donor)
+                    .any(|e| e.weight().order != BondOrder::Single)
+            {
+                continue;
+            }
+            let mut path = HydrogenShift {
+                atoms: vec![donor],
+                bonds: Vec::new(),
+            };
+            self.extend_shift(&mut path, rules, &mut shifts);
+        }
+        shifts
+    }
+
+    /// Extends the path with a single and a double bond, recording it when a rule applies.
+    fn extend_shift(
+        &self,
+        path: &mut HydrogenShift,
+        rules: &BTreeSet<TautomerRule>,
+        shifts: &mut Vec<HydrogenShift>,
+    ) {
+        let last = *path.atoms.last().unwrap();
+        for single in self.graph.edges(last) {
+            if single.weight().order != BondOrder::Single {
+                continue;
+            }
+            let middle = other_atom(single.source(), single.target(), last);
+            if path.atoms.contains(&middle) {
+                continue;
+            }
+            for double in self.graph.edges(middle) {
+                if double.weight().order != BondOrder::Double {
+                    continue;
+                }
+                let acceptor = other_atom(double.source(), double.target(), middle);
+                if path.atoms.contains(&acceptor) {
+                    continue;
+                }
+                path.atoms.extend([middle, acceptor]);
+                path.bonds.extend([single.id(), double.id()]);
+                if self.graph[acceptor].charge == 0
+                    && self
+                        .shift_rule(&path.atoms)
+                        .is_some_and(|r| rules.contains(&r))
+                {
+                    shifts.push(HydrogenShift {
+                        atoms: path.atoms.clone(),
+                        bonds: path.bonds.clone(),
+                    });
+                }
+                if path.atoms.len() < 5 {
+                    self.extend_shift(path, rules, shifts);
+                }
+                path.atoms.truncate(path.atoms.len() - 2);
+                path.bonds.truncate(path.bonds.len() - 2);
+            }
+        }
+    }
+
+    fn shift_rule(&self, atoms: &[NodeIndex]) -> Option<TautomerRule> {
+        let element = |a: &NodeIndex| self.graph[*a].element.atomic_number;
+        let is_hetero = |z: u8| matches!(z, 7 | 8 | 16);
+        let ends = (element(&atoms[0]), element(atoms.last().unwrap()));
+        if atoms.len() == 5 {
+            return (is_hetero(ends.0) && is_hetero(ends.1))
+                .then_some(TautomerRule::HeteroatomShift15);
+        }
+        match ends {
+            (6, 8 | 16) | (8 | 16, 6) => Some(TautomerRule::KetoEnol),
+            (7, 8 | 16) | (8 | 16, 7) if element(&atoms[1]) == 6 => {
+                Some(TautomerRule::AmideImidicAcid)
+            }
+            (a, b) if is_hetero(a) && is_hetero(b) => Some(TautomerRule::HeteroatomShift13),
+            _ => None,
+        }
+    }
+
+    fn shift_hydrogen(&self, shift: &HydrogenShift) -> Molecule {
+        let donor = shift.atoms[0];
+        let acceptor = *shift.atoms.last().unwrap();
+        let hydrogens = [donor, acceptor].map(|a| self.total_hydrogens(a));
+
+        let mut tautomer = self.clone();
+        for bond in &shift.bonds {
+            let order = &mut tautomer.graph[*bond].order;
+            *order = match order {
+                BondOrder::Single => BondOrder::Double,
+                _ => BondOrder::Single,
+            };
+        }
+        tautomer.set_total_hydrogens(donor, hydrogens[0] - 1);
+        tautomer.set_total_hydrogens(acceptor, hydrogens[1] + 1);
+        tautomer
+    }
+}
+
+fn other_atom(source: NodeIndex, target: NodeIndex, atom: NodeIndex) -> NodeIndex {
+    if source == atom {
+        target
+    } else {
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::tautomers::{TautomerOptions, TautomerRule};
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use std::collections::BTreeSet;
+
+    fn tautomers(smiles: &str, options: &TautomerOptions) -> BTreeSet<String> {
+        parse_molecule(smiles)
+            .unwrap()
+            .1
+            .enumerate_tautomers(options)
+            .iter()
+            .map(|t| t.canonical_smiles())
+            .collect()
+    }
+
+    fn canonical(smiles: &str) -> String {
+        parse_molecule(smiles)
+            .unwrap()
+            .1
+            .canonical_tautomer(&TautomerOptions::default())
+            .canonical_smiles()
+    }
+
+    #[test]
+    fn keto_enol() {
+        assert_eq!(
+            tautomers("CC(C)=O", &TautomerOptions::default()),
+            BTreeSet::from(["CC(C)=O".to_owned(), "C=C(C)O".to_owned()])
+        );
+        assert_eq!(canonical("C=C(C)O"), "CC(C)=O");
+    }
+
+    #[test]
+    fn pyridone() {
+        let pyridone = canonical("OC1=NC=CC=C1");
+        assert_eq!(pyridone, "O=c1cccc[nH]1");
+        assert_eq!(canonical("O=C1NC=CC=C1"), pyridone);
+        assert_eq!(canonical("Oc1ccccn1"), pyridone);
+        // only saturated atoms give hydrogens, so no cumulated double bonds appear
+        assert!(tautomers("O=C1NC=CC=C1", &TautomerOptions::default())
+            .iter()
+            .all(|t| !t.contains("=C=")));
+        // 1,5 shift
+        assert_eq!(canonical("OC1=CC=NC=C1"), canonical("O=C1C=CNC=C1"));
+    }
+
+    #[test]
+    fn rules() {
+        let amide = TautomerOptions {
+            rules: BTreeSet::from([TautomerRule::AmideImidicAcid]),
+            ..TautomerOptions::default()
+        };
+        assert_eq!(tautomers("CC(N)=O", &amide).len(), 2);
+        let no_amide = TautomerOptions {
+            rules: BTreeSet::from([TautomerRule::HeteroatomShift13]),
+            ..TautomerOptions::default()
+        };
+        assert_eq!(tautomers("CC(N)=O", &no_amide).len(), 1);
+        // amidine hydrogen shift gives the same compound
+        assert_eq!(tautomers("CC(N)=N", &TautomerOptions::default()).len(), 1);
+        assert_eq!(tautomers("CC(=N)NC", &TautomerOptions::default()).len(), 2);
+    }
+
+    #[test]
+    fn max_tautomers() {
+        let options = TautomerOptions {
+            max_tautomers: 3,
+            ..TautomerOptions::default()
+        };
+        let all = tautomers("CC(=O)CC(C)=O", &TautomerOptions::default());
+        assert!(all.len() > 3);
+        assert_eq!(tautomers("CC(=O)CC(C)=O", &options).len(), 3);
+    }
+}
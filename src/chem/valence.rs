@@ -0,0 +1,196 @@
+use crate::model::bond::BondOrder;
+use crate::model::element::Element;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::NodeIndex;
+
+/// Atoms which may carry implicit hydrogens when written outside of square brackets in SMILES.
+const ORGANIC_SUBSET: [u8; 10] = [5, 6, 7, 8, 9, 15, 16, 17, 35, 53];
+
+/// Allowed valences of an element with the given formal charge.
+/// Charged atoms follow the isoelectronic rule: N+ behaves like C, O- like F, C- like N etc.
+pub fn allowed_valences(element: &Element, charge: i8) -> Vec<u8> {
+    let adjust = |v: u8| -> Option<u8> {
+        let v = v as i16;
+        let charge = charge as i16;
+        let adjusted = match element.valence_electrons() {
+            Some(1) | Some(4) => v - charge.abs(),
+            Some(e) if e >= 5 => v + charge,
+            Some(_) => v - charge,
+            None => v,
+        };
+        u8::try_from(adjusted).ok()
+    };
+    let mut valences: Vec<u8> = element
+        .valences()
+        .iter()
+        .filter_map(|v| adjust(*v))
+        .collect();
+    valences.dedup();
+    valences
+}
+
+impl Molecule {
+    /// Sum of bond orders and hydrogen count of the atom, aromatic bonds counted as single.
+    /// The second value is true if the atom has aromatic bonds, in which case one more
+    /// valence unit is used by the delocalized double bond.
+    fn bond_valence(&self, atom: NodeIndex) -> (u8, bool) {
+        let mut valence = self.graph[atom].hs;
+        let mut aromatic = false;
+        for edge in self.get_bonds_for_atom(atom) {
+            valence += match self.graph[edge].order {
+                BondOrder::Single => 1,
+                BondOrder::Double => 2,
+                BondOrder::Triple => 3,
+                BondOrder::Aromatic => {
+                    aromatic = true;
+                    1
+                }
+            };
+        }
+        (valence, aromatic)
+    }
+
+    /// Valence of the atom from its bonds and explicit hydrogen count.
+    pub fn explicit_valence(&self, atom: NodeIndex) -> u8 {
+        let (valence, aromatic) = self.bond_valence(atom);
+        valence + aromatic as u8
+    }
+
+    /// Number of hydrogens needed to reach the lowest allowed valence of the atom.
+    /// Always 0 for bracket atoms and atoms outside of the SMILES organic subset.
+    pub fn implicit_hydrogens(&self, atom: NodeIndex) -> u8 {
+        let a = &self.graph[atom];
+        if a.no_implicit_hs || !ORGANIC_SUBSET.contains(&a.element.atomic_number) {
+            return 0;
+        }
+        let allowed = allowed_valences(&a.element, a.charge);
+        // aromatic atoms that are saturated by their single bonds donate a lone pair to the
+        // ring instead of a double bond, like O in furan or S in thiophene
+        let (single_valence, aromatic) = self.bond_valence(atom);
+        if aromatic && allowed.contains(&single_valence) {
+            return 0;
+        }
+        let valence = self.explicit_valence(atom);
+        allowed
+            .into_iter()
+            .find(|v| *v >= valence)
+            .map_or(0, |v| v - valence)
+    }
+
+    pub fn total_hydrogens(&self, atom: NodeIndex) -> u8 {
+        self.graph[atom].hs + self.implicit_hydrogens(atom)
+    }
+
+    /// Sets the total hydrogen count of the atom. The count is fixed as explicit unless
+    /// implicit hydrogens already give it.
+    pub fn set_total_hydrogens(&mut self, atom: NodeIndex, count: u8) {
+        let a = &mut self.graph[atom];
+        if !a.no_implicit_hs {
+            a.hs = 0;
+            if self.implicit_hydrogens(atom) == count {
+                return;
+            }
+        }
+        let a = &mut self.graph[atom];
+        a.hs = count;
+        a.no_implicit_hs = true;
+    }
+
+    /// Returns false if the atom valence exceeds the largest allowed valence.
+    /// Lower valences are accepted, so radicals like `[CH3]` are valid.
+    /// Elements without known valences are always considered valid.
+    pub fn has_valid_valence(&self, atom: NodeIndex) -> bool {
+        let a = &self.graph[atom];
+        if a.element.valences().is_empty() {
+            return true;
+        }
+        let (valence, _) = self.bond_valence(atom);
+        allowed_valences(&a.element, a.charge)
+            .last()
+            .is_some_and(|max| valence <= *max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chem::valence::allowed_valences;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::element::Element;
+    use petgraph::stable_graph::NodeIndex;
+
+    #[test]
+    fn allowed_valences_charged() {
+        assert_eq!(
+            allowed_valences(&Element { atomic_number: 7 }, 1),
+            vec![4, 6]
+        );
+        assert_eq!(allowed_valences(&Element { atomic_number: 8 }, -1), vec![1]);
+        assert_eq!(allowed_valences(&Element { atomic_number: 6 }, -1), vec![3]);
+        assert_eq!(allowed_valences(&Element { atomic_number: 5 }, -1), vec![4]);
+    }
+
+    #[test]
+    fn set_total_hydrogens() {
+        let mut m = parse_molecule("CC[O-]").unwrap().1;
+        m.set_charge(NodeIndex::new(2), 0);
+        m.set_total_hydrogens(NodeIndex::new(2), 1);
+        assert_eq!(m.graph[NodeIndex::new(2)].hs, 1);
+        m.set_total_hydrogens(NodeIndex::new(1), 2);
+        assert!(!m.graph[NodeIndex::new(1)].no_implicit_hs);
+        m.set_total_hydrogens(NodeIndex::new(0), 2);
+        assert!(m.graph[NodeIndex::new(0)].no_implicit_hs);
+        assert_eq!(m.formula().to_string(), "C2H5O");
+    }
+
+    #[test]
+    fn implicit_hydrogens() {
+        let m = parse_molecule("CC(=O)[NH]C#N").unwrap().1;
+        let hs: Vec<u8> = (0..6)
+            .map(|i| m.implicit_hydrogens(NodeIndex::new(i)))
+            .collect();
+        assert_eq!(hs, vec![3, 0, 0, 0, 0, 0]);
+        assert_eq!(m.total_hydrogens(NodeIndex::new(3)), 1);
+    }
+
+    #[test]
+    fn implicit_hydrogens_charged() {
+        let m = parse_molecule("C[N+](C)(C)C").unwrap().1;
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(1)), 0);
+        let m = parse_molecule("CS(=O)(=O)O").unwrap().1;
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(1)), 0);
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(4)), 1);
+    }
+
+    #[test]
+    fn implicit_hydrogens_aromatic() {
+        let m = parse_molecule("C1:C:C:C:C:C:1").unwrap().1;
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(0)), 1);
+        let m = parse_molecule("c1ccsc1").unwrap().1;
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(0)), 1);
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(3)), 0);
+        let m = parse_molecule("c1ccncc1").unwrap().1;
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(3)), 0);
+        let m = parse_molecule("c1ccc2ccccc2c1").unwrap().1;
+        assert_eq!(m.implicit_hydrogens(NodeIndex::new(3)), 0);
+        assert_eq!(m.formula().to_string(), "C10H8");
+    }
+
+    #[test]
+    fn valid_valence() {
+        let m = parse_molecule("C(C)(C)(C)(C)C").unwrap().1;
+        assert!(!m.has_valid_valence(NodeIndex::new(0)));
+        assert!(m.has_valid_valence(NodeIndex::new(1)));
+        let m = parse_molecule("[CH7]").unwrap().1;
+        assert!(!m.has_valid_valence(NodeIndex::new(0)));
+        let m = parse_molecule("[CH4]").unwrap().1;
+        assert!(m.has_valid_valence(NodeIndex::new(0)));
+        let m = parse_molecule("[CH3]").unwrap().1;
+        assert!(m.has_valid_valence(NodeIndex::new(0)));
+        let m = parse_molecule("[NH4]").unwrap().1;
+        assert!(m.has_valid_valence(NodeIndex::new(0)));
+        let m = parse_molecule("[OH3]").unwrap().1;
+        assert!(!m.has_valid_valence(NodeIndex::new(0)));
+        let m = parse_molecule("[OH3+]").unwrap().1;
+        assert!(m.has_valid_valence(NodeIndex::new(0)));
+    }
+}
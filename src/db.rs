@@ -1,3 +1,6 @@
 pub mod index;
 pub mod index_item;
+pub mod mmp;
+pub mod molecule_store;
+pub mod scaffolds;
 pub mod search;
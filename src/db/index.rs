@@ -1,46 +1,472 @@
+use crate::chem::sanitize::SanitizeProblem;
+use crate::chem::standardize::StandardizeOptions;
+use crate::chem::tautomers::TautomerOptions;
 use crate::db::index_item::IndexItem;
-use crate::io::smiles::reader::molecule::parse_molecule;
-use crate::model::fingerprint::FINGERPRINT_SIZE;
-use bincode::encode_into_slice;
+use crate::db::molecule_store::{store_path, MoleculeStoreWriter};
+use crate::io::smiles::reader::molecule::{parse_molecule, parse_molecule_line};
+use crate::io::smiles::reader::reaction::parse_reaction;
+use crate::model::fingerprint::{Fingerprint, FINGERPRINT_SIZE};
+use crate::model::molecule::Molecule;
+use crate::model::reaction::Reaction;
+use bincode::{
+    decode_from_slice, decode_from_std_read, encode_into_slice, encode_into_std_write, Decode,
+    Encode,
+};
 use std::fs::File;
-use std::io::{BufRead, BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Write};
 
 #[cfg(windows)]
 const LINE_ENDING_LENGTH: usize = 2;
 #[cfg(not(windows))]
 const LINE_ENDING_LENGTH: usize = 1;
 
-pub fn index_file(smiles_file: &str) {
+/// Index file (`.fp`) layout:
+/// * header: magic `RINGOIDX`, format version (u32, little-endian), bincode-encoded
+///   `IndexContents`
+/// * items: bincode-encoded `IndexItem`s, zero padded to `INDEX_ITEM_SIZE` bytes each
+const MAGIC: &[u8; 8] = b"RINGOIDX";
+pub const INDEX_VERSION: u32 = 1;
+const HEADER_SIZE: usize = 12;
+
+/// Size of an index item: the longest varint encodings of the position and of every
+/// fingerprint block, and the block count.
+pub(crate) const INDEX_ITEM_SIZE: usize = 9 + 1 + FINGERPRINT_SIZE / 64 * 9;
+
+/// Radius of the ECFP fingerprints of molecules and reactions in the index.
+pub(crate) const FINGERPRINT_RADIUS: usize = 2;
+
+/// Record that could not be read or failed sanitization and was not written to the index.
+#[derive(Debug)]
+pub struct RejectedRecord {
+    pub line_number: usize,
+    pub line: String,
+    pub problems: Vec<SanitizeProblem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub struct IndexOptions {
+    /// Standardize molecules before sanitization, so that salt forms and different group
+    /// representations of a compound are indexed as the same structure.
+    pub standardize: Option<StandardizeOptions>,
+    /// Fingerprint the canonical tautomer instead of the molecule as written, so that
+    /// tautomers of a compound get the same fingerprint. The stored molecule is unchanged.
+    pub canonical_tautomers: Option<TautomerOptions>,
+}
+
+/// Index fingerprint of a molecule. Queries must be fingerprinted with the options used for
+/// indexing, which are recorded in the index header. Fails with `InvalidData` if the canonical
+/// tautomer can not be read back from its SMILES.
+pub fn index_fingerprint(
+    molecule: &Molecule,
+    options: &IndexOptions,
+) -> std::io::Result<Fingerprint> {
+    match &options.canonical_tautomers {
+        // re-read from canonical SMILES, so that the fingerprint does not depend on the
+        // input atom order and hydrogen notation
+        Some(tautomer_options) => {
+            let smiles = molecule
+                .canonical_tautomer(tautomer_options)
+                .canonical_smiles();
+            match parse_molecule(&smiles) {
+                Ok(("", tautomer)) => Ok(tautomer.ecfp(FINGERPRINT_RADIUS, FINGERPRINT_SIZE)),
+                _ => Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("could not read the canonical tautomer {}", smiles),
+                )),
+            }
+        }
+        None => Ok(molecule.ecfp(FINGERPRINT_RADIUS, FINGERPRINT_SIZE)),
+    }
+}
+
+/// Fingerprint of reactions in the index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+pub enum ReactionFingerprint {
+    /// Atom environments changed by the reaction (see `Reaction::difference_fingerprint`).
+    #[default]
+    Difference,
+    /// Reactant and product structures (see `Reaction::structural_fingerprint`).
+    Structural,
+}
+
+/// What the records of an index are and how they were fingerprinted, stored in the index
+/// header so that searches fingerprint the query the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum IndexContents {
+    Molecules(IndexOptions),
+    Reactions(ReactionFingerprint),
+}
+
+/// Index fingerprint of a reaction. Queries must be fingerprinted with the kind used for
+/// indexing.
+pub fn reaction_index_fingerprint(reaction: &Reaction, kind: ReactionFingerprint) -> Fingerprint {
+    match kind {
+        ReactionFingerprint::Difference => {
+            reaction.difference_fingerprint(FINGERPRINT_RADIUS, FINGERPRINT_SIZE)
+        }
+        ReactionFingerprint::Structural => {
+            reaction.structural_fingerprint(FINGERPRINT_RADIUS, FINGERPRINT_SIZE)
+        }
+    }
+}
+
+/// Writes the fingerprint index (`.fp`) and the molecule store (`.mol`) for the SMILES file.
+/// Index items and store records have the same order, rejected records are in neither:
+/// molecules failing sanitization with their problems, lines which are not SMILES and
+/// molecules which can not be fingerprinted without problems.
+pub fn index_file(smiles_file: &str) -> Vec<RejectedRecord> {
+    index_file_with_options(smiles_file, &IndexOptions::default())
+}
+
+pub fn index_file_with_options(smiles_file: &str, options: &IndexOptions) -> Vec<RejectedRecord> {
     // open file for reading
     let fi = File::open(smiles_file).expect("Could not open file");
 
     // open binary file for index
     let mut offset = 0;
-    let fo = File::create(smiles_file.to_owned() + ".fp");
-    let mut writer = BufWriter::new(fo.unwrap());
+    let mut writer = create_index(smiles_file, &IndexContents::Molecules(options.clone())).unwrap();
+    let mut store = MoleculeStoreWriter::create(&store_path(smiles_file)).unwrap();
+    let mut rejected = Vec::new();
 
-    for line in std::io::BufReader::new(fi).lines() {
+    for (line_number, line) in std::io::BufReader::new(fi).lines().enumerate() {
         let line = line.unwrap();
-        let molecule = parse_molecule(&line).unwrap().1;
-        let index_item = IndexItem {
-            position: offset,
-            fingerprint: molecule.ecfp(2, 512),
+        let position = offset;
+        offset += line.len() + LINE_ENDING_LENGTH;
+
+        let Ok((name, mut molecule)) = parse_molecule_line(&line) else {
+            rejected.push(RejectedRecord {
+                line_number,
+                line,
+                problems: Vec::new(),
+            });
+            continue;
         };
+        if let Some(standardize) = &options.standardize {
+            molecule.standardize(standardize);
+        }
+        let problems = molecule.sanitize();
+        if !problems.is_empty() {
+            rejected.push(RejectedRecord {
+                line_number,
+                line,
+                problems,
+            });
+            continue;
+        }
+
+        let Ok(fingerprint) = index_fingerprint(&molecule, options) else {
+            rejected.push(RejectedRecord {
+                line_number,
+                line,
+                problems: Vec::new(),
+            });
+            continue;
+        };
+        write_index_item(&mut writer, position, fingerprint).unwrap();
+
+        let name = name.trim();
+        if !name.is_empty() {
+            molecule
+                .properties
+                .insert("name".to_owned(), name.to_owned());
+        }
+        store.write(&molecule).unwrap();
+    }
+    store.finish().unwrap();
+
+    rejected
+}
+
+/// Writes the fingerprint index (`.fp`) for a file of reaction SMILES, one reaction per line
+/// like `CC(=O)O.OCC>>CC(=O)OCC esterification`. There is no molecule store for reactions, a
+/// store from indexing the file as molecules is removed.
+/// Reactions with a molecule failing sanitization are rejected with the problems of all
+/// their molecules, lines which are not reaction SMILES without problems.
+pub fn index_reaction_file(reaction_file: &str, kind: ReactionFingerprint) -> Vec<RejectedRecord> {
+    let fi = File::open(reaction_file).expect("Could not open file");
+    let mut offset = 0;
+    let mut writer = create_index(reaction_file, &IndexContents::Reactions(kind)).unwrap();
+    // a molecule store left by indexing the file as molecules would not match the index
+    match std::fs::remove_file(store_path(reaction_file)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => panic!("Could not remove store: {}", e),
+        _ => {}
+    }
+    let mut rejected = Vec::new();
+
+    for (line_number, line) in BufReader::new(fi).lines().enumerate() {
+        let line = line.unwrap();
+        let position = offset;
         offset += line.len() + LINE_ENDING_LENGTH;
 
-        let mut buf = vec![0u8; FINGERPRINT_SIZE / 8 + 8];
+        let Ok((_, reaction)) = parse_reaction(&line) else {
+            rejected.push(RejectedRecord {
+                line_number,
+                line,
+                problems: Vec::new(),
+            });
+            continue;
+        };
+        let problems: Vec<SanitizeProblem> = reaction
+            .reactants
+            .iter()
+            .chain(&reaction.agents)
+            .chain(&reaction.products)
+            .flat_map(|m| m.sanitize())
+            .collect();
+        if !problems.is_empty() {
+            rejected.push(RejectedRecord {
+                line_number,
+                line,
+                problems,
+            });
+            continue;
+        }
+        let fingerprint = reaction_index_fingerprint(&reaction, kind);
+        write_index_item(&mut writer, position, fingerprint).unwrap();
+    }
+    writer.flush().unwrap();
+
+    rejected
+}
+
+/// Creates the `.fp` index of the file and writes its header.
+fn create_index(file: &str, contents: &IndexContents) -> std::io::Result<BufWriter<File>> {
+    let mut writer = BufWriter::new(File::create(file.to_owned() + ".fp")?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&INDEX_VERSION.to_le_bytes())?;
+    encode_into_std_write(contents, &mut writer, bincode::config::standard())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok(writer)
+}
 
-        encode_into_slice(&index_item, buf.as_mut_slice(), bincode::config::standard()).unwrap();
-        writer.write(&buf).unwrap();
+/// Opens the `.fp` index of the file, checks its header and returns the contents it
+/// records. Items follow, see `read_index_item`.
+pub(crate) fn open_index(file: &str) -> std::io::Result<(BufReader<File>, IndexContents)> {
+    let mut reader = BufReader::new(File::open(file.to_owned() + ".fp")?);
+    let mut header = [0u8; HEADER_SIZE];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "not a fingerprint index",
+        ));
+    }
+    let version = u32::from_le_bytes(header[8..].try_into().unwrap());
+    if version != INDEX_VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("unsupported fingerprint index version {}", version),
+        ));
     }
+    let contents = decode_from_std_read(&mut reader, bincode::config::standard())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok((reader, contents))
+}
+
+/// Reads the next index item, `None` at the end of the index.
+pub(crate) fn read_index_item<R: Read>(reader: &mut R) -> std::io::Result<Option<IndexItem>> {
+    let mut buf = [0u8; INDEX_ITEM_SIZE];
+    match reader.read_exact(&mut buf) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let (item, _) = decode_from_slice(&buf, bincode::config::standard())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok(Some(item))
+}
+
+fn write_index_item<W: Write>(
+    writer: &mut W,
+    position: usize,
+    fingerprint: Fingerprint,
+) -> std::io::Result<()> {
+    let index_item = IndexItem {
+        position,
+        fingerprint,
+    };
+    let mut buf = vec![0u8; INDEX_ITEM_SIZE];
+    encode_into_slice(&index_item, buf.as_mut_slice(), bincode::config::standard())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    writer.write_all(&buf)
+}
+
+/// Positions of the indexed records in the SMILES file, in record order.
+pub(crate) fn record_positions(smiles_file: &str) -> std::io::Result<Vec<usize>> {
+    let (mut reader, _) = open_index(smiles_file)?;
+    let mut positions = Vec::new();
+    while let Some(item) = read_index_item(&mut reader)? {
+        positions.push(item.position);
+    }
+    Ok(positions)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::db::index::index_file;
+    use crate::chem::standardize::StandardizeOptions;
+    use crate::chem::tautomers::TautomerOptions;
+    use crate::db::index::{
+        index_file, index_file_with_options, index_fingerprint, index_reaction_file,
+        record_positions, IndexOptions, ReactionFingerprint,
+    };
+    use crate::db::molecule_store::{store_path, MoleculeStoreReader};
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use std::fs;
 
     #[test]
     fn test_index() {
-        index_file("molecules.smi");
+        // index a copy to not race with search tests using molecules.smi.fp
+        let path = std::env::temp_dir().join("ringo_test_index.smi");
+        fs::copy("molecules.smi", &path).unwrap();
+        let path = path.to_str().unwrap();
+        assert!(index_file(path).is_empty());
+
+        let mut store = MoleculeStoreReader::open(&store_path(path)).unwrap();
+        assert_eq!(store.len(), 2);
+        let naproxen = store.read(1).unwrap();
+        assert_eq!(naproxen.properties["name"], "Naproxen");
+        assert_eq!(naproxen.formula().to_string(), "C14H14O3");
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+
+    #[test]
+    fn test_index_standardized() {
+        let path = std::env::temp_dir().join("ringo_test_index_standardized.smi");
+        fs::write(
+            &path,
+            "[O-]C(=O)C1=CC=CC=C1.[Na+] sodium benzoate\nCN(=O)=O nitromethane\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        let options = IndexOptions {
+            standardize: Some(StandardizeOptions::default()),
+            ..IndexOptions::default()
+        };
+        assert!(index_file_with_options(path, &options).is_empty());
+
+        let mut store = MoleculeStoreReader::open(&store_path(path)).unwrap();
+        assert_eq!(store.read(0).unwrap().canonical_smiles(), "O=C(O)c1ccccc1");
+        assert_eq!(store.read(1).unwrap().canonical_smiles(), "C[N+]([O-])=O");
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+
+    #[test]
+    fn test_index_rejects_invalid_valence() {
+        let path = std::env::temp_dir().join("ringo_test_index_rejects.smi");
+        fs::write(
+            &path,
+            "CCO ethanol\n[CH7] invalid\nCC(C)(C)(C)C invalid\nC1CC unclosed\nCN\n",
+        )
+        .unwrap();
+        let rejected = index_file(path.to_str().unwrap());
+        assert_eq!(rejected.len(), 3);
+        assert_eq!(rejected[0].line_number, 1);
+        assert_eq!(rejected[1].line, "CC(C)(C)(C)C invalid");
+        assert_eq!(rejected[2].line_number, 3);
+        assert!(rejected[2].problems.is_empty());
+
+        let fp_path = path.to_str().unwrap().to_owned() + ".fp";
+        assert_eq!(record_positions(path.to_str().unwrap()).unwrap().len(), 2);
+        let store = MoleculeStoreReader::open(&store_path(path.to_str().unwrap())).unwrap();
+        assert_eq!(store.len(), 2);
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&fp_path).unwrap();
+        fs::remove_file(store_path(path.to_str().unwrap())).unwrap();
+    }
+
+    #[test]
+    fn test_index_rejects_unread_input() {
+        let path = std::env::temp_dir().join("ringo_test_index_unread.smi");
+        fs::write(&path, "BC boron\nc1cc[se]c1 selenophene\nCC&O\n\nCCO\n").unwrap();
+        let path = path.to_str().unwrap();
+        let rejected = index_file(path);
+        let lines: Vec<usize> = rejected.iter().map(|r| r.line_number).collect();
+        assert_eq!(lines, vec![2, 3]);
+
+        let mut store = MoleculeStoreReader::open(&store_path(path)).unwrap();
+        assert_eq!(store.len(), 3);
+        let boron = store.read(0).unwrap();
+        assert_eq!(boron.properties["name"], "boron");
+        assert_eq!(boron.count_atoms(), 2);
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+
+    #[test]
+    fn test_index_fingerprint_tautomers() {
+        let options = IndexOptions {
+            canonical_tautomers: Some(TautomerOptions::default()),
+            ..IndexOptions::default()
+        };
+        let fingerprint = |smiles: &str| {
+            let molecule = parse_molecule(smiles).unwrap().1;
+            index_fingerprint(&molecule, &options).unwrap().0
+        };
+        assert_eq!(fingerprint("O=C1NC=CC=C1"), fingerprint("OC1=NC=CC=C1"));
+        assert_eq!(fingerprint("CB(C)C"), fingerprint("B(C)(C)C"));
+    }
+
+    #[test]
+    fn test_index_dense_fingerprints() {
+        // bits in the high half of every block give the longest item encoding
+        let path = std::env::temp_dir().join("ringo_test_index_dense.smi");
+        fs::write(
+            &path,
+            "CC1=C2C(C(=O)C3(C(CC4C(C3C(C(C2(C)C)(CC1OC(=O)C(C(C5=CC=CC=C5)NC(=O)C6=CC=CC=C6)O)O)OC(=O)C7=CC=CC=C7)(CO4)OC(=O)C)O)C)OC(=O)C paclitaxel\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        assert!(index_file(path).is_empty());
+        assert_eq!(record_positions(path).unwrap(), vec![0]);
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+
+    #[test]
+    fn test_index_rejects_other_format() {
+        let path = std::env::temp_dir().join("ringo_test_index_format.smi");
+        let path = path.to_str().unwrap();
+        let mut header = b"RINGOIDX".to_vec();
+        header.extend(0u32.to_le_bytes());
+        fs::write(path.to_owned() + ".fp", &header).unwrap();
+        let error = record_positions(path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        // index items without a header, as written by earlier versions
+        fs::write(path.to_owned() + ".fp", [0u8; 72]).unwrap();
+        assert!(record_positions(path).is_err());
+
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+    }
+
+    #[test]
+    fn test_index_reactions() {
+        let path = std::env::temp_dir().join("ringo_test_index_reactions.smi");
+        fs::write(
+            &path,
+            "CC(=O)O.OCC>>CC(=O)OCC esterification\nC[CH7]>>C invalid\nCC>C\nC=C.[H][H]>>CC\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        index_file(path);
+        assert!(fs::exists(store_path(path)).unwrap());
+        let rejected = index_reaction_file(path, ReactionFingerprint::Structural);
+        assert!(!fs::exists(store_path(path)).unwrap());
+        assert_eq!(rejected.len(), 2);
+        assert_eq!(rejected[0].line_number, 1);
+        assert!(rejected[1].problems.is_empty());
+        assert_eq!(record_positions(path).unwrap(), vec![0, 61]);
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
     }
 }
@@ -0,0 +1,104 @@
+use crate::chem::mmp::{MatchedPair, MmpIndex, MmpOptions};
+use crate::db::index::record_positions;
+use crate::db::molecule_store::{store_path, MoleculeStoreReader};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+
+/// A matched pair of records of an indexed SMILES file with their lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedPairRecord {
+    pub pair: MatchedPair,
+    pub from_line: String,
+    pub to_line: String,
+}
+
+/// Finds the matched molecular pairs of an indexed SMILES file (see `index_file`), reading
+/// the molecules from its molecule store. Record numbers are those of the index.
+pub fn matched_pairs(
+    smiles_file: &str,
+    options: &MmpOptions,
+) -> std::io::Result<Vec<MatchedPairRecord>> {
+    let mut store = MoleculeStoreReader::open(&store_path(smiles_file))?;
+    let mut index = MmpIndex::default();
+    for record in 0..store.len() {
+        index.add(record, &store.read(record)?, options);
+    }
+
+    let positions = record_positions(smiles_file)?;
+    let mut reader = BufReader::new(File::open(smiles_file)?);
+    let mut read_line = |record: usize| -> std::io::Result<String> {
+        reader.seek(SeekFrom::Start(positions[record] as u64))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        Ok(line.trim_end().to_owned())
+    };
+    index
+        .pairs()
+        .into_iter()
+        .map(|pair| {
+            Ok(MatchedPairRecord {
+                from_line: read_line(pair.from)?,
+                to_line: read_line(pair.to)?,
+                pair,
+            })
+        })
+        .collect()
+}
+
+/// Writes the pairs as tab separated `from line`, `to line`, `constant`, `from variable` and
+/// `to variable` lines, after a header line.
+pub fn write_matched_pairs<W: Write>(
+    pairs: &[MatchedPairRecord],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    writeln!(writer, "from\tto\tconstant\tfrom_variable\tto_variable")?;
+    for record in pairs {
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{}\t{}",
+            record.from_line,
+            record.to_line,
+            record.pair.constant,
+            record.pair.from_variable,
+            record.pair.to_variable
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::chem::mmp::MmpOptions;
+    use crate::db::index::index_file;
+    use crate::db::mmp::{matched_pairs, write_matched_pairs};
+    use crate::db::molecule_store::store_path;
+    use std::fs;
+
+    #[test]
+    fn test_matched_pairs() {
+        let path = std::env::temp_dir().join("ringo_test_mmp.smi");
+        fs::write(
+            &path,
+            "ClCC1=CC=CC=C1 benzyl chloride\n[CH7] invalid\nBrCc1ccccc1 benzyl bromide\nCCCC butane\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        index_file(path);
+
+        let pairs = matched_pairs(path, &MmpOptions::default()).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].from_line, "ClCC1=CC=CC=C1 benzyl chloride");
+        assert_eq!(pairs[0].to_line, "BrCc1ccccc1 benzyl bromide");
+        assert_eq!(pairs[0].pair.transformation(), "[1*]Cl>>[1*]Br");
+
+        let mut out = Vec::new();
+        write_matched_pairs(&pairs, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.lines().nth(1).unwrap().ends_with("\t[1*]Cl\t[1*]Br"));
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+}
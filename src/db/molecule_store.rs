@@ -0,0 +1,170 @@
+use crate::model::molecule::Molecule;
+use bincode::config::standard;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+
+/// Store file layout (all integers little-endian):
+/// * header: magic `RINGOMOL`, format version (u32)
+/// * records: bincode-encoded molecules, one after another
+/// * footer: offset of every record (u64 each), record count (u64)
+const MAGIC: &[u8; 8] = b"RINGOMOL";
+pub const STORE_VERSION: u32 = 1;
+const HEADER_SIZE: u64 = 12;
+
+/// Path of the store file for the SMILES file, next to its `.fp` index.
+pub fn store_path(smiles_file: &str) -> String {
+    smiles_file.to_owned() + ".mol"
+}
+
+pub struct MoleculeStoreWriter {
+    writer: BufWriter<File>,
+    offsets: Vec<u64>,
+    offset: u64,
+}
+
+impl MoleculeStoreWriter {
+    pub fn create(path: &str) -> std::io::Result<MoleculeStoreWriter> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&STORE_VERSION.to_le_bytes())?;
+        Ok(MoleculeStoreWriter {
+            writer,
+            offsets: Vec::new(),
+            offset: HEADER_SIZE,
+        })
+    }
+
+    /// Appends a molecule and returns its record number.
+    pub fn write(&mut self, molecule: &Molecule) -> std::io::Result<usize> {
+        let encoded = bincode::encode_to_vec(molecule, standard())
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        self.writer.write_all(&encoded)?;
+        self.offsets.push(self.offset);
+        self.offset += encoded.len() as u64;
+        Ok(self.offsets.len() - 1)
+    }
+
+    /// Writes the record offsets. The store is not readable until finished.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        for offset in &self.offsets {
+            self.writer.write_all(&offset.to_le_bytes())?;
+        }
+        self.writer
+            .write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        self.writer.flush()
+    }
+}
+
+pub struct MoleculeStoreReader {
+    reader: BufReader<File>,
+    offsets: Vec<u64>,
+}
+
+impl MoleculeStoreReader {
+    pub fn open(path: &str) -> std::io::Result<MoleculeStoreReader> {
+        let mut file = File::open(path)?;
+        let mut header = [0u8; HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a molecule store"));
+        }
+        let version = u32::from_le_bytes(header[8..].try_into().unwrap());
+        if version != STORE_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported molecule store version {}", version),
+            ));
+        }
+
+        let mut buf = [0u8; 8];
+        let file_len = file.seek(SeekFrom::End(-8))?;
+        file.read_exact(&mut buf)?;
+        let count = u64::from_le_bytes(buf);
+        let footer_start = count
+            .checked_mul(8)
+            .and_then(|size| file_len.checked_sub(size))
+            .filter(|start| *start >= HEADER_SIZE)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid record count"))?;
+        file.seek(SeekFrom::Start(footer_start))?;
+        let mut offsets = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            file.read_exact(&mut buf)?;
+            offsets.push(u64::from_le_bytes(buf));
+        }
+        offsets.push(footer_start);
+
+        Ok(MoleculeStoreReader {
+            reader: BufReader::new(file),
+            offsets,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn read(&mut self, record: usize) -> std::io::Result<Molecule> {
+        if record >= self.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("no record {}", record),
+            ));
+        }
+        let start = self.offsets[record];
+        let size = self.offsets[record + 1]
+            .checked_sub(start)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid record offset"))?;
+        let mut buf = vec![0u8; size as usize];
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.reader.read_exact(&mut buf)?;
+        bincode::decode_from_slice(&buf, standard())
+            .map(|(molecule, _)| molecule)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::molecule_store::{MoleculeStoreReader, MoleculeStoreWriter};
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use std::fs;
+
+    #[test]
+    fn store_write_read() {
+        let path = std::env::temp_dir().join("ringo_test_store.mol");
+        let path = path.to_str().unwrap();
+        let smiles = ["CCO", "C1=CC=CC=C1", "[13CH3][O-]"];
+        let mut writer = MoleculeStoreWriter::create(path).unwrap();
+        for s in smiles {
+            writer.write(&parse_molecule(s).unwrap().1).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let mut reader = MoleculeStoreReader::open(path).unwrap();
+        assert_eq!(reader.len(), 3);
+        for i in [2, 0, 1] {
+            let expected = parse_molecule(smiles[i]).unwrap().1;
+            assert_eq!(reader.read(i).unwrap().formula(), expected.formula());
+        }
+        assert!(reader.read(3).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn store_rejects_other_version() {
+        let path = std::env::temp_dir().join("ringo_test_store_version.mol");
+        let path = path.to_str().unwrap();
+        MoleculeStoreWriter::create(path).unwrap().finish().unwrap();
+        assert!(MoleculeStoreReader::open(path).unwrap().is_empty());
+
+        let mut data = fs::read(path).unwrap();
+        data[8] = 99;
+        fs::write(path, &data).unwrap();
+        assert!(MoleculeStoreReader::open(path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+use crate::chem::scaffold::ScaffoldGraph;
+use crate::db::molecule_store::{store_path, MoleculeStoreReader};
+
+/// How scaffolds of a molecule are related to their parents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaffoldHierarchy {
+    /// One parent per scaffold, chosen by the scaffold tree rules.
+    Tree,
+    /// All parents obtained by removing any terminal ring.
+    Network,
+}
+
+/// Builds the scaffold tree or network of all molecules of an indexed SMILES file, reading
+/// them from its molecule store (see `index_file`).
+pub fn scaffold_graph(
+    smiles_file: &str,
+    hierarchy: ScaffoldHierarchy,
+) -> std::io::Result<ScaffoldGraph> {
+    let mut store = MoleculeStoreReader::open(&store_path(smiles_file))?;
+    let mut graph = ScaffoldGraph::default();
+    for record in 0..store.len() {
+        let molecule = store.read(record)?;
+        match hierarchy {
+            ScaffoldHierarchy::Tree => graph.add_tree(&molecule),
+            ScaffoldHierarchy::Network => graph.add_network(&molecule),
+        }
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::index::index_file;
+    use crate::db::molecule_store::store_path;
+    use crate::db::scaffolds::{scaffold_graph, ScaffoldHierarchy};
+    use std::fs;
+
+    #[test]
+    fn test_scaffold_graph() {
+        let path = std::env::temp_dir().join("ringo_test_scaffolds.smi");
+        fs::copy("molecules.smi", &path).unwrap();
+        let path = path.to_str().unwrap();
+        index_file(path);
+
+        let tree = scaffold_graph(path, ScaffoldHierarchy::Tree).unwrap();
+        assert_eq!(tree.counts["c1ccccc1"], 2);
+        assert_eq!(tree.counts["c1ccc2ccccc2c1"], 1);
+        assert_eq!(tree.edges.len(), 1);
+        let network = scaffold_graph(path, ScaffoldHierarchy::Network).unwrap();
+        assert_eq!(network, tree);
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+}
@@ -1,67 +1,190 @@
-use crate::db::index_item::IndexItem;
-use crate::io::smiles::reader::molecule::parse_molecule;
+use crate::db::index::{
+    index_fingerprint, open_index, reaction_index_fingerprint, read_index_item, IndexContents,
+    FINGERPRINT_RADIUS,
+};
+use crate::db::molecule_store::{store_path, MoleculeStoreReader};
+use crate::io::smiles::reader::molecule::parse_molecule_line;
+use crate::io::smiles::reader::reaction::parse_reaction;
 use crate::math::similarity::tanimoto::tanimoto_bitset;
+use crate::model::bond::BondOrder;
+use crate::model::fingerprint::{Fingerprint, FINGERPRINT_SIZE};
+use crate::model::formula::FormulaFilter;
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::NodeIndex;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Seek};
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Seek};
 
 pub struct SearchResult {
     pub line: String,
     pub similarity: f32,
+    /// Record number in the index and in the molecule store.
+    pub record: usize,
 }
 
+/// Similarity search in a file indexed with `index_file` or `index_file_with_options`. The
+/// query is standardized and fingerprinted with the options recorded in the index. An invalid
+/// query is an `InvalidInput` error, an index of reactions an `InvalidData` error.
 pub fn similarity_search(
     smiles_file: &str,
     query: &str,
     min_similarity: f32,
     limit: usize,
-) -> Vec<SearchResult> {
-    let query = parse_molecule(query).unwrap().1;
-    let query_fp = query.ecfp(2, 512);
+) -> std::io::Result<Vec<SearchResult>> {
+    let mut query = parse_molecule_line(query)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?
+        .1;
+    let (index_reader, contents) = open_index(smiles_file)?;
+    let IndexContents::Molecules(options) = contents else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "not an index of molecules",
+        ));
+    };
+    if let Some(standardize) = &options.standardize {
+        query.standardize(standardize);
+    }
+    let query_fp =
+        index_fingerprint(&query, &options).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    search_index(smiles_file, index_reader, &query_fp, min_similarity, limit)
+}
+
+/// Similarity search in a reaction file indexed with `index_reaction_file`. The query is
+/// reaction SMILES, fingerprinted with the kind of reaction fingerprint recorded in the
+/// index. An invalid query is an `InvalidInput` error, an index of molecules an
+/// `InvalidData` error.
+pub fn reaction_similarity_search(
+    reaction_file: &str,
+    query: &str,
+    min_similarity: f32,
+    limit: usize,
+) -> std::io::Result<Vec<SearchResult>> {
+    let query = parse_reaction(query)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?
+        .1;
+    let (index_reader, contents) = open_index(reaction_file)?;
+    let IndexContents::Reactions(kind) = contents else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "not an index of reactions",
+        ));
+    };
+    let query_fp = reaction_index_fingerprint(&query, kind);
+    search_index(
+        reaction_file,
+        index_reader,
+        &query_fp,
+        min_similarity,
+        limit,
+    )
+}
 
+/// Reads the items of the opened `.fp` index and returns the lines of the records with a
+/// Tanimoto similarity of at least `min_similarity` to the query fingerprint.
+fn search_index<R: Read>(
+    smiles_file: &str,
+    mut index_reader: R,
+    query_fp: &Fingerprint,
+    min_similarity: f32,
+    limit: usize,
+) -> std::io::Result<Vec<SearchResult>> {
     // smiles file
-    let fis = File::open(&smiles_file).expect("Could not open file");
+    let fis = File::open(smiles_file)?;
     let mut reader = BufReader::new(fis);
 
-    //fingerprings file
-    let fif = File::open(smiles_file.to_owned() + ".fp").expect("Could not open file");
-    let file_len = fif.metadata().unwrap().len();
-    let index_item_size = 72u8;
-    let index_count = file_len / index_item_size as u64;
-    let mut buf_reader = BufReader::new(fif);
-
     let mut results = Vec::new();
 
-    for _ in 0..index_count {
-        // read index item from file
-        let mut buf = vec![0u8; index_item_size as usize];
-        buf_reader.read_exact(&mut buf).unwrap();
-
-        // decode index item
-        let index_item: IndexItem = bincode::decode_from_slice(&buf, bincode::config::standard())
-            .unwrap()
-            .0;
-
+    let mut record = 0;
+    while let Some(index_item) = read_index_item(&mut index_reader)? {
         // calculate similarity
         let similarity = tanimoto_bitset(&index_item.fingerprint.0, &query_fp.0);
         // print similarity if it is greater than min_similarity
         if similarity >= min_similarity {
             let position = index_item.position;
-            reader
-                .seek(std::io::SeekFrom::Start(position as u64))
-                .unwrap();
+            reader.seek(std::io::SeekFrom::Start(position as u64))?;
 
             let mut line = String::new();
-            reader.read_line(&mut line).unwrap();
+            reader.read_line(&mut line)?;
             // println!("{i} {similarity} {position} {line}");
             results.push(SearchResult {
-                line: line,
-                similarity: similarity,
+                line,
+                similarity,
+                record,
             });
 
             if results.len() >= limit {
                 break;
             }
         }
+        record += 1;
+    }
+
+    Ok(results)
+}
+
+/// Explains a similarity search hit: returns the hit molecule from the molecule store and
+/// its per-atom contributions to the similarity with the query (see
+/// `Molecule::similarity_weights`), ready for `SvgOptions::atom_weights`. The query is
+/// standardized with the options recorded in the index and, for an index of canonical
+/// tautomers, both molecules are weighted as their canonical tautomers. An invalid query is
+/// an `InvalidInput` error, an index of reactions an `InvalidData` error.
+pub fn hit_similarity_weights(
+    smiles_file: &str,
+    query: &str,
+    hit: &SearchResult,
+) -> std::io::Result<(Molecule, BTreeMap<NodeIndex, f64>)> {
+    let mut query = parse_molecule_line(query)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?
+        .1;
+    let (_, contents) = open_index(smiles_file)?;
+    let IndexContents::Molecules(options) = contents else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "not an index of molecules",
+        ));
+    };
+    if let Some(standardize) = &options.standardize {
+        query.standardize(standardize);
+    }
+    let molecule = MoleculeStoreReader::open(&store_path(smiles_file))?.read(hit.record)?;
+    let weights = match &options.canonical_tautomers {
+        // tautomers keep the atoms of the molecule, so the weights apply to the hit as stored
+        Some(tautomer_options) => {
+            let tautomer = |m: &Molecule| {
+                let mut tautomer = m.canonical_tautomer(tautomer_options);
+                for bond in tautomer.aromaticity().bonds {
+                    tautomer.graph[bond].order = BondOrder::Aromatic;
+                }
+                tautomer
+            };
+            tautomer(&molecule).similarity_weights(
+                &tautomer(&query),
+                FINGERPRINT_RADIUS,
+                FINGERPRINT_SIZE,
+            )
+        }
+        None => molecule.similarity_weights(&query, FINGERPRINT_RADIUS, FINGERPRINT_SIZE),
+    };
+    Ok((molecule, weights))
+}
+
+/// Returns lines of the SMILES file whose molecular formula matches the filter.
+pub fn formula_search(smiles_file: &str, filter: &FormulaFilter, limit: usize) -> Vec<String> {
+    let fis = File::open(smiles_file).expect("Could not open file");
+    let mut results = Vec::new();
+
+    for line in BufReader::new(fis).lines() {
+        let line = line.unwrap();
+        let molecule = match parse_molecule_line(&line) {
+            Ok((_, molecule)) => molecule,
+            Err(_) => continue,
+        };
+        if filter.matches(&molecule.formula()) {
+            results.push(line);
+            if results.len() >= limit {
+                break;
+            }
+        }
     }
 
     results
@@ -69,17 +192,141 @@ pub fn similarity_search(
 
 #[cfg(test)]
 mod test {
-    use crate::db::index::index_file;
-    use crate::db::search::similarity_search;
+    use crate::chem::tautomers::TautomerOptions;
+    use crate::db::index::{
+        index_file, index_file_with_options, index_reaction_file, IndexOptions, ReactionFingerprint,
+    };
+    use crate::db::molecule_store::store_path;
+    use crate::db::search::{
+        formula_search, hit_similarity_weights, reaction_similarity_search, similarity_search,
+    };
+    use crate::io::formula::reader::parse_formula;
+    use crate::model::formula::FormulaFilter;
+    use std::fs;
 
     #[test]
     fn test_similarity_search() {
-        index_file("molecles.smi");
-        let results = similarity_search("molecules.smi", "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O", 0.7, 100);
+        index_file("molecules.smi");
+        let results =
+            similarity_search("molecules.smi", "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O", 0.7, 100).unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].line.starts_with("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O"));
         assert_eq!(results[0].similarity, 1.0);
-        let results = similarity_search("molecules.smi", "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O", 0.5, 100);
+        assert_eq!(results[0].record, 0);
+        let results =
+            similarity_search("molecules.smi", "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O", 0.5, 100).unwrap();
         assert_eq!(results.len(), 2);
+
+        let (molecule, weights) = hit_similarity_weights(
+            "molecules.smi",
+            "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O",
+            &results[1],
+        )
+        .unwrap();
+        assert_eq!(molecule.properties["name"], "Naproxen");
+        assert_eq!(weights.len(), molecule.count_atoms());
+        assert!(weights.values().any(|w| *w > 0.0));
+        assert!(weights.values().any(|w| *w < 0.0));
+
+        let error = hit_similarity_weights("molecules.smi", "C(C", &results[1]).err();
+        assert_eq!(
+            error.map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_tautomer_search() {
+        let path = std::env::temp_dir().join("ringo_test_tautomer_search.smi");
+        fs::write(&path, "O=C1NC=CC=C1 2-pyridone\nCCO ethanol\n").unwrap();
+        let path = path.to_str().unwrap();
+        let query = "OC1=NC=CC=C1";
+
+        index_file(path);
+        let results = similarity_search(path, query, 0.9, 100).unwrap();
+        assert!(results.is_empty());
+
+        let options = IndexOptions {
+            canonical_tautomers: Some(TautomerOptions::default()),
+            ..IndexOptions::default()
+        };
+        index_file_with_options(path, &options);
+        // the query is fingerprinted with the options of the index
+        let results = similarity_search(path, query, 0.9, 100).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].similarity, 1.0);
+        assert!(results[0].line.contains("2-pyridone"));
+        // and weighted as its canonical tautomer, which has all the bits of the hit
+        let (molecule, weights) = hit_similarity_weights(path, query, &results[0]).unwrap();
+        assert_eq!(weights.len(), molecule.count_atoms());
+        assert!(weights.values().all(|w| *w > 0.0));
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+        fs::remove_file(store_path(path)).unwrap();
+    }
+
+    #[test]
+    fn test_reaction_similarity_search() {
+        let path = std::env::temp_dir().join("ringo_test_reaction_search.smi");
+        fs::write(
+            &path,
+            "CC(=O)O.NCC>>CC(=O)NCC amide\nCC(=O)O.OCC>>CC(=O)OCC ester\nC=CC.[H][H]>>CCC hydrogenation\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        let query = "CC(=O)O.OCCC>>CC(=O)OCCC";
+
+        for kind in [
+            ReactionFingerprint::Difference,
+            ReactionFingerprint::Structural,
+        ] {
+            assert!(index_reaction_file(path, kind).is_empty());
+            let results = reaction_similarity_search(path, query, 0.5, 100).unwrap();
+            assert_eq!(results.len(), 1, "{:?}", kind);
+            assert!(results[0].line.contains("ester"));
+            assert_eq!(results[0].record, 1);
+            // the extra methyl group is on both sides, so only the structures differ
+            assert_eq!(
+                results[0].similarity == 1.0,
+                kind == ReactionFingerprint::Difference
+            );
+
+            let results =
+                reaction_similarity_search(path, "CC(=O)O.OCC>>CC(=O)OCC", 1.0, 100).unwrap();
+            assert_eq!(results.len(), 1);
+        }
+
+        let error = reaction_similarity_search(path, "CC(=O)O", 0.5, 100).err();
+        assert_eq!(
+            error.map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidInput)
+        );
+        // the index is of reactions
+        let error = similarity_search(path, "CC(=O)O", 0.5, 100).err();
+        assert_eq!(
+            error.map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidData)
+        );
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(path.to_owned() + ".fp").unwrap();
+    }
+
+    #[test]
+    fn test_formula_search() {
+        let ibuprofen = parse_formula("C13H18O2").unwrap().1;
+        let results = formula_search("molecules.smi", &FormulaFilter::Exact(ibuprofen), 100);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].ends_with("Ibuprofen"));
+
+        let o3 = parse_formula("O3").unwrap().1;
+        let results = formula_search("molecules.smi", &FormulaFilter::Contains(o3), 100);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].ends_with("Naproxen"));
+
+        let max = parse_formula("C20H20O5").unwrap().1;
+        let results = formula_search("molecules.smi", &FormulaFilter::ContainedIn(max), 1);
+        assert_eq!(results.len(), 1);
     }
 }
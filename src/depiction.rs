@@ -0,0 +1,5 @@
+mod layout;
+pub mod svg;
+
+/// Bond length of generated depictions, in the usual Molfile units.
+pub const BOND_LENGTH: f64 = 1.5;
@@ -0,0 +1,552 @@
+use crate::chem::rings::Ring;
+use crate::depiction::BOND_LENGTH;
+use crate::model::bond::BondOrder;
+use crate::model::molecule::Molecule;
+use crate::model::point::Point;
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+
+/// Non-bonded atoms closer than this are considered overlapping.
+const CLASH_DISTANCE: f64 = 0.6 * BOND_LENGTH;
+/// Horizontal gap between disconnected components.
+const COMPONENT_GAP: f64 = 2.0 * BOND_LENGTH;
+const MAX_FLIP_PASSES: usize = 10;
+
+impl Molecule {
+    /// Generates 2D depiction coordinates for all atoms, replacing existing ones.
+    ///
+    /// Rings are drawn as regular polygons, fused and spiro rings are attached to the
+    /// already placed ones, chains are drawn as zig-zags, and overlaps are resolved by
+    /// flipping substituents around acyclic bonds. Disconnected components are placed
+    /// side by side from left to right.
+    pub fn generate_2d_coords(&mut self) {
+        let coords = Layout::new(self).run();
+        for (atom, point) in coords {
+            self.graph[atom].coords = Some(point);
+        }
+    }
+
+    /// Returns true if all atoms have 2D coordinates.
+    pub fn has_2d_coords(&self) -> bool {
+        self.graph.node_weights().all(|atom| atom.coords.is_some())
+    }
+}
+
+struct Layout<'a> {
+    molecule: &'a Molecule,
+    rings: &'a [Ring],
+    placed_rings: Vec<bool>,
+    coords: HashMap<NodeIndex, Point>,
+    /// Turn direction (+1 or -1) for the next chain atom, alternated to get a zig-zag.
+    turn: HashMap<NodeIndex, f64>,
+}
+
+impl<'a> Layout<'a> {
+    fn new(molecule: &'a Molecule) -> Layout<'a> {
+        let rings = &molecule.ring_info().rings;
+        Layout {
+            molecule,
+            rings,
+            placed_rings: vec![false; rings.len()],
+            coords: HashMap::new(),
+            turn: HashMap::new(),
+        }
+    }
+
+    fn run(mut self) -> HashMap<NodeIndex, Point> {
+        let mut seen: HashSet<NodeIndex> = HashSet::new();
+        let mut offset = 0.0;
+        for start in self.molecule.graph.node_indices() {
+            if seen.contains(&start) {
+                continue;
+            }
+            let component = self.component(start);
+            seen.extend(component.iter().copied());
+            self.place_component(&component);
+            self.resolve_overlaps(&component);
+
+            let points: Vec<Point> = component.iter().map(|a| self.coords[a]).collect();
+            let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+            let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+            let min_y = points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+            let max_y = points.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+            let shift = Point::new(offset - min_x, -(min_y + max_y) / 2.0);
+            for atom in &component {
+                let point = self.coords[atom] + shift;
+                self.coords.insert(*atom, point);
+            }
+            offset += max_x - min_x + COMPONENT_GAP;
+        }
+        self.coords
+    }
+
+    fn component(&self, start: NodeIndex) -> Vec<NodeIndex> {
+        let mut component = vec![start];
+        let mut visited: HashSet<NodeIndex> = HashSet::from([start]);
+        let mut i = 0;
+        while i < component.len() {
+            for neighbor in self.molecule.graph.neighbors(component[i]) {
+                if visited.insert(neighbor) {
+                    component.push(neighbor);
+                }
+            }
+            i += 1;
+        }
+        component
+    }
+
+    fn place_component(&mut self, component: &[NodeIndex]) {
+        let members: HashSet<NodeIndex> = component.iter().copied().collect();
+        // start from the most fused ring, so that ring systems grow outwards
+        let start_ring = (0..self.rings.len())
+            .filter(|r| members.contains(&self.rings[*r].atoms[0]))
+            .max_by_key(|r| (self.fused_ring_count(*r), self.rings[*r].len()));
+
+        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+        match start_ring {
+            Some(ring) => queue.extend(self.place_ring(ring)),
+            None => {
+                let start = component
+                    .iter()
+                    .copied()
+                    .find(|a| self.molecule.graph.neighbors(*a).count() <= 1)
+                    .unwrap_or(component[0]);
+                self.coords.insert(start, Point::default());
+                queue.push_back(start);
+            }
+        }
+
+        while let Some(atom) = queue.pop_front() {
+            for ring in 0..self.rings.len() {
+                if !self.placed_rings[ring] && self.rings[ring].contains_atom(atom) {
+                    queue.extend(self.place_ring(ring));
+                }
+            }
+            let mut unplaced: Vec<NodeIndex> = self
+                .molecule
+                .graph
+                .neighbors(atom)
+                .filter(|n| !self.coords.contains_key(n))
+                .collect();
+            if !unplaced.is_empty() {
+                unplaced.sort();
+                self.place_substituents(atom, &unplaced);
+                queue.extend(unplaced);
+            }
+        }
+    }
+
+    fn fused_ring_count(&self, ring: usize) -> usize {
+        let bonds = &self.rings[ring].bonds;
+        (0..self.rings.len())
+            .filter(|r| *r != ring && self.rings[*r].bonds.iter().any(|b| bonds.contains(b)))
+            .count()
+    }
+
+    /// Places the unplaced atoms of a ring as a regular polygon attached to its longest
+    /// run of already placed atoms. Returns the newly placed atoms.
+    fn place_ring(&mut self, ring: usize) -> Vec<NodeIndex> {
+        self.placed_rings[ring] = true;
+        let atoms = self.rings[ring].atoms.clone();
+        let n = atoms.len();
+        let radius = BOND_LENGTH / (2.0 * (PI / n as f64).sin());
+        let placed: Vec<bool> = atoms.iter().map(|a| self.coords.contains_key(a)).collect();
+
+        if placed.iter().all(|p| !p) {
+            for (i, atom) in atoms.iter().enumerate() {
+                let angle = PI / 2.0 + 2.0 * PI * i as f64 / n as f64;
+                self.coords.insert(*atom, Point::from_angle(angle) * radius);
+            }
+            return atoms;
+        }
+        if placed.iter().all(|p| *p) {
+            return Vec::new();
+        }
+
+        // longest cyclic run of placed atoms: atoms[start] .. atoms[start + length - 1]
+        let (start, length) = (0..n)
+            .filter(|i| placed[*i] && !placed[(i + n - 1) % n])
+            .map(|i| (i, (0..n).take_while(|j| placed[(i + j) % n]).count()))
+            .max_by_key(|(i, length)| (*length, n - i))
+            .unwrap();
+        let first_atom = atoms[start];
+        let last_atom = atoms[(start + length - 1) % n];
+        let first = self.coords[&first_atom];
+        let last = self.coords[&last_atom];
+
+        let (center, radius, start_angle, sweep) = if length == 1 {
+            let center = first + self.free_direction(first_atom) * radius;
+            (center, radius, (first - center).angle(), 2.0 * PI)
+        } else {
+            let run: Vec<NodeIndex> = (0..length).map(|j| atoms[(start + j) % n]).collect();
+            let middle = (first + last) * 0.5;
+            let chord = last - first;
+            let half = chord.length() / 2.0;
+            let normal = Point::new(-chord.y, chord.x).normalized();
+            let height = (radius * radius - half * half).max(0.0).sqrt();
+            // the new ring goes to the side opposite to the atoms around the run
+            let side = match self.centroid_around(&run, ring) {
+                Some(p) if dot(p - middle, normal) > 0.0 => -1.0,
+                _ => 1.0,
+            };
+            let center = middle + normal * (height * side);
+            let last_angle = (last - center).angle();
+            let delta = normalize_angle((first - center).angle() - last_angle);
+            let direction = if delta > 0.0 { -1.0 } else { 1.0 };
+            (
+                center,
+                radius.max(half),
+                last_angle,
+                direction * (2.0 * PI - delta.abs()),
+            )
+        };
+
+        let unplaced = n - length;
+        let mut added = Vec::new();
+        for j in 1..=unplaced {
+            let atom = atoms[(start + length - 1 + j) % n];
+            if self.coords.contains_key(&atom) {
+                continue;
+            }
+            let angle = start_angle + sweep * j as f64 / (unplaced + 1) as f64;
+            self.coords
+                .insert(atom, center + Point::from_angle(angle) * radius);
+            added.push(atom);
+        }
+        added
+    }
+
+    /// Centroid of placed atoms bonded to the run but outside the ring, and of the inner
+    /// atoms of the run.
+    fn centroid_around(&self, run: &[NodeIndex], ring: usize) -> Option<Point> {
+        let mut points: Vec<Point> = run[1..run.len() - 1]
+            .iter()
+            .map(|a| self.coords[a])
+            .collect();
+        for atom in run {
+            for neighbor in self.molecule.graph.neighbors(*atom) {
+                if !self.rings[ring].contains_atom(neighbor) {
+                    points.extend(self.coords.get(&neighbor));
+                }
+            }
+        }
+        if points.is_empty() {
+            return None;
+        }
+        let sum = points.iter().fold(Point::default(), |s, p| s + *p);
+        Some(sum * (1.0 / points.len() as f64))
+    }
+
+    fn place_substituents(&mut self, atom: NodeIndex, unplaced: &[NodeIndex]) {
+        let origin = self.coords[&atom];
+        let angles = self.placed_neighbor_angles(atom);
+        let turn = *self.turn.get(&atom).unwrap_or(&1.0);
+        let k = unplaced.len();
+
+        let new_angles: Vec<f64> = if angles.is_empty() {
+            (0..k)
+                .map(|i| PI / 6.0 + 2.0 * PI * i as f64 / k as f64)
+                .collect()
+        } else if angles.len() == 1 && k == 1 {
+            if self.is_linear(atom) {
+                vec![angles[0] + PI]
+            } else {
+                vec![angles[0] + turn * 2.0 * PI / 3.0]
+            }
+        } else {
+            let (gap_start, gap) = largest_gap(&angles);
+            let mut slots: Vec<f64> = (1..=k)
+                .map(|i| gap_start + gap * i as f64 / (k + 1) as f64)
+                .collect();
+            // the middle slots go to the largest branches to keep the backbone straight
+            let middle = gap_start + gap / 2.0;
+            slots.sort_by(|a, b| (a - middle).abs().total_cmp(&(b - middle).abs()));
+            slots
+        };
+        let mut unplaced = unplaced.to_vec();
+        if k > 1 {
+            unplaced.sort_by_key(|n| std::cmp::Reverse(self.side(*n, atom).len()));
+        }
+
+        for (neighbor, angle) in unplaced.iter().zip(new_angles) {
+            self.coords
+                .insert(*neighbor, origin + Point::from_angle(angle) * BOND_LENGTH);
+            self.turn.insert(*neighbor, -turn);
+        }
+    }
+
+    /// Sorted angles of bonds from the atom to its placed neighbors.
+    fn placed_neighbor_angles(&self, atom: NodeIndex) -> Vec<f64> {
+        let origin = self.coords[&atom];
+        let mut angles: Vec<f64> = self
+            .molecule
+            .graph
+            .neighbors(atom)
+            .filter_map(|n| self.coords.get(&n))
+            .map(|p| (*p - origin).angle())
+            .collect();
+        angles.sort_by(f64::total_cmp);
+        angles
+    }
+
+    /// Unit vector pointing into the largest free angle around the atom.
+    fn free_direction(&self, atom: NodeIndex) -> Point {
+        let angles = self.placed_neighbor_angles(atom);
+        if angles.is_empty() {
+            return Point::new(1.0, 0.0);
+        }
+        let (gap_start, gap) = largest_gap(&angles);
+        Point::from_angle(gap_start + gap / 2.0)
+    }
+
+    /// Atoms with a triple bond or two double bonds are drawn with straight bonds.
+    fn is_linear(&self, atom: NodeIndex) -> bool {
+        let orders: Vec<BondOrder> = self
+            .molecule
+            .graph
+            .edges(atom)
+            .map(|e| e.weight().order)
+            .collect();
+        orders.contains(&BondOrder::Triple)
+            || orders.iter().filter(|o| **o == BondOrder::Double).count() >= 2
+    }
+
+    /// Mirrors the smaller side of acyclic bonds while this reduces overlaps.
+    fn resolve_overlaps(&mut self, component: &[NodeIndex]) {
+        let ring_info = self.molecule.ring_info();
+        let members: HashSet<NodeIndex> = component.iter().copied().collect();
+        let bonds: Vec<(NodeIndex, NodeIndex)> = (&self.molecule.graph)
+            .edge_references()
+            .filter(|e| members.contains(&e.source()) && !ring_info.is_bond_in_ring(e.id()))
+            .map(|e| (e.source(), e.target()))
+            .filter(|(a, b)| {
+                self.molecule.graph.neighbors(*a).count() > 1
+                    && self.molecule.graph.neighbors(*b).count() > 1
+            })
+            .collect();
+
+        let mut score = self.clash_score(component);
+        for _ in 0..MAX_FLIP_PASSES {
+            if score == 0.0 {
+                break;
+            }
+            let mut improved = false;
+            for (a, b) in &bonds {
+                let side_a = self.side(*a, *b);
+                let side_b = self.side(*b, *a);
+                let moved = if side_a.len() < side_b.len() {
+                    side_a
+                } else {
+                    side_b
+                };
+                let (pa, pb) = (self.coords[a], self.coords[b]);
+                let before: Vec<Point> = moved.iter().map(|n| self.coords[n]).collect();
+                for (atom, point) in moved.iter().zip(&before) {
+                    self.coords.insert(*atom, point.reflect(pa, pb));
+                }
+                let new_score = self.clash_score(component);
+                if new_score < score - 1e-9 {
+                    score = new_score;
+                    improved = true;
+                } else {
+                    for (atom, point) in moved.iter().zip(before) {
+                        self.coords.insert(*atom, point);
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+    }
+
+    /// Atoms reachable from `atom` without passing through `excluded`.
+    fn side(&self, atom: NodeIndex, excluded: NodeIndex) -> Vec<NodeIndex> {
+        let mut side = vec![atom];
+        let mut visited: HashSet<NodeIndex> = HashSet::from([atom, excluded]);
+        let mut i = 0;
+        while i < side.len() {
+            for neighbor in self.molecule.graph.neighbors(side[i]) {
+                if visited.insert(neighbor) {
+                    side.push(neighbor);
+                }
+            }
+            i += 1;
+        }
+        side
+    }
+
+    fn clash_score(&self, component: &[NodeIndex]) -> f64 {
+        let mut score = 0.0;
+        for (i, a) in component.iter().enumerate() {
+            for b in &component[i + 1..] {
+                let distance = self.coords[a].distance(self.coords[b]);
+                if distance < CLASH_DISTANCE && !self.molecule.has_bond(*a, *b) {
+                    score += CLASH_DISTANCE - distance;
+                }
+            }
+        }
+        score
+    }
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+/// Normalizes an angle to (-pi, pi].
+fn normalize_angle(angle: f64) -> f64 {
+    let mut angle = angle % (2.0 * PI);
+    if angle <= -PI {
+        angle += 2.0 * PI;
+    } else if angle > PI {
+        angle -= 2.0 * PI;
+    }
+    angle
+}
+
+/// Largest angular gap between sorted angles, as (start angle, width).
+fn largest_gap(angles: &[f64]) -> (f64, f64) {
+    let mut best = (
+        angles[angles.len() - 1],
+        angles[0] + 2.0 * PI - angles[angles.len() - 1],
+    );
+    for pair in angles.windows(2) {
+        if pair[1] - pair[0] > best.1 {
+            best = (pair[0], pair[1] - pair[0]);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::depiction::BOND_LENGTH;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::molecule::Molecule;
+    use crate::model::point::Point;
+    use petgraph::stable_graph::NodeIndex;
+    use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+
+    fn layout(smiles: &str) -> Molecule {
+        let mut molecule = parse_molecule(smiles).unwrap().1;
+        molecule.generate_2d_coords();
+        assert!(molecule.has_2d_coords());
+        molecule
+    }
+
+    fn point(molecule: &Molecule, atom: usize) -> Point {
+        molecule.graph[NodeIndex::new(atom)].coords.unwrap()
+    }
+
+    fn assert_bond_lengths(molecule: &Molecule) {
+        for edge in (&molecule.graph).edge_references() {
+            let a = molecule.graph[edge.source()].coords.unwrap();
+            let b = molecule.graph[edge.target()].coords.unwrap();
+            assert!((a.distance(b) - BOND_LENGTH).abs() < 1e-6);
+        }
+    }
+
+    fn min_nonbonded_distance(molecule: &Molecule) -> f64 {
+        let atoms: Vec<NodeIndex> = molecule.graph.node_indices().collect();
+        let mut min = f64::INFINITY;
+        for (i, a) in atoms.iter().enumerate() {
+            for b in &atoms[i + 1..] {
+                if !molecule.has_bond(*a, *b) {
+                    let pa = molecule.graph[*a].coords.unwrap();
+                    let pb = molecule.graph[*b].coords.unwrap();
+                    min = min.min(pa.distance(pb));
+                }
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn test_chain_zigzag() {
+        let m = layout("CCCCCC");
+        assert_bond_lengths(&m);
+        // every bond angle is 120 degrees and the chain alternates sides
+        for i in 0..4 {
+            let (a, c) = (point(&m, i), point(&m, i + 2));
+            assert!((a.distance(c) - BOND_LENGTH * 3f64.sqrt()).abs() < 1e-6);
+        }
+        assert!(
+            (point(&m, 0).distance(point(&m, 4)) - 4.0 * BOND_LENGTH * 0.75f64.sqrt()).abs() < 1e-6
+        );
+    }
+
+    #[test]
+    fn test_linear() {
+        let m = layout("CC#CC");
+        assert!((point(&m, 0).distance(point(&m, 3)) - 3.0 * BOND_LENGTH).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_benzene() {
+        let m = layout("C1=CC=CC=C1");
+        assert_bond_lengths(&m);
+        let center = (0..6).fold(Point::default(), |s, i| s + point(&m, i)) * (1.0 / 6.0);
+        for i in 0..6 {
+            assert!((point(&m, i).distance(center) - BOND_LENGTH).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fused_and_spiro_rings() {
+        for smiles in [
+            "C1=CC=C2C=CC=CC2=C1",
+            "C1=CC=C2C(=C1)C=CN2",
+            "C1=CC2=CC=C3C=CC=CC3=C2C=C1",
+            "C1CC2(C1)CCCC2",
+            "C1CCC(CC1)C1CCCCC1",
+        ] {
+            let m = layout(smiles);
+            assert_bond_lengths(&m);
+            assert!(min_nonbonded_distance(&m) > 0.9 * BOND_LENGTH, "{}", smiles);
+        }
+    }
+
+    #[test]
+    fn test_no_overlaps() {
+        for smiles in [
+            "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O",
+            "CC(C)(C)C(C)(C)C(C)(C)C",
+            "CC1=C(C(=O)C2=CC=CC=C2)C=CC=C1C(=O)N(C)CCCCN(C)C",
+            "OCC(O)C(O)C(O)C(O)CO",
+        ] {
+            let m = layout(smiles);
+            assert_bond_lengths(&m);
+            assert!(
+                min_nonbonded_distance(&m) > 0.6 * BOND_LENGTH,
+                "{} {}",
+                smiles,
+                min_nonbonded_distance(&m)
+            );
+        }
+    }
+
+    #[test]
+    fn test_components_and_bridged() {
+        let mut m = parse_molecule("CCO").unwrap().1;
+        m.merge(&parse_molecule("C1=CC=CC=C1").unwrap().1);
+        m.add_atom(parse_molecule("[Cl-]").unwrap().1.graph[NodeIndex::new(0)].clone());
+        m.generate_2d_coords();
+        assert_bond_lengths(&m);
+        let max_x = |atoms: std::ops::Range<usize>| {
+            atoms
+                .map(|i| point(&m, i).x)
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        let min_x = |atoms: std::ops::Range<usize>| {
+            atoms.map(|i| point(&m, i).x).fold(f64::INFINITY, f64::min)
+        };
+        assert!(max_x(0..3) < min_x(3..9));
+        assert!(max_x(3..9) < min_x(9..10));
+
+        let m = layout("C1CC2CCC1C2");
+        assert!(min_nonbonded_distance(&m) > 0.1);
+    }
+}
@@ -0,0 +1,717 @@
+use crate::depiction::BOND_LENGTH;
+use crate::model::atom::Chirality;
+use crate::model::bond::{BondOrder, BondStereo};
+use crate::model::molecule::Molecule;
+use crate::model::point::Point;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// Drawing options for `Molecule::to_svg`.
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// Pixels per coordinate unit.
+    pub scale: f64,
+    /// Margin around the drawing, in pixels.
+    pub padding: f64,
+    pub font_size: f64,
+    pub line_width: f64,
+    /// Atoms to highlight, e.g. a substructure match.
+    pub highlight_atoms: BTreeSet<NodeIndex>,
+    /// Bonds to highlight.
+    pub highlight_bonds: BTreeSet<EdgeIndex>,
+    pub highlight_color: String,
+    /// Per-atom weights in [-1, 1] drawn as a colour map, e.g. from
+    /// `Molecule::similarity_weights`: positive in green, negative in red.
+    pub atom_weights: BTreeMap<NodeIndex, f64>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            scale: 20.0,
+            padding: 20.0,
+            font_size: 14.0,
+            line_width: 1.5,
+            highlight_atoms: BTreeSet::new(),
+            highlight_bonds: BTreeSet::new(),
+            highlight_color: "#ffb3b3".to_owned(),
+            atom_weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl Molecule {
+    /// Draws the molecule as an SVG document. Atoms without 2D coordinates are laid out
+    /// with `generate_2d_coords` on a copy of the molecule. Stereocenters without a wedge or
+    /// hash bond get one from their chirality.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        if self.has_2d_coords() {
+            Renderer::new(self, options).render()
+        } else {
+            let mut molecule = self.clone();
+            molecule.generate_2d_coords();
+            Renderer::new(&molecule, options).render()
+        }
+    }
+}
+
+/// Relative size of sub- and superscripts.
+const SCRIPT_SIZE: &str = "70%";
+/// Distance between the lines of multiple bonds, in bond lengths.
+const MULTIPLE_BOND_OFFSET: f64 = 0.18;
+/// Shortening of the inner line of ring double bonds at each end, in bond lengths.
+const INNER_BOND_SHORTENING: f64 = 0.15;
+/// Width of the wide end of wedge and hash bonds, in bond lengths.
+const WEDGE_WIDTH: f64 = 0.2;
+const HASH_LINES: usize = 6;
+
+struct Label {
+    symbol: String,
+    isotope: u16,
+    hs: u8,
+    charge: i8,
+    /// Hydrogens are written before the symbol, e.g. "HO".
+    hs_left: bool,
+}
+
+struct Renderer<'a> {
+    molecule: &'a Molecule,
+    options: &'a SvgOptions,
+    /// Atom positions in pixels, y axis pointing down.
+    points: HashMap<NodeIndex, Point>,
+    labels: HashMap<NodeIndex, Label>,
+    /// Wedge and hash bonds with the atom at their narrow end.
+    stereo_bonds: HashMap<EdgeIndex, (BondStereo, NodeIndex)>,
+    width: f64,
+    height: f64,
+    bond_length: f64,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(molecule: &'a Molecule, options: &'a SvgOptions) -> Renderer<'a> {
+        let coords: Vec<(NodeIndex, Point)> = molecule
+            .graph
+            .node_indices()
+            .map(|n| (n, molecule.graph[n].coords.unwrap()))
+            .collect();
+        let min_x = coords
+            .iter()
+            .map(|(_, p)| p.x)
+            .fold(f64::INFINITY, f64::min);
+        let max_x = coords
+            .iter()
+            .map(|(_, p)| p.x)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = coords
+            .iter()
+            .map(|(_, p)| p.y)
+            .fold(f64::INFINITY, f64::min);
+        let max_y = coords
+            .iter()
+            .map(|(_, p)| p.y)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let (min_x, max_x, min_y, max_y) = if coords.is_empty() {
+            (0.0, 0.0, 0.0, 0.0)
+        } else {
+            (min_x, max_x, min_y, max_y)
+        };
+
+        let points = coords
+            .into_iter()
+            .map(|(n, p)| {
+                let x = (p.x - min_x) * options.scale + options.padding;
+                let y = (max_y - p.y) * options.scale + options.padding;
+                (n, Point::new(x, y))
+            })
+            .collect();
+        let mut renderer = Renderer {
+            molecule,
+            options,
+            points,
+            labels: HashMap::new(),
+            stereo_bonds: HashMap::new(),
+            width: (max_x - min_x) * options.scale + 2.0 * options.padding,
+            height: (max_y - min_y) * options.scale + 2.0 * options.padding,
+            bond_length: BOND_LENGTH * options.scale,
+        };
+        for atom in molecule.graph.node_indices() {
+            if let Some(label) = renderer.label(atom) {
+                renderer.labels.insert(atom, label);
+            }
+        }
+        renderer.stereo_bonds = renderer.stereo_bonds();
+        renderer
+    }
+
+    fn render(&self) -> String {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.2} {h:.2}\">",
+            w = self.width,
+            h = self.height
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>"
+        )
+        .unwrap();
+        self.render_weights(&mut svg);
+        self.render_highlights(&mut svg);
+        for edge in (&self.molecule.graph).edge_references() {
+            self.render_bond(&mut svg, edge.id(), edge.source(), edge.target());
+        }
+        for atom in self.molecule.graph.node_indices() {
+            self.render_label(&mut svg, atom);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn label(&self, atom: NodeIndex) -> Option<Label> {
+        let a = &self.molecule.graph[atom];
+        let neighbors: Vec<NodeIndex> = self.molecule.graph.neighbors(atom).collect();
+        if a.element.atomic_number == 6 && a.charge == 0 && a.isotope == 0 && !neighbors.is_empty()
+        {
+            return None;
+        }
+        let position = self.points[&atom];
+        let direction: f64 = neighbors
+            .iter()
+            .map(|n| self.points[n].x - position.x)
+            .sum();
+        Some(Label {
+            symbol: a.element.symbol().unwrap_or("*").to_owned(),
+            isotope: a.isotope,
+            hs: self.molecule.total_hydrogens(atom),
+            charge: a.charge,
+            hs_left: direction > 1e-6 && a.isotope == 0,
+        })
+    }
+
+    /// Bonds drawn as wedges or hashes: those with a `Bond::stereo`, and for every other
+    /// stereocenter one single bond, preferably to a terminal atom outside of rings, drawn so
+    /// that the 2D coordinates give the chirality of the atom.
+    fn stereo_bonds(&self) -> HashMap<EdgeIndex, (BondStereo, NodeIndex)> {
+        let graph = &self.molecule.graph;
+        let mut stereo_bonds: HashMap<EdgeIndex, (BondStereo, NodeIndex)> = graph
+            .edge_references()
+            .filter(|e| e.weight().stereo != BondStereo::None)
+            .map(|e| (e.id(), (e.weight().stereo, e.source())))
+            .collect();
+        let ring_info = self.molecule.ring_info();
+        for atom in graph.node_indices() {
+            let chirality = graph[atom].chirality;
+            let neighbors = self.molecule.stereo_neighbors(atom);
+            if chirality == Chirality::None
+                || neighbors.len() != 4
+                || graph
+                    .edges(atom)
+                    .any(|e| e.weight().stereo != BondStereo::None)
+            {
+                continue;
+            }
+            let mut candidates: Vec<(EdgeIndex, NodeIndex)> = graph
+                .edges(atom)
+                .filter(|e| e.weight().order == BondOrder::Single)
+                .filter(|e| !stereo_bonds.contains_key(&e.id()))
+                .map(|e| {
+                    (
+                        e.id(),
+                        if e.source() == atom {
+                            e.target()
+                        } else {
+                            e.source()
+                        },
+                    )
+                })
+                .collect();
+            candidates.sort_by_key(|(edge, other)| {
+                (
+                    graph[*other].chirality != Chirality::None,
+                    ring_info.is_bond_in_ring(*edge),
+                    graph.neighbors(*other).count(),
+                    *other,
+                )
+            });
+            for (edge, other) in candidates {
+                let volume = self.signed_volume(atom, &neighbors, other);
+                if volume.abs() < 1e-6 {
+                    continue;
+                }
+                // a negative volume is anticlockwise when looking from the first neighbor
+                let stereo = if (volume < 0.0) == (chirality == Chirality::AntiClockwise) {
+                    BondStereo::Wedge
+                } else {
+                    BondStereo::Hash
+                };
+                stereo_bonds.insert(edge, (stereo, atom));
+                break;
+            }
+        }
+        stereo_bonds
+    }
+
+    /// Signed volume of the four neighbors of a stereocenter in the order of
+    /// `Molecule::stereo_neighbors`, with `raised` pointing towards the viewer, the other atoms
+    /// in the plane and an implicit hydrogen opposite to them.
+    fn signed_volume(
+        &self,
+        atom: NodeIndex,
+        neighbors: &[Option<NodeIndex>],
+        raised: NodeIndex,
+    ) -> f64 {
+        let center = self.molecule.graph[atom].coords.unwrap();
+        let mut vectors: Vec<Option<[f64; 3]>> = neighbors
+            .iter()
+            .map(|n| {
+                n.map(|n| {
+                    let v = (self.molecule.graph[n].coords.unwrap() - center).normalized();
+                    [v.x, v.y, if n == raised { 1.0 } else { 0.0 }]
+                })
+            })
+            .collect();
+        let sum = vectors
+            .iter()
+            .flatten()
+            .fold([0.0; 3], |s, v| [s[0] + v[0], s[1] + v[1], s[2] + v[2]]);
+        for v in vectors.iter_mut().filter(|v| v.is_none()) {
+            *v = Some(sum.map(|c| -c));
+        }
+        let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|i| vectors[i].unwrap());
+        let [a, b, c] = [p1, p2, p3].map(|p| [p[0] - p0[0], p[1] - p0[1], p[2] - p0[2]]);
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    }
+
+    fn render_weights(&self, svg: &mut String) {
+        for (atom, weight) in &self.options.atom_weights {
+            let p = match self.points.get(atom) {
+                Some(p) => p,
+                None => continue,
+            };
+            let color = if *weight >= 0.0 { "#00a000" } else { "#e00000" };
+            writeln!(
+                svg,
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"{}\" fill-opacity=\"{:.2}\"/>",
+                p.x,
+                p.y,
+                0.45 * self.bond_length,
+                color,
+                weight.abs().min(1.0) * 0.6
+            )
+            .unwrap();
+        }
+    }
+
+    fn render_highlights(&self, svg: &mut String) {
+        let color = &self.options.highlight_color;
+        for bond in &self.options.highlight_bonds {
+            if let Some((a, b)) = self.molecule.graph.edge_endpoints(*bond) {
+                let (pa, pb) = (self.points[&a], self.points[&b]);
+                writeln!(
+                    svg,
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" stroke-width=\"{:.2}\" stroke-linecap=\"round\"/>",
+                    pa.x, pa.y, pb.x, pb.y, color, 0.4 * self.bond_length
+                )
+                .unwrap();
+            }
+        }
+        for atom in &self.options.highlight_atoms {
+            if let Some(p) = self.points.get(atom) {
+                writeln!(
+                    svg,
+                    "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"{}\"/>",
+                    p.x,
+                    p.y,
+                    0.3 * self.bond_length,
+                    color
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn render_bond(&self, svg: &mut String, bond: EdgeIndex, a: NodeIndex, b: NodeIndex) {
+        let weight = &self.molecule.graph[bond];
+        let (stereo, narrow) = self
+            .stereo_bonds
+            .get(&bond)
+            .copied()
+            .unwrap_or((BondStereo::None, a));
+        let (start, end) = if narrow == a {
+            self.trimmed(a, b)
+        } else {
+            self.trimmed(b, a)
+        };
+        let normal = perpendicular(end - start);
+        let offset = MULTIPLE_BOND_OFFSET * self.bond_length;
+
+        match stereo {
+            BondStereo::Wedge if weight.order == BondOrder::Single => {
+                let half = normal * (WEDGE_WIDTH * self.bond_length / 2.0);
+                let (c, d) = (end + half, end - half);
+                writeln!(
+                    svg,
+                    "<polygon points=\"{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}\" fill=\"#000000\"/>",
+                    start.x, start.y, c.x, c.y, d.x, d.y
+                )
+                .unwrap();
+                return;
+            }
+            BondStereo::Hash if weight.order == BondOrder::Single => {
+                for i in 0..HASH_LINES {
+                    let t = i as f64 / (HASH_LINES - 1) as f64;
+                    let center = start + (end - start) * t;
+                    let half = normal * (WEDGE_WIDTH * self.bond_length / 2.0 * t.max(0.1));
+                    self.line(svg, center + half, center - half, None);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        match weight.order {
+            BondOrder::Single => self.line(svg, start, end, None),
+            BondOrder::Triple => {
+                self.line(svg, start, end, None);
+                self.line(svg, start + normal * offset, end + normal * offset, None);
+                self.line(svg, start - normal * offset, end - normal * offset, None);
+            }
+            BondOrder::Double | BondOrder::Aromatic => {
+                let dash = match weight.order {
+                    BondOrder::Aromatic => Some(0.1 * self.bond_length),
+                    _ => None,
+                };
+                match self.double_bond_side(bond, a, b, normal) {
+                    Some(side) => {
+                        // main line plus a shorter line on the inner side
+                        let shift = normal * (offset * side);
+                        let shorten =
+                            (end - start).normalized() * (INNER_BOND_SHORTENING * self.bond_length);
+                        self.line(svg, start, end, None);
+                        self.line(svg, start + shift + shorten, end + shift - shorten, dash);
+                    }
+                    None => {
+                        let shift = normal * (offset / 2.0);
+                        self.line(svg, start + shift, end + shift, None);
+                        self.line(svg, start - shift, end - shift, dash);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Side (+1 or -1 along `normal`) for the second line of a double bond, or `None` to
+    /// draw both lines centered on the bond.
+    fn double_bond_side(
+        &self,
+        bond: EdgeIndex,
+        a: NodeIndex,
+        b: NodeIndex,
+        normal: Point,
+    ) -> Option<f64> {
+        let origin = self.points[&a];
+        let side_of = |p: Point| -> f64 {
+            let v = p - origin;
+            (v.x * normal.x + v.y * normal.y).signum()
+        };
+        let ring_info = self.molecule.ring_info();
+        if let Some(ring) = ring_info.rings.iter().find(|r| r.contains_bond(bond)) {
+            let sum = ring
+                .atoms
+                .iter()
+                .fold(Point::default(), |s, n| s + self.points[n]);
+            return Some(side_of(sum * (1.0 / ring.len() as f64)));
+        }
+
+        let others_a: Vec<NodeIndex> = self
+            .molecule
+            .graph
+            .neighbors(a)
+            .filter(|n| *n != b)
+            .collect();
+        let others_b: Vec<NodeIndex> = self
+            .molecule
+            .graph
+            .neighbors(b)
+            .filter(|n| *n != a)
+            .collect();
+        if others_a.is_empty() || others_b.is_empty() {
+            return None;
+        }
+        let total: f64 = others_a
+            .iter()
+            .chain(&others_b)
+            .map(|n| side_of(self.points[n]))
+            .sum();
+        Some(if total < 0.0 { -1.0 } else { 1.0 })
+    }
+
+    /// Bond end points, shortened at labelled atoms.
+    fn trimmed(&self, a: NodeIndex, b: NodeIndex) -> (Point, Point) {
+        let (pa, pb) = (self.points[&a], self.points[&b]);
+        let direction = (pb - pa).normalized();
+        let margin = 0.55 * self.options.font_size;
+        let start = if self.labels.contains_key(&a) {
+            pa + direction * margin
+        } else {
+            pa
+        };
+        let end = if self.labels.contains_key(&b) {
+            pb - direction * margin
+        } else {
+            pb
+        };
+        (start, end)
+    }
+
+    fn line(&self, svg: &mut String, a: Point, b: Point, dash: Option<f64>) {
+        let dash = match dash {
+            Some(length) => format!(" stroke-dasharray=\"{:.2}\"", length),
+            None => String::new(),
+        };
+        writeln!(
+            svg,
+            "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"#000000\" stroke-width=\"{:.2}\" stroke-linecap=\"round\"{}/>",
+            a.x, a.y, b.x, b.y, self.options.line_width, dash
+        )
+        .unwrap();
+    }
+
+    fn render_label(&self, svg: &mut String, atom: NodeIndex) {
+        let label = match self.labels.get(&atom) {
+            Some(label) => label,
+            None => return,
+        };
+        let p = self.points[&atom];
+        let font_size = self.options.font_size;
+        let color = element_color(self.molecule.graph[atom].element.atomic_number);
+        let text = |svg: &mut String, x: f64, anchor: &str, content: &str| {
+            writeln!(
+                svg,
+                "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{:.1}\" fill=\"{}\" text-anchor=\"{}\" dominant-baseline=\"central\">{}</text>",
+                x, p.y, font_size, color, anchor, content
+            )
+            .unwrap();
+        };
+
+        // the element symbol is centered on the atom, the rest is attached to its sides
+        let half_width = 0.3 * font_size * label.symbol.len() as f64;
+        let mut symbol = String::new();
+        if label.isotope != 0 {
+            symbol.push_str(&superscript(&label.isotope.to_string()));
+        }
+        symbol.push_str(&label.symbol);
+        text(svg, p.x, "middle", &symbol);
+
+        let hydrogens = match label.hs {
+            0 => String::new(),
+            1 => "H".to_owned(),
+            n => format!("H{}", subscript(&n.to_string())),
+        };
+        let charge = match label.charge {
+            0 => String::new(),
+            1 => superscript("+"),
+            -1 => superscript("\u{2212}"),
+            c if c > 0 => superscript(&format!("{}+", c)),
+            c => superscript(&format!("{}\u{2212}", -c)),
+        };
+        if label.hs_left {
+            if !hydrogens.is_empty() {
+                text(svg, p.x - half_width, "end", &hydrogens);
+            }
+            if !charge.is_empty() {
+                text(svg, p.x + half_width, "start", &charge);
+            }
+        } else if !hydrogens.is_empty() || !charge.is_empty() {
+            text(svg, p.x + half_width, "start", &(hydrogens + &charge));
+        }
+    }
+}
+
+/// Unit vector perpendicular to the given one.
+fn perpendicular(v: Point) -> Point {
+    Point::new(-v.y, v.x).normalized()
+}
+
+fn superscript(text: &str) -> String {
+    format!(
+        "<tspan baseline-shift=\"super\" font-size=\"{}\">{}</tspan>",
+        SCRIPT_SIZE, text
+    )
+}
+
+fn subscript(text: &str) -> String {
+    format!(
+        "<tspan baseline-shift=\"sub\" font-size=\"{}\">{}</tspan>",
+        SCRIPT_SIZE, text
+    )
+}
+
+fn element_color(atomic_number: u8) -> &'static str {
+    match atomic_number {
+        7 => "#2233cc",
+        8 => "#dd0000",
+        9 | 17 => "#228b22",
+        15 => "#ff8000",
+        16 => "#b8860b",
+        35 => "#a52a2a",
+        53 => "#940094",
+        _ => "#000000",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::depiction::svg::SvgOptions;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::bond::BondStereo;
+    use crate::model::point::Point;
+    use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+
+    fn count(svg: &str, pattern: &str) -> usize {
+        svg.matches(pattern).count()
+    }
+
+    #[test]
+    fn test_labels() {
+        let m = parse_molecule("CCO").unwrap().1;
+        let svg = m.to_svg(&SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(count(&svg, "<text"), 2);
+        assert!(svg.contains(">O</text>"));
+        assert!(svg.contains(">H</text>"));
+        assert_eq!(count(&svg, "<line"), 2);
+
+        let m = parse_molecule("[NH4+]").unwrap().1;
+        let svg = m.to_svg(&SvgOptions::default());
+        assert!(svg.contains(">N</text>"));
+        assert!(svg.contains("H<tspan baseline-shift=\"sub\" font-size=\"70%\">4</tspan>"));
+        assert!(svg.contains("<tspan baseline-shift=\"super\" font-size=\"70%\">+</tspan>"));
+
+        let m = parse_molecule("[13CH4]").unwrap().1;
+        let svg = m.to_svg(&SvgOptions::default());
+        assert!(svg.contains(">13</tspan>C</text>"));
+    }
+
+    #[test]
+    fn test_hydrogens_left() {
+        let mut m = parse_molecule("OC").unwrap().1;
+        m.graph[NodeIndex::new(0)].coords = Some(Point::new(0.0, 0.0));
+        m.graph[NodeIndex::new(1)].coords = Some(Point::new(1.5, 0.0));
+        let svg = m.to_svg(&SvgOptions::default());
+        assert!(svg.contains("text-anchor=\"end\" dominant-baseline=\"central\">H</text>"));
+    }
+
+    #[test]
+    fn test_bond_styles() {
+        let svg = parse_molecule("C1=CC=CC=C1")
+            .unwrap()
+            .1
+            .to_svg(&SvgOptions::default());
+        assert_eq!(count(&svg, "<line"), 9);
+        assert_eq!(count(&svg, "<text"), 0);
+
+        let svg = parse_molecule("CC#N")
+            .unwrap()
+            .1
+            .to_svg(&SvgOptions::default());
+        assert_eq!(count(&svg, "<line"), 4);
+
+        let svg = parse_molecule("C=C")
+            .unwrap()
+            .1
+            .to_svg(&SvgOptions::default());
+        assert_eq!(count(&svg, "<line"), 2);
+
+        let mut m = parse_molecule("C1CCCCC1").unwrap().1;
+        for bond in m.graph.edge_weights_mut() {
+            bond.order = crate::model::bond::BondOrder::Aromatic;
+        }
+        let svg = m.to_svg(&SvgOptions::default());
+        assert_eq!(count(&svg, "stroke-dasharray"), 6);
+    }
+
+    #[test]
+    fn test_stereo_bonds() {
+        let mut m = parse_molecule("CC(O)N").unwrap().1;
+        m.graph[EdgeIndex::new(1)].stereo = BondStereo::Wedge;
+        m.graph[EdgeIndex::new(2)].stereo = BondStereo::Hash;
+        let svg = m.to_svg(&SvgOptions::default());
+        assert_eq!(count(&svg, "<polygon"), 1);
+        assert_eq!(count(&svg, "<line"), 1 + 6);
+    }
+
+    #[test]
+    fn test_stereo_bonds_from_chirality() {
+        let svg = parse_molecule("C[C@H](O)N")
+            .unwrap()
+            .1
+            .to_svg(&SvgOptions::default());
+        // a wedge, or a hash of six lines next to the two plain bonds
+        assert!(count(&svg, "<polygon") == 1 || count(&svg, "<line") == 2 + 6);
+
+        // (R)-bromochlorofluoromethane with the fluorine up: the hydrogen points away when the
+        // fluorine points towards the viewer
+        for (smiles, polygons, lines) in [("F[C@H](Cl)Br", 1, 2), ("F[C@@H](Cl)Br", 0, 2 + 6)] {
+            let mut m = parse_molecule(smiles).unwrap().1;
+            for (atom, (x, y)) in [(0.0, 1.5), (0.0, 0.0), (-1.3, -0.75), (1.3, -0.75)]
+                .into_iter()
+                .enumerate()
+            {
+                m.graph[NodeIndex::new(atom)].coords = Some(Point::new(x, y));
+            }
+            let svg = m.to_svg(&SvgOptions::default());
+            assert_eq!(count(&svg, "<polygon"), polygons, "{}", smiles);
+            assert_eq!(count(&svg, "<line"), lines, "{}", smiles);
+        }
+
+        // a bond with a stereo is kept
+        let mut m = parse_molecule("C[C@H](O)N").unwrap().1;
+        m.graph[EdgeIndex::new(1)].stereo = BondStereo::Hash;
+        let svg = m.to_svg(&SvgOptions::default());
+        assert_eq!(count(&svg, "<polygon"), 0);
+        assert_eq!(count(&svg, "<line"), 2 + 6);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let m = parse_molecule("CCO").unwrap().1;
+        let options = SvgOptions {
+            highlight_atoms: [NodeIndex::new(1), NodeIndex::new(2)].into(),
+            highlight_bonds: [EdgeIndex::new(1)].into(),
+            highlight_color: "#00ff00".to_owned(),
+            ..SvgOptions::default()
+        };
+        let svg = m.to_svg(&options);
+        assert_eq!(count(&svg, "<circle"), 2);
+        assert_eq!(count(&svg, "#00ff00"), 3);
+    }
+
+    #[test]
+    fn test_atom_weights() {
+        let m = parse_molecule("CCO").unwrap().1;
+        let options = SvgOptions {
+            atom_weights: [(NodeIndex::new(0), -0.5), (NodeIndex::new(2), 1.0)].into(),
+            ..SvgOptions::default()
+        };
+        let svg = m.to_svg(&options);
+        assert!(svg.contains("fill=\"#e00000\" fill-opacity=\"0.30\""));
+        assert!(svg.contains("fill=\"#00a000\" fill-opacity=\"0.60\""));
+    }
+
+    #[test]
+    fn test_existing_coords() {
+        let mut m = parse_molecule("CC").unwrap().1;
+        m.graph[NodeIndex::new(0)].coords = Some(Point::new(0.0, 0.0));
+        m.graph[NodeIndex::new(1)].coords = Some(Point::new(3.0, 0.0));
+        let svg = m.to_svg(&SvgOptions::default());
+        assert!(svg.contains("width=\"100\" height=\"40\""));
+        assert!(svg.contains("x1=\"20.00\" y1=\"20.00\" x2=\"80.00\" y2=\"20.00\""));
+    }
+}
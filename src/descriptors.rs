@@ -1,2 +1,6 @@
 mod fingerprints;
+mod formula;
+mod isotope_pattern;
+mod ring_descriptors;
+mod similarity_map;
 mod weight;
@@ -1 +1,2 @@
 mod ecfp;
+mod reaction;
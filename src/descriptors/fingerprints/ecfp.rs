@@ -1,6 +1,6 @@
 use crate::model::atom::Atom;
 use crate::model::bond::Bond;
-use crate::model::fingerprint::{Fingerprint, FINGERPRINT_SIZE};
+use crate::model::fingerprint::{BitProvenance, Fingerprint, FINGERPRINT_SIZE};
 use crate::model::molecule::Molecule;
 use fixedbitset::FixedBitSet;
 use petgraph::graph::NodeIndex;
@@ -10,6 +10,26 @@ use std::hash::{DefaultHasher, Hasher};
 
 impl Molecule {
     pub fn ecfp(&self, radius: usize, fp_length: usize) -> Fingerprint {
+        self.ecfp_fingerprint(radius, fp_length, None)
+    }
+
+    /// Same fingerprint as `ecfp`, together with the atom environments that set each bit.
+    pub fn ecfp_with_provenance(
+        &self,
+        radius: usize,
+        fp_length: usize,
+    ) -> (Fingerprint, BitProvenance) {
+        let mut provenance = BitProvenance::default();
+        let fp = self.ecfp_fingerprint(radius, fp_length, Some(&mut provenance));
+        (fp, provenance)
+    }
+
+    fn ecfp_fingerprint(
+        &self,
+        radius: usize,
+        fp_length: usize,
+        mut provenance: Option<&mut BitProvenance>,
+    ) -> Fingerprint {
         let mut fp = FixedBitSet::with_capacity(FINGERPRINT_SIZE);
 
         for node in self.graph.node_indices() {
@@ -21,6 +41,8 @@ impl Molecule {
                 &mut fp,
                 fp_length,
                 &mut DefaultHasher::new(),
+                &mut Vec::new(),
+                provenance.as_deref_mut(),
             );
         }
 
@@ -28,6 +50,7 @@ impl Molecule {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn ecfp_recursive(
     graph: &StableGraph<Atom, Bond, Undirected>,
     radius: usize,
@@ -36,19 +59,31 @@ fn ecfp_recursive(
     fp: &mut FixedBitSet,
     fp_length: usize,
     hasher: &mut DefaultHasher,
+    path: &mut Vec<NodeIndex>,
+    mut provenance: Option<&mut BitProvenance>,
 ) {
     if depth > radius {
         return;
     }
+    path.push(node);
 
     let atom = graph.node_weight(node).unwrap();
     hasher.write_u8(atom.element.atomic_number);
-    hasher.write_u8(atom.isotope);
+    // the high byte only for mass numbers above 255, so that other fingerprints do not change
+    let [isotope_low, isotope_high] = atom.isotope.to_le_bytes();
+    hasher.write_u8(isotope_low);
+    if isotope_high != 0 {
+        hasher.write_u8(isotope_high);
+    }
     hasher.write_i8(atom.charge);
     hasher.write_u8(atom.hs);
 
     let value = hasher.clone().finish();
-    fp.insert(value as usize % fp_length);
+    let bit = value as usize % fp_length;
+    fp.insert(bit);
+    if let Some(provenance) = provenance.as_deref_mut() {
+        provenance.add(bit, path.clone());
+    }
 
     for edge in graph.edges(node) {
         let bond = edge.weight();
@@ -60,8 +95,19 @@ fn ecfp_recursive(
             edge.source()
         };
 
-        ecfp_recursive(graph, radius, depth + 1, target, fp, fp_length, hasher);
+        ecfp_recursive(
+            graph,
+            radius,
+            depth + 1,
+            target,
+            fp,
+            fp_length,
+            hasher,
+            path,
+            provenance.as_deref_mut(),
+        );
     }
+    path.pop();
 }
 
 #[cfg(test)]
@@ -81,4 +127,23 @@ mod test {
         let sim = tanimoto_bitset(&ecfp_ibuprofen.0, &ecfp_naproxen.0);
         assert!(0.53 < sim && sim < 0.54);
     }
+
+    #[test]
+    fn test_ecfp_provenance() {
+        let m = parse_molecule("CC(=O)O").unwrap().1;
+        let (fp, provenance) = m.ecfp_with_provenance(2, 512);
+        assert_eq!(fp.0, m.ecfp(2, 512).0);
+        assert_eq!(
+            provenance.environments.keys().copied().collect::<Vec<_>>(),
+            fp.0.ones().collect::<Vec<_>>()
+        );
+        for environments in provenance.environments.values() {
+            for environment in environments {
+                assert!(!environment.is_empty() && environment.len() <= 2);
+                if environment.len() == 2 {
+                    assert!(m.has_bond(environment[0], environment[1]));
+                }
+            }
+        }
+    }
 }
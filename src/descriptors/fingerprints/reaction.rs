@@ -0,0 +1,68 @@
+use crate::model::fingerprint::{Fingerprint, FINGERPRINT_SIZE};
+use crate::model::molecule::Molecule;
+use crate::model::reaction::Reaction;
+use fixedbitset::FixedBitSet;
+
+impl Reaction {
+    /// Difference fingerprint: ECFP bits set by the products but not by the reactants, or by
+    /// the reactants but not by the products, i.e. the atom environments changed by the
+    /// reaction. Agents are left out.
+    pub fn difference_fingerprint(&self, radius: usize, fp_length: usize) -> Fingerprint {
+        let mut fp = side_ecfp(&self.reactants, radius, fp_length);
+        fp.symmetric_difference_with(&side_ecfp(&self.products, radius, fp_length));
+        Fingerprint(fp)
+    }
+
+    /// Structural fingerprint: the ECFP of the reactants in the first half of the bits and
+    /// the ECFP of the products in the second half. Agents are left out.
+    pub fn structural_fingerprint(&self, radius: usize, fp_length: usize) -> Fingerprint {
+        let half = fp_length / 2;
+        let mut fp = side_ecfp(&self.reactants, radius, half);
+        fp.extend(
+            side_ecfp(&self.products, radius, half)
+                .ones()
+                .map(|b| b + half),
+        );
+        Fingerprint(fp)
+    }
+}
+
+/// Union of the ECFP bits of the molecules.
+fn side_ecfp(molecules: &[Molecule], radius: usize, fp_length: usize) -> FixedBitSet {
+    let mut fp = FixedBitSet::with_capacity(FINGERPRINT_SIZE);
+    for molecule in molecules {
+        fp.union_with(&molecule.ecfp(radius, fp_length).0);
+    }
+    fp
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::smiles::reader::reaction::parse_reaction;
+    use crate::math::similarity::tanimoto::tanimoto_bitset;
+
+    #[test]
+    fn test_reaction_fingerprints() {
+        let esterification = parse_reaction("CC(=O)O.OCC>>CC(=O)OCC").unwrap().1;
+        let propyl = parse_reaction("CC(=O)O.OCCC>>CC(=O)OCCC").unwrap().1;
+        let amide = parse_reaction("CC(=O)O.NCC>>CC(=O)NCC").unwrap().1;
+
+        let difference = esterification.difference_fingerprint(2, 512);
+        assert!(difference.0.count_ones(..) > 0);
+        // agents do not change the fingerprint, reversing the reaction does not either
+        let catalyzed = parse_reaction("CC(=O)O.OCC>[H+]>CC(=O)OCC").unwrap().1;
+        assert_eq!(catalyzed.difference_fingerprint(2, 512).0, difference.0);
+        let reverse = parse_reaction("CC(=O)OCC>>CC(=O)O.OCC").unwrap().1;
+        assert_eq!(reverse.difference_fingerprint(2, 512).0, difference.0);
+        let similar = tanimoto_bitset(&difference.0, &propyl.difference_fingerprint(2, 512).0);
+        let different = tanimoto_bitset(&difference.0, &amide.difference_fingerprint(2, 512).0);
+        assert!(similar > different);
+
+        let structural = esterification.structural_fingerprint(2, 512);
+        assert!(structural.0.ones().any(|b| b < 256));
+        assert!(structural.0.ones().any(|b| b >= 256));
+        assert!(structural.0.ones().all(|b| b < 512));
+        // the reverse reaction has the halves swapped
+        assert_ne!(reverse.structural_fingerprint(2, 512).0, structural.0);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::model::formula::Formula;
+use crate::model::molecule::Molecule;
+
+impl Molecule {
+    /// Molecular formula including explicit and implicit hydrogen counts.
+    pub fn formula(&self) -> Formula {
+        let mut formula = Formula::new();
+        for node in self.graph.node_indices() {
+            let atom = &self.graph[node];
+            formula.add(atom.element.atomic_number, atom.isotope, 1);
+            formula.add(1, 0, self.total_hydrogens(node) as u32);
+            formula.charge += atom.charge as i32;
+        }
+        formula
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+
+    fn formula(smiles: &str) -> String {
+        parse_molecule(smiles).unwrap().1.formula().to_string()
+    }
+
+    #[test]
+    fn formula_molecule() {
+        assert_eq!(formula("CCO"), "C2H6O");
+        assert_eq!(formula("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O"), "C13H18O2");
+        assert_eq!(formula("[13CH3]C[2H]"), "C[13C]H5[2H]");
+        assert_eq!(formula("C[N+](C)(C)C"), "C4H12N+");
+    }
+}
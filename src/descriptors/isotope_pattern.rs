@@ -0,0 +1,201 @@
+use crate::model::adduct::ELECTRON_MASS;
+use crate::model::element::Element;
+use crate::model::formula::Formula;
+use crate::model::isotope_pattern::{IsotopePatternOptions, Peak};
+use crate::model::molecule::Molecule;
+
+/// Intermediate peaks are pruned with a lower threshold to keep the final pattern accurate.
+const INTERMEDIATE_PRUNING_FACTOR: f64 = 1e-3;
+
+impl Formula {
+    /// Theoretical isotope distribution. Unlabelled atoms follow the natural isotopic
+    /// composition, labelled atoms contribute their isotope only. For charged formulas
+    /// peaks are given as m/z corrected for electron mass.
+    /// Returns None if the formula contains unknown elements, isotopes, or elements
+    /// without natural isotopic composition.
+    pub fn isotope_pattern(&self, options: &IsotopePatternOptions) -> Option<Vec<Peak>> {
+        let threshold = options.min_intensity * INTERMEDIATE_PRUNING_FACTOR;
+        let mut pattern = vec![Peak {
+            mz: 0.0,
+            intensity: 1.0,
+        }];
+        for ((z, isotope), count) in &self.counts {
+            let element = Element { atomic_number: *z };
+            let distribution: Vec<Peak> = if *isotope == 0 {
+                element
+                    .isotopes()
+                    .iter()
+                    .filter(|i| i.abundance > 0.0)
+                    .map(|i| Peak {
+                        mz: i.exact_mass,
+                        intensity: i.abundance,
+                    })
+                    .collect()
+            } else {
+                vec![Peak {
+                    mz: element.isotope(*isotope)?.exact_mass,
+                    intensity: 1.0,
+                }]
+            };
+            if distribution.is_empty() {
+                return None;
+            }
+            let power = power(&distribution, *count, threshold, options.resolution);
+            pattern = convolve(&pattern, &power, threshold, options.resolution);
+        }
+
+        let mut pattern = merge(pattern, options.resolution);
+        let max = pattern.iter().map(|p| p.intensity).fold(0.0, f64::max);
+        pattern.retain(|p| p.intensity >= options.min_intensity * max);
+        let charge = self.charge as f64;
+        for peak in pattern.iter_mut() {
+            peak.intensity /= max;
+            peak.mz -= charge * ELECTRON_MASS;
+            if self.charge != 0 {
+                peak.mz /= charge.abs();
+            }
+        }
+        Some(pattern)
+    }
+}
+
+impl Molecule {
+    /// Theoretical isotope distribution of the molecular formula.
+    pub fn isotope_pattern(&self, options: &IsotopePatternOptions) -> Option<Vec<Peak>> {
+        self.formula().isotope_pattern(options)
+    }
+}
+
+/// Distribution of `count` atoms by binary exponentiation.
+fn power(distribution: &[Peak], count: u32, threshold: f64, resolution: f64) -> Vec<Peak> {
+    let mut result = vec![Peak {
+        mz: 0.0,
+        intensity: 1.0,
+    }];
+    let mut base = distribution.to_vec();
+    let mut n = count;
+    while n > 0 {
+        if n & 1 == 1 {
+            result = convolve(&result, &base, threshold, resolution);
+        }
+        n >>= 1;
+        if n > 0 {
+            base = convolve(&base, &base, threshold, resolution);
+        }
+    }
+    result
+}
+
+fn convolve(a: &[Peak], b: &[Peak], threshold: f64, resolution: f64) -> Vec<Peak> {
+    let mut result = Vec::with_capacity(a.len() * b.len());
+    for pa in a {
+        for pb in b {
+            result.push(Peak {
+                mz: pa.mz + pb.mz,
+                intensity: pa.intensity * pb.intensity,
+            });
+        }
+    }
+    let mut result = merge(result, resolution);
+    let max = result.iter().map(|p| p.intensity).fold(0.0, f64::max);
+    result.retain(|p| p.intensity >= threshold * max);
+    result
+}
+
+/// Merges peaks closer than `resolution` to the first peak of their group into one peak
+/// with intensity-weighted mass.
+fn merge(mut peaks: Vec<Peak>, resolution: f64) -> Vec<Peak> {
+    peaks.sort_by(|a, b| a.mz.total_cmp(&b.mz));
+    let mut merged: Vec<Peak> = Vec::new();
+    let mut group_start = f64::NEG_INFINITY;
+    for peak in peaks {
+        match merged.last_mut() {
+            Some(last) if peak.mz - group_start < resolution => {
+                let intensity = last.intensity + peak.intensity;
+                last.mz = (last.mz * last.intensity + peak.mz * peak.intensity) / intensity;
+                last.intensity = intensity;
+            }
+            _ => {
+                group_start = peak.mz;
+                merged.push(peak);
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::formula::reader::parse_formula;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::isotope_pattern::{IsotopePatternOptions, Peak};
+
+    fn pattern(formula: &str, options: &IsotopePatternOptions) -> Vec<Peak> {
+        parse_formula(formula)
+            .unwrap()
+            .1
+            .isotope_pattern(options)
+            .unwrap()
+    }
+
+    #[test]
+    fn isotope_pattern_carbon() {
+        let peaks = pattern("C", &Default::default());
+        assert_eq!(peaks.len(), 2);
+        assert_eq!(peaks[0].mz, 12.0);
+        assert_eq!(peaks[0].intensity, 1.0);
+        assert!((peaks[1].mz - 13.00335483507).abs() < 1e-9);
+        assert!((peaks[1].intensity - 0.0107 / 0.9893).abs() < 1e-9);
+    }
+
+    #[test]
+    fn isotope_pattern_c100() {
+        let peaks = pattern("C100", &Default::default());
+        // M+1 is more intense than M for 100 carbons
+        assert!((peaks[0].intensity - 1.0 / (100.0 * 0.0107 / 0.9893)).abs() < 1e-6);
+        assert_eq!(peaks[1].intensity, 1.0);
+    }
+
+    #[test]
+    fn isotope_pattern_chlorine() {
+        let peaks = pattern("CH2Cl2", &Default::default());
+        let m = peaks[0];
+        let m2 = peaks
+            .iter()
+            .find(|p| (p.mz - m.mz - 1.997).abs() < 0.01)
+            .unwrap();
+        // 2 * 37Cl/35Cl with small 13C2 contribution
+        assert!((m2.intensity - 2.0 * 0.2424 / 0.7576).abs() < 0.01);
+    }
+
+    #[test]
+    fn isotope_pattern_resolution() {
+        let options = IsotopePatternOptions {
+            min_intensity: 1e-3,
+            resolution: 0.05,
+        };
+        let m = parse_molecule("CN1C=NC2=C1C(=O)N(C(=O)N2C)C").unwrap().1;
+        let peaks = m.isotope_pattern(&options).unwrap();
+        assert!((peaks[0].mz - 194.080376).abs() < 1e-5);
+        assert!((peaks[1].mz - 195.08).abs() < 0.01);
+        assert!((peaks[1].intensity - 0.103).abs() < 0.002);
+        assert!(peaks.iter().all(|p| p.intensity >= 1e-3));
+    }
+
+    #[test]
+    fn isotope_pattern_labelled_and_charged() {
+        let peaks = pattern("[13C]H4", &Default::default());
+        assert!((peaks[0].mz - (13.00335483507 + 4.0 * 1.00782503223)).abs() < 1e-9);
+        let peaks = pattern("C2+2", &Default::default());
+        assert!((peaks[0].mz - (24.0 - 2.0 * 0.000548579909) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn isotope_pattern_without_natural_isotopes() {
+        assert!(parse_formula("Tc")
+            .unwrap()
+            .1
+            .isotope_pattern(&Default::default())
+            .is_none());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::model::molecule::Molecule;
+use crate::model::ring_descriptors::RingDescriptors;
+
+impl Molecule {
+    pub fn ring_descriptors(&self) -> RingDescriptors {
+        let systems = self.ring_systems();
+        let largest = systems.iter().max_by_key(|s| (s.len(), s.ring_count()));
+        RingDescriptors {
+            ring_count: self.ring_info().count(),
+            ring_system_count: systems.len(),
+            largest_ring_system_size: largest.map_or(0, |s| s.len()),
+            largest_ring_system_rings: largest.map_or(0, |s| s.ring_count()),
+            fused_ring_count: self.fused_ring_count(),
+            spiro_atom_count: self.spiro_atoms().len(),
+            bridgehead_atom_count: self.bridgehead_atoms().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::ring_descriptors::RingDescriptors;
+
+    #[test]
+    fn ring_descriptors() {
+        // naproxen linked to a spiro system
+        let m = parse_molecule("CC(C1=CC2=C(C=C1)C=C(C=C2)OC)C(=O)NC1CCC2(C1)CCCC2")
+            .unwrap()
+            .1;
+        assert_eq!(
+            m.ring_descriptors(),
+            RingDescriptors {
+                ring_count: 4,
+                ring_system_count: 2,
+                largest_ring_system_size: 10,
+                largest_ring_system_rings: 2,
+                fused_ring_count: 2,
+                spiro_atom_count: 1,
+                bridgehead_atom_count: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn ring_descriptors_acyclic() {
+        let m = parse_molecule("CCCC(=O)O").unwrap().1;
+        assert_eq!(m.ring_descriptors(), RingDescriptors::default());
+    }
+}
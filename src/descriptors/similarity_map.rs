@@ -0,0 +1,72 @@
+use crate::model::molecule::Molecule;
+use petgraph::stable_graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Molecule {
+    /// Per-atom contributions to the ECFP similarity with `other`, for use as a colour map.
+    ///
+    /// Every bit of this molecule's fingerprint is worth +1 if `other` has it too and -1
+    /// otherwise. The value is split evenly between the environments that set the bit and
+    /// between the distinct atoms of each environment. Weights are scaled so that the
+    /// largest absolute weight is 1; atoms without bits are absent.
+    pub fn similarity_weights(
+        &self,
+        other: &Molecule,
+        radius: usize,
+        fp_length: usize,
+    ) -> BTreeMap<NodeIndex, f64> {
+        let (_, provenance) = self.ecfp_with_provenance(radius, fp_length);
+        let other_fp = other.ecfp(radius, fp_length);
+
+        let mut weights: BTreeMap<NodeIndex, f64> = BTreeMap::new();
+        for (bit, environments) in &provenance.environments {
+            let value = if other_fp.0.contains(*bit) { 1.0 } else { -1.0 };
+            for environment in environments {
+                let atoms: BTreeSet<NodeIndex> = environment.iter().copied().collect();
+                let share = value / (environments.len() * atoms.len()) as f64;
+                for atom in atoms {
+                    *weights.entry(atom).or_default() += share;
+                }
+            }
+        }
+
+        let max = weights.values().fold(0.0f64, |m, w| m.max(w.abs()));
+        if max > 0.0 {
+            for weight in weights.values_mut() {
+                *weight /= max;
+            }
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use petgraph::stable_graph::NodeIndex;
+
+    #[test]
+    fn test_identical_molecules() {
+        let m = parse_molecule("CC(=O)O").unwrap().1;
+        let weights = m.similarity_weights(&m, 2, 512);
+        assert_eq!(weights.len(), 4);
+        assert!(weights.values().all(|w| *w > 0.0));
+        assert!(weights.values().any(|w| *w == 1.0));
+    }
+
+    #[test]
+    fn test_differing_part() {
+        // the hit shares the carboxylic acid, the amine is not in the query
+        let query = parse_molecule("CCC(=O)O").unwrap().1;
+        let hit = parse_molecule("NCCC(=O)O").unwrap().1;
+        let weights = hit.similarity_weights(&query, 2, 512);
+        assert!(weights[&NodeIndex::new(0)] < 0.0);
+        assert!(weights[&NodeIndex::new(4)] > 0.0);
+        assert!(weights[&NodeIndex::new(4)] > weights[&NodeIndex::new(1)]);
+        assert!(weights.values().all(|w| (-1.0..=1.0).contains(w)));
+
+        let unrelated = parse_molecule("ClCl").unwrap().1;
+        let weights = hit.similarity_weights(&unrelated, 2, 512);
+        assert!(weights.values().all(|w| *w < 0.0));
+    }
+}
@@ -1,11 +1,135 @@
+use crate::model::adduct::{Adduct, ELECTRON_MASS};
+use crate::model::element::Element;
 use crate::model::molecule::Molecule;
 
 impl Molecule {
-    pub fn weight(&self) -> f64 {
+    /// Average molecular weight including explicit and implicit hydrogen counts.
+    /// Labelled atoms contribute the exact mass of their isotope.
+    /// Returns None if the molecule contains an unknown element or isotope.
+    pub fn weight(&self) -> Option<f64> {
+        let hydrogen = Element { atomic_number: 1 }.atomic_weight()?;
         let mut weight: f64 = 0.0;
-        for atom in self.graph.node_weights() {
-            weight += atom.element.atomic_weight();
+        for node in self.graph.node_indices() {
+            weight += self.graph[node].average_mass()?;
+            weight += self.total_hydrogens(node) as f64 * hydrogen;
         }
-        weight
+        Some(weight)
+    }
+
+    /// Monoisotopic mass: unlabelled atoms contribute their most abundant isotope.
+    /// Charged molecules are corrected for the mass of missing or extra electrons.
+    pub fn exact_mass(&self) -> Option<f64> {
+        let hydrogen = Element { atomic_number: 1 }.monoisotopic_mass()?;
+        let mut mass: f64 = 0.0;
+        for node in self.graph.node_indices() {
+            mass += self.graph[node].exact_mass()?;
+            mass += self.total_hydrogens(node) as f64 * hydrogen;
+        }
+        Some(mass - self.charge() as f64 * ELECTRON_MASS)
+    }
+
+    /// Sum of mass numbers of the most abundant (or labelled) isotopes.
+    pub fn nominal_mass(&self) -> Option<u32> {
+        let mut mass: u32 = 0;
+        for node in self.graph.node_indices() {
+            let atom = &self.graph[node];
+            mass += match atom.isotope {
+                0 => atom.element.most_abundant_isotope()? as u32,
+                isotope => isotope as u32,
+            };
+            mass += self.total_hydrogens(node) as u32;
+        }
+        Some(mass)
+    }
+
+    /// Total formal charge.
+    pub fn charge(&self) -> i32 {
+        self.graph.node_weights().map(|a| a.charge as i32).sum()
+    }
+
+    /// m/z of the adduct ion. Formal charges of the molecule are added to the adduct charge,
+    /// so e.g. [M+H]+ of a quaternary ammonium cation is doubly charged.
+    /// Returns None if the resulting ion is neutral.
+    pub fn adduct_mz(&self, adduct: Adduct) -> Option<f64> {
+        let charge = self.charge() + adduct.charge();
+        if charge == 0 {
+            return None;
+        }
+        Some((self.exact_mass()? + adduct.mass_delta()) / charge.unsigned_abs() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::adduct::Adduct;
+    use crate::model::molecule::Molecule;
+
+    fn molecule(smiles: &str) -> Molecule {
+        parse_molecule(smiles).unwrap().1
+    }
+
+    fn weight(smiles: &str) -> f64 {
+        molecule(smiles).weight().unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn weight_implicit_hydrogens() {
+        assert!((weight("C") - 16.043).abs() < 1e-9);
+        assert!((weight("CCO") - 46.069).abs() < 1e-9);
+    }
+
+    #[test]
+    fn weight_isotopes() {
+        assert!((weight("[13CH3]O") - (13.00335483507 + 15.999 + 4.0 * 1.008)).abs() < 1e-9);
+        assert!((weight("[2H]O[2H]") - (2.0 * 2.01410177812 + 15.999)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn weight_unknown_isotope() {
+        assert!(molecule("[7H]").weight().is_none());
+    }
+
+    #[test]
+    fn exact_mass() {
+        let caffeine = molecule("CN1C=NC2=C1C(=O)N(C(=O)N2C)C");
+        assert_close(caffeine.exact_mass().unwrap(), 194.080375579);
+        assert_eq!(caffeine.nominal_mass(), Some(194));
+    }
+
+    #[test]
+    fn exact_mass_isotopes() {
+        let m = molecule("[13CH3]O");
+        assert_close(
+            m.exact_mass().unwrap() - molecule("CO").exact_mass().unwrap(),
+            1.00335483507,
+        );
+        assert_eq!(m.nominal_mass(), Some(33));
+    }
+
+    #[test]
+    fn adduct_mz() {
+        let caffeine = molecule("CN1C=NC2=C1C(=O)N(C(=O)N2C)C");
+        assert_close(caffeine.adduct_mz(Adduct::MPlusH).unwrap(), 195.087652046);
+        assert_close(caffeine.adduct_mz(Adduct::MMinusH).unwrap(), 193.073099113);
+        assert_close(caffeine.adduct_mz(Adduct::MPlusNa).unwrap(), 217.069596281);
+    }
+
+    #[test]
+    fn adduct_mz_charged() {
+        let m = molecule("C[N+](C)(C)C");
+        assert_eq!(m.charge(), 1);
+        assert_close(m.exact_mass().unwrap(), 74.096425811);
+        assert_close(m.adduct_mz(Adduct::MPlusH).unwrap(), 37.551851139);
+        assert!(m.adduct_mz(Adduct::MMinusH).is_none());
     }
 }
@@ -1 +1,2 @@
-pub(crate) mod smiles;
+pub mod formula;
+pub mod smiles;
@@ -0,0 +1,137 @@
+use crate::model::element::Element;
+use crate::model::formula::Formula;
+use nom::branch::alt;
+use nom::character::complete::{char, digit1, satisfy};
+use nom::combinator::{map_res, opt, recognize};
+use nom::multi::many0;
+use nom::sequence::{delimited, pair, tuple};
+use nom::IResult;
+
+fn parse_count(input: &str) -> IResult<&str, u32> {
+    map_res(digit1, str::parse::<u32>)(input)
+}
+
+fn parse_symbol(input: &str) -> IResult<&str, u8> {
+    map_res(
+        recognize(pair(
+            satisfy(|c| c.is_ascii_uppercase()),
+            opt(satisfy(|c| c.is_ascii_lowercase())),
+        )),
+        |symbol: &str| {
+            Element::from_symbol(symbol)
+                .map(|e| e.atomic_number)
+                .ok_or(())
+        },
+    )(input)
+}
+
+/// `C`, `Cl` or isotope in square brackets like `[13C]`.
+fn parse_atom(input: &str) -> IResult<&str, Formula> {
+    let (input, (isotope, atomic_number)) = alt((
+        delimited(
+            char('['),
+            tuple((map_res(digit1, str::parse::<u16>), parse_symbol)),
+            char(']'),
+        ),
+        |i| parse_symbol(i).map(|(i, z)| (i, (0, z))),
+    ))(input)?;
+    let mut formula = Formula::new();
+    formula.add(atomic_number, isotope, 1);
+    Ok((input, formula))
+}
+
+/// Atom or parenthesized group, followed by optional multiplier.
+fn parse_group(input: &str) -> IResult<&str, Formula> {
+    let (input, formula) = alt((parse_atom, delimited(char('('), parse_groups, char(')'))))(input)?;
+    let (input, count) = opt(parse_count)(input)?;
+    let mut result = Formula::new();
+    for ((z, isotope), c) in formula.counts {
+        result.add(z, isotope, c * count.unwrap_or(1));
+    }
+    Ok((input, result))
+}
+
+fn parse_groups(input: &str) -> IResult<&str, Formula> {
+    let (input, groups) = many0(parse_group)(input)?;
+    let mut formula = Formula::new();
+    for group in groups {
+        for ((z, isotope), c) in group.counts {
+            formula.add(z, isotope, c);
+        }
+    }
+    Ok((input, formula))
+}
+
+/// Parses a charge suffix: `+`, `-`, `+2`, `-2` or repeated signs like `--`.
+fn parse_charge(input: &str) -> IResult<&str, i32> {
+    let (input, sign) = alt((char('+'), char('-')))(input)?;
+    let sign = if sign == '+' { 1 } else { -1 };
+    let (input, count) = opt(parse_count)(input)?;
+    if let Some(count) = count {
+        return Ok((input, sign * count as i32));
+    }
+    let (input, repeated) = many0(char(if sign > 0 { '+' } else { '-' }))(input)?;
+    Ok((input, sign * (repeated.len() as i32 + 1)))
+}
+
+/// Parses a molecular formula like `C2H6O`, `C[13C]H5[2H]`, `Ca(OH)2` or `NH4+`.
+/// Elements may be given in any order, repeated elements are summed up.
+pub fn parse_formula(input: &str) -> IResult<&str, Formula> {
+    let (input, mut formula) = parse_groups(input)?;
+    let (input, charge) = opt(parse_charge)(input)?;
+    formula.charge = charge.unwrap_or(0);
+    Ok((input, formula))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::formula::reader::parse_formula;
+    use crate::io::smiles::reader::molecule::parse_molecule;
+
+    #[test]
+    fn parse_formula_simple() {
+        let (rest, f) = parse_formula("C2H6O").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(f.count(6), 2);
+        assert_eq!(f.count(1), 6);
+        assert_eq!(f.count(8), 1);
+        assert_eq!(f.charge, 0);
+    }
+
+    #[test]
+    fn parse_formula_two_letter_symbols() {
+        let f = parse_formula("CoCl2").unwrap().1;
+        assert_eq!(f.count(27), 1);
+        assert_eq!(f.count(17), 2);
+        assert_eq!(f.count(6), 0);
+    }
+
+    #[test]
+    fn parse_formula_isotopes() {
+        let f = parse_formula("C[13C]H5[2H]").unwrap().1;
+        assert_eq!(f.counts.get(&(6, 0)), Some(&1));
+        assert_eq!(f.counts.get(&(6, 13)), Some(&1));
+        assert_eq!(f.counts.get(&(1, 2)), Some(&1));
+        assert_eq!(f.to_string(), "C[13C]H5[2H]");
+    }
+
+    #[test]
+    fn parse_formula_groups_and_charge() {
+        let f = parse_formula("Ca(OH)2").unwrap().1;
+        assert_eq!(f.to_string(), "CaH2O2");
+        assert_eq!(parse_formula("NH4+").unwrap().1.charge, 1);
+        assert_eq!(parse_formula("SO4-2").unwrap().1.charge, -2);
+        assert_eq!(parse_formula("SO4--").unwrap().1.charge, -2);
+    }
+
+    #[test]
+    fn parse_formula_unknown_symbol() {
+        assert_eq!(parse_formula("CXx").unwrap().0, "Xx");
+    }
+
+    #[test]
+    fn parse_formula_roundtrip() {
+        let m = parse_molecule("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O").unwrap().1;
+        assert_eq!(parse_formula("C13H18O2").unwrap().1, m.formula());
+    }
+}
@@ -1,7 +1,10 @@
 mod atom;
+mod atom_map;
 mod bond;
 mod charge;
+mod chirality;
 mod element;
 mod hydrogens;
 mod isotope;
 pub mod molecule;
+pub mod reaction;
@@ -1,25 +1,55 @@
+use crate::io::smiles::reader::atom_map::parse_atom_map;
 use crate::io::smiles::reader::charge::parse_charge;
-use crate::io::smiles::reader::element::parse_element;
+use crate::io::smiles::reader::chirality::parse_chirality;
+use crate::io::smiles::reader::element::{
+    parse_aromatic_element, parse_bracket_aromatic_element, parse_bracket_element, parse_element,
+};
 use crate::io::smiles::reader::hydrogens::parse_hydrogens;
 use crate::io::smiles::reader::isotope::parse_isotope;
-use crate::model::atom::Atom;
+use crate::model::atom::{Atom, Chirality};
 use crate::model::element::Element;
-use nom::combinator::opt;
+use nom::branch::alt;
+use nom::combinator::{map, opt};
 use nom::IResult;
 
-pub fn parse_atom(input: &str) -> IResult<&str, Atom> {
-    let mut isotope: Option<u8> = None;
+/// Parses an atom; the flag, also set as `Atom::aromatic`, is set for atoms written with a
+/// lowercase aromatic symbol.
+/// The chirality of the atom still refers to the order of its neighbors in the SMILES string.
+pub fn parse_atom(input: &str) -> IResult<&str, (Atom, bool)> {
+    let mut isotope: Option<u16> = None;
     let mut charge: Option<i8> = None;
     let mut hs: Option<u8> = None;
+    let mut chirality: Option<Chirality> = None;
+    let mut atom_map: Option<u16> = None;
     let atomic_number: u8;
     let (mut input, sqro_found) = opt(nom::character::complete::char('['))(input)?;
     if sqro_found.is_some() {
         (input, isotope) = opt(parse_isotope)(input).unwrap_or((input, None));
     }
-    (input, atomic_number) = parse_element(input)?;
+    let aromatic: bool;
+    (input, (atomic_number, aromatic)) = if sqro_found.is_some() {
+        alt((
+            map(parse_bracket_element, |z| (z, false)),
+            map(parse_bracket_aromatic_element, |z| (z, true)),
+        ))(input)?
+    } else {
+        alt((
+            map(parse_element, |z| (z, false)),
+            map(parse_aromatic_element, |z| (z, true)),
+        ))(input)?
+    };
+    // mass number can not be lower than the number of protons
+    if isotope.is_some_and(|isotope| isotope < atomic_number as u16) {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
     if sqro_found.is_some() {
+        (input, chirality) = opt(parse_chirality)(input).unwrap_or((input, None));
         (input, hs) = opt(parse_hydrogens)(input).unwrap_or((input, None));
         (input, charge) = opt(parse_charge)(input).unwrap_or((input, None));
+        (input, atom_map) = opt(parse_atom_map)(input).unwrap_or((input, None));
         let sqrc_found: Option<char>;
         (input, sqrc_found) = opt(nom::character::complete::char(']'))(input)?;
         if (sqro_found.is_some() && sqrc_found.is_none())
@@ -34,23 +64,32 @@ pub fn parse_atom(input: &str) -> IResult<&str, Atom> {
 
     Ok((
         input,
-        Atom {
-            element: Element {
-                atomic_number: atomic_number,
+        (
+            Atom {
+                element: Element { atomic_number },
+                isotope: isotope.unwrap_or(0),
+                charge: charge.unwrap_or(0),
+                hs: hs.unwrap_or(0),
+                no_implicit_hs: sqro_found.is_some(),
+                aromatic,
+                coords: None,
+                chirality: chirality.unwrap_or_default(),
+                map: atom_map.unwrap_or(0),
             },
-            isotope: isotope.unwrap_or(0),
-            charge: charge.unwrap_or(0),
-            hs: hs.unwrap_or(0),
-        },
+            aromatic,
+        ),
     ))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::io::smiles::reader::atom::parse_atom;
+    use crate::model::atom::Chirality;
 
-    fn do_test_parse_atom(input: &str, atomic_number: u8, charge: i8, hs: u8, isotope: u8) {
-        let (remaining_input, atom) = parse_atom(input).unwrap();
+    fn do_test_parse_atom(input: &str, atomic_number: u8, charge: i8, hs: u8, isotope: u16) {
+        let (remaining_input, (atom, aromatic)) = parse_atom(input).unwrap();
+        assert!(!aromatic);
+        assert_eq!(atom.no_implicit_hs, input.starts_with('['));
         assert_eq!(atom.element.atomic_number, atomic_number);
         assert_eq!(atom.isotope, isotope);
         assert_eq!(atom.charge, charge);
@@ -93,6 +132,68 @@ mod tests {
         do_test_parse_atom("[13CH3-]", 6, -1, 3, 13);
     }
 
+    #[test]
+    fn parse_atom_238_u() {
+        do_test_parse_atom("[238U]", 92, 0, 0, 238);
+    }
+
+    #[test]
+    fn parse_atom_2_h() {
+        do_test_parse_atom("[2H]", 1, 0, 0, 2);
+    }
+
+    #[test]
+    fn parse_atom_wildcard() {
+        do_test_parse_atom("[*]", 0, 0, 0, 0);
+        do_test_parse_atom("[14*]", 0, 0, 0, 14);
+        let (_, (atom, _)) = parse_atom("*").unwrap();
+        assert!(atom.is_wildcard());
+    }
+
+    #[test]
+    fn parse_atom_map() {
+        let (rest, (atom, _)) = parse_atom("[CH3+:12]C").unwrap();
+        assert_eq!(rest, "C");
+        assert_eq!((atom.hs, atom.charge, atom.map), (3, 1, 12));
+        let (_, (atom, _)) = parse_atom("[*:1]").unwrap();
+        assert!(atom.is_wildcard());
+        assert_eq!(atom.map, 1);
+        assert!(parse_atom("[C:]").is_err());
+    }
+
+    #[test]
+    fn parse_atom_metal() {
+        do_test_parse_atom("[Na+]", 11, 1, 0, 0);
+        do_test_parse_atom("[Fe+2]", 26, 2, 0, 0);
+        // only the organic subset is allowed outside brackets
+        assert_eq!(parse_atom("Na").unwrap().0, "a");
+    }
+
+    #[test]
+    fn parse_atom_chirality() {
+        do_test_parse_atom("[C@@H]", 6, 0, 1, 0);
+        let (_, (atom, _)) = parse_atom("[13C@H2+]").unwrap();
+        assert_eq!(atom.chirality, Chirality::AntiClockwise);
+        assert_eq!((atom.isotope, atom.hs, atom.charge), (13, 2, 1));
+        assert_eq!(parse_atom("C").unwrap().1 .0.chirality, Chirality::None);
+    }
+
+    #[test]
+    fn parse_atom_aromatic() {
+        let (_, (atom, aromatic)) = parse_atom("c").unwrap();
+        assert_eq!(atom.element.atomic_number, 6);
+        assert!(aromatic);
+        let (_, (atom, aromatic)) = parse_atom("[nH]").unwrap();
+        assert_eq!((atom.element.atomic_number, atom.hs), (7, 1));
+        assert!(aromatic);
+        assert!(!parse_atom("Cl").unwrap().1 .1);
+    }
+
+    #[test]
+    fn parse_atom_isotope_lower_than_atomic_number() {
+        assert!(parse_atom("[2C]").is_err())
+    }
+
     #[test]
     fn parse_atom_13_c_h3_minus_incorrect_closing() {
         assert!(parse_atom("[13CH3-C").is_err())
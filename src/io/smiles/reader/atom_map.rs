@@ -0,0 +1,25 @@
+use nom::{
+    character::complete::{char, digit1},
+    combinator::map_res,
+    sequence::preceded,
+    IResult,
+};
+
+/// Parses an atom map number (atom class), written as `:` and a number at the end of a
+/// bracket atom, e.g. `[CH3:1]`.
+pub fn parse_atom_map(input: &str) -> IResult<&str, u16> {
+    preceded(char(':'), map_res(digit1, str::parse::<u16>))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::atom_map::parse_atom_map;
+
+    #[test]
+    fn parse_atom_map_number() {
+        assert_eq!(parse_atom_map(":1]").unwrap(), ("]", 1));
+        assert_eq!(parse_atom_map(":12").unwrap().1, 12);
+        assert!(parse_atom_map(":]").is_err());
+        assert!(parse_atom_map("1").is_err());
+    }
+}
@@ -11,12 +11,17 @@ pub fn parse_bond(input: &str) -> IResult<&str, Bond> {
         map(tag(":"), |_| BondOrder::Aromatic),
         map(tag("-"), |_| BondOrder::Single),
     ))(input)?;
-    Ok((input, Bond { order: bond_order }))
+    Ok((input, Bond::new(bond_order)))
+}
+
+/// Parses a directional single bond: `/` gives true, `\` gives false.
+pub fn parse_bond_direction(input: &str) -> IResult<&str, bool> {
+    alt((map(tag("/"), |_| true), map(tag("\\"), |_| false)))(input)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::io::smiles::reader::bond::parse_bond;
+    use crate::io::smiles::reader::bond::{parse_bond, parse_bond_direction};
     use crate::model::bond::BondOrder;
 
     #[test]
@@ -39,6 +44,13 @@ mod tests {
         assert_eq!(parse_bond("#").unwrap().1.order, BondOrder::Triple);
     }
 
+    #[test]
+    fn parse_bond_direction_marks() {
+        assert!(parse_bond_direction("/").unwrap().1);
+        assert!(!parse_bond_direction("\\").unwrap().1);
+        assert!(parse_bond_direction("-").is_err());
+    }
+
     #[test]
     fn parse_bond_aromatic() {
         assert_eq!(parse_bond(":").unwrap().1.order, BondOrder::Aromatic);
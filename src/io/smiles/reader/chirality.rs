@@ -0,0 +1,30 @@
+use crate::model::atom::Chirality;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::combinator::map;
+use nom::IResult;
+
+/// Parses a tetrahedral chirality mark: `@` (anticlockwise) or `@@` (clockwise).
+/// The configuration refers to the order of the neighbors in the SMILES string.
+pub fn parse_chirality(input: &str) -> IResult<&str, Chirality> {
+    alt((
+        map(tag("@@"), |_| Chirality::Clockwise),
+        map(tag("@"), |_| Chirality::AntiClockwise),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::chirality::parse_chirality;
+    use crate::model::atom::Chirality;
+
+    #[test]
+    fn parse_chirality_marks() {
+        assert_eq!(
+            parse_chirality("@H").unwrap(),
+            ("H", Chirality::AntiClockwise)
+        );
+        assert_eq!(parse_chirality("@@]").unwrap(), ("]", Chirality::Clockwise));
+        assert!(parse_chirality("H").is_err());
+    }
+}
@@ -1,13 +1,17 @@
+use crate::model::element::Element;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::combinator::map_res;
+use nom::error::{Error, ErrorKind};
 use nom::IResult;
 
+/// Symbols of the organic subset, which may be written without square brackets.
 pub fn parse_element(input: &str) -> IResult<&str, u8> {
     map_res(
         alt((
             tag("Cl"),
             tag("Br"),
+            tag("B"),
             tag("C"),
             tag("N"),
             tag("O"),
@@ -16,10 +20,12 @@ pub fn parse_element(input: &str) -> IResult<&str, u8> {
             tag("S"),
             tag("I"),
             tag("H"),
+            tag("*"),
         )),
         |symbol| match symbol {
             "Cl" => Ok(17),
             "Br" => Ok(35),
+            "B" => Ok(5),
             "C" => Ok(6),
             "N" => Ok(7),
             "O" => Ok(8),
@@ -28,14 +34,72 @@ pub fn parse_element(input: &str) -> IResult<&str, u8> {
             "S" => Ok(16),
             "I" => Ok(53),
             "H" => Ok(1),
+            "*" => Ok(0),
             _ => Err(()),
         },
     )(input)
 }
 
+/// Any element symbol or the wildcard `*`, as allowed inside square brackets. Two letter
+/// symbols take precedence, so `[Sc]` is scandium.
+pub fn parse_bracket_element(input: &str) -> IResult<&str, u8> {
+    if let Some(rest) = input.strip_prefix('*') {
+        return Ok((rest, 0));
+    }
+    let mut chars = input.char_indices();
+    match chars.next() {
+        Some((_, first)) if first.is_ascii_uppercase() => {}
+        _ => {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+    }
+    let two = chars
+        .next()
+        .filter(|(_, c)| c.is_ascii_lowercase())
+        .map(|(i, c)| i + c.len_utf8());
+    for length in two.into_iter().chain([1]) {
+        if let Some(element) = Element::from_symbol(&input[..length]) {
+            return Ok((&input[length..], element.atomic_number));
+        }
+    }
+    Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)))
+}
+
+/// Lowercase symbols of atoms in aromatic rings.
+pub fn parse_aromatic_element(input: &str) -> IResult<&str, u8> {
+    map_res(
+        alt((tag("b"), tag("c"), tag("n"), tag("o"), tag("p"), tag("s"))),
+        |symbol| match symbol {
+            "b" => Ok(5),
+            "c" => Ok(6),
+            "n" => Ok(7),
+            "o" => Ok(8),
+            "p" => Ok(15),
+            "s" => Ok(16),
+            _ => Err(()),
+        },
+    )(input)
+}
+
+/// Lowercase symbols of aromatic atoms inside square brackets, where selenium and arsenic
+/// may be aromatic too.
+pub fn parse_bracket_aromatic_element(input: &str) -> IResult<&str, u8> {
+    alt((
+        map_res(alt((tag("se"), tag("as"))), |symbol| match symbol {
+            "se" => Ok(34),
+            "as" => Ok(33),
+            _ => Err(()),
+        }),
+        parse_aromatic_element,
+    ))(input)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::io::smiles::reader::element::parse_element;
+    use crate::io::smiles::reader::element::{
+        parse_aromatic_element, parse_bracket_aromatic_element, parse_bracket_element,
+        parse_element,
+    };
     use nom::error::{Error, ErrorKind};
 
     #[test]
@@ -55,4 +119,34 @@ mod tests {
     fn parse_atom_symbol_c() {
         assert_eq!(parse_element("C").unwrap().1, 6);
     }
+
+    #[test]
+    fn parse_aromatic_symbols() {
+        assert_eq!(parse_aromatic_element("c1").unwrap(), ("1", 6));
+        assert_eq!(parse_aromatic_element("n").unwrap().1, 7);
+        assert!(parse_aromatic_element("C").is_err());
+        assert!(parse_element("c").is_err());
+        assert_eq!(parse_bracket_aromatic_element("se]").unwrap(), ("]", 34));
+        assert_eq!(parse_bracket_aromatic_element("asH]").unwrap(), ("H]", 33));
+        assert_eq!(parse_bracket_aromatic_element("s]").unwrap(), ("]", 16));
+        assert!(parse_aromatic_element("se").is_ok_and(|(rest, _)| rest == "e"));
+    }
+
+    #[test]
+    fn parse_organic_subset() {
+        assert_eq!(parse_element("B").unwrap().1, 5);
+        assert_eq!(parse_element("Br").unwrap().1, 35);
+        assert_eq!(parse_element("BC").unwrap(), ("C", 5));
+    }
+
+    #[test]
+    fn parse_bracket_symbols() {
+        assert_eq!(parse_bracket_element("Na+]").unwrap(), ("+]", 11));
+        assert_eq!(parse_bracket_element("Sc]").unwrap().1, 21);
+        assert_eq!(parse_bracket_element("CH4]").unwrap(), ("H4]", 6));
+        assert_eq!(parse_bracket_element("Clx").unwrap(), ("x", 17));
+        assert_eq!(parse_bracket_element("*]").unwrap(), ("]", 0));
+        assert!(parse_bracket_element("Xx").is_err());
+        assert!(parse_bracket_element("c").is_err());
+    }
 }
@@ -2,8 +2,8 @@ use nom::{character::complete::digit1, combinator::map_res, IResult};
 
 /// Parses isotope value, that should be a number
 /// Returns the isotope value
-pub fn parse_isotope(input: &str) -> IResult<&str, u8> {
-    Ok(map_res(digit1, str::parse::<u8>)(input)?)
+pub fn parse_isotope(input: &str) -> IResult<&str, u16> {
+    map_res(digit1, str::parse::<u16>)(input)
 }
 
 #[cfg(test)]
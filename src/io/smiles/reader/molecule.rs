@@ -1,96 +1,273 @@
+use crate::chem::stereo::permutation_parity;
 use crate::io::smiles::reader::atom::parse_atom;
-use crate::io::smiles::reader::bond::parse_bond;
-use crate::model::bond::{Bond, BondOrder};
+use crate::io::smiles::reader::bond::{parse_bond, parse_bond_direction};
+use crate::model::atom::{Atom, Chirality};
+use crate::model::bond::{Bond, BondOrder, DoubleBondStereo};
 use crate::model::molecule::Molecule;
 use nom::branch::alt;
-use nom::character::complete::{char, digit1};
+use nom::bytes::complete::take_while_m_n;
+use nom::character::complete::char;
 use nom::combinator::{map, map_res};
 use nom::error::ErrorKind;
 use nom::multi::many0;
+use nom::sequence::preceded;
 use nom::IResult;
-use petgraph::stable_graph::NodeIndex;
-use std::collections::HashMap;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{HashMap, HashSet};
 
+enum Token {
+    Atom(Atom, bool),
+    Bond(Bond),
+    /// Directional single bond, `/` (true) or `\` (false).
+    Direction(bool),
+    RingClosure(u8),
+    BranchOpen,
+    BranchClose,
+    Dot,
+}
+
+/// Bond written between two atoms or ring closures: order and direction, if given.
+#[derive(Clone, Copy, Default)]
+struct BondSymbol {
+    order: Option<BondOrder>,
+    direction: Option<bool>,
+}
+
+impl BondSymbol {
+    fn or(self, other: BondSymbol) -> BondSymbol {
+        BondSymbol {
+            order: self.order.or(other.order),
+            direction: self.direction.or(other.direction),
+        }
+    }
+}
+
+/// Ring closure number: a single digit, or two digits after `%`.
 fn parse_cycle_digit(input: &str) -> IResult<&str, u8> {
-    map_res(digit1, str::parse::<u8>)(input)
+    let digits = |n| take_while_m_n(n, n, |c: char| c.is_ascii_digit());
+    alt((
+        map_res(digits(1), str::parse::<u8>),
+        map_res(preceded(char('%'), digits(2)), str::parse::<u8>),
+    ))(input)
+}
+
+/// Order of a bond written without a symbol: aromatic between two aromatic atoms, unless it
+/// turns out to join two rings (see `parse_molecule`).
+fn default_order(aromatic: &HashSet<NodeIndex>, a: NodeIndex, b: NodeIndex) -> BondOrder {
+    if aromatic.contains(&a) && aromatic.contains(&b) {
+        BondOrder::Aromatic
+    } else {
+        BondOrder::Single
+    }
 }
 
 pub fn parse_molecule(input: &str) -> IResult<&str, Molecule> {
     let mut molecule = Molecule::new();
-    let mut open_cycles: HashMap<u8, NodeIndex> = HashMap::new();
-    let mut stack: Vec<(NodeIndex, BondOrder)> = Vec::new();
-    let mut pending_bonds: Vec<(NodeIndex, NodeIndex, Bond)> = Vec::new();
+    let mut aromatic: HashSet<NodeIndex> = HashSet::new();
+    // ring closures by digit: opening atom, bond symbol and the position of the closure in
+    // the neighbor order of the opening atom
+    let mut open_cycles: HashMap<u8, (NodeIndex, BondSymbol, usize)> = HashMap::new();
+    let mut stack: Vec<(NodeIndex, BondSymbol)> = Vec::new();
+    // bonds with the atom written on the left of the bond symbol first
+    let mut pending_bonds: Vec<(NodeIndex, NodeIndex, BondSymbol)> = Vec::new();
+    // neighbors of every atom in the order they are written, `None` for the hydrogen of
+    // bracket atoms with one hydrogen
+    let mut neighbor_order: HashMap<NodeIndex, Vec<Option<NodeIndex>>> = HashMap::new();
 
-    let mut parse_atoms_and_bonds = many0(alt((
-        map(parse_atom, |atom| (Some(atom), None, None, None)),
-        map(parse_bond, |bond| (None, Some(bond), None, None)),
-        map(parse_cycle_digit, |digit| (None, None, Some(digit), None)),
-        map(char('('), |_| (None, None, None, Some(true))),
-        map(char(')'), |_| (None, None, None, Some(false))),
-    )));
-
-    let (input, atoms_and_bonds) = parse_atoms_and_bonds(input)?;
+    let (input, tokens) = many0(alt((
+        map(parse_atom, |(atom, aromatic)| Token::Atom(atom, aromatic)),
+        map(parse_bond, Token::Bond),
+        map(parse_bond_direction, Token::Direction),
+        map(parse_cycle_digit, Token::RingClosure),
+        map(char('('), |_| Token::BranchOpen),
+        map(char(')'), |_| Token::BranchClose),
+        map(char('.'), |_| Token::Dot),
+    )))(input)?;
 
+    let failure = || nom::Err::Failure(nom::error::Error::new(input, ErrorKind::Verify));
     let mut prev_node = NodeIndex::end();
-    let mut prev_bond = BondOrder::Single;
+    // explicitly written bond to the next atom or ring closure
+    let mut prev_bond = BondSymbol::default();
 
-    for (atom, bond, cycle_digit, open_paren) in atoms_and_bonds {
-        if let Some(open) = open_paren {
-            if open {
-                stack.push((prev_node, prev_bond));
-            } else {
-                if stack.is_empty() {
-                    return Err(nom::Err::Failure(nom::error::Error::new(
-                        input,
-                        ErrorKind::Verify,
-                    )));
-                }
-
-                let (node, bond) = stack.pop().unwrap();
-                prev_node = node;
-                prev_bond = bond;
+    for token in tokens {
+        match token {
+            Token::BranchOpen => stack.push((prev_node, prev_bond)),
+            Token::BranchClose => {
+                (prev_node, prev_bond) = stack.pop().ok_or_else(failure)?;
             }
-        } else if let Some(digit) = cycle_digit {
-            if let Some(open_node) = open_cycles.remove(&digit) {
-                pending_bonds.push((prev_node, open_node, Bond { order: prev_bond }));
-                prev_bond = BondOrder::Single;
-            } else {
-                open_cycles.insert(digit, prev_node);
+            Token::RingClosure(digit) => {
+                if prev_node == NodeIndex::end() {
+                    return Err(failure());
+                }
+                let order = neighbor_order.entry(prev_node).or_default();
+                if let Some((open_node, open_bond, position)) = open_cycles.remove(&digit) {
+                    order.push(Some(open_node));
+                    neighbor_order.get_mut(&open_node).unwrap()[position] = Some(prev_node);
+                    if prev_bond.direction.is_none() && open_bond.direction.is_some() {
+                        pending_bonds.push((open_node, prev_node, open_bond.or(prev_bond)));
+                    } else {
+                        pending_bonds.push((prev_node, open_node, prev_bond.or(open_bond)));
+                    }
+                } else {
+                    order.push(None);
+                    open_cycles.insert(digit, (prev_node, prev_bond, order.len() - 1));
+                }
+                prev_bond = BondSymbol::default();
             }
-        } else {
-            if let Some(atom) = atom {
+            Token::Atom(atom, is_aromatic) => {
+                let hydrogen = atom.no_implicit_hs && atom.hs == 1;
                 let node = molecule.add_atom(atom);
-                if prev_node != NodeIndex::end() && bond.is_none() {
-                    pending_bonds.push((prev_node, node, Bond { order: prev_bond }));
+                if is_aromatic {
+                    aromatic.insert(node);
+                }
+                let mut order = Vec::new();
+                if prev_node != NodeIndex::end() {
+                    pending_bonds.push((prev_node, node, prev_bond));
+                    neighbor_order
+                        .entry(prev_node)
+                        .or_default()
+                        .push(Some(node));
+                    order.push(Some(prev_node));
+                }
+                if hydrogen {
+                    order.push(None);
                 }
+                neighbor_order.insert(node, order);
                 prev_node = node;
+                prev_bond = BondSymbol::default();
             }
-
-            if let Some(bond) = bond {
-                prev_bond = bond.order;
-            } else {
-                prev_bond = BondOrder::Single;
+            Token::Bond(bond) => prev_bond.order = Some(bond.order),
+            Token::Direction(direction) => {
+                prev_bond.order = Some(BondOrder::Single);
+                prev_bond.direction = Some(direction);
+            }
+            Token::Dot => {
+                if !stack.is_empty() {
+                    return Err(failure());
+                }
+                prev_node = NodeIndex::end();
+                prev_bond = BondSymbol::default();
             }
         }
     }
 
     if !open_cycles.is_empty() || !stack.is_empty() {
+        return Err(failure());
+    }
+
+    let mut directions: HashMap<EdgeIndex, (NodeIndex, bool)> = HashMap::new();
+    let mut implicit_aromatic: Vec<EdgeIndex> = Vec::new();
+    for (node1, node2, symbol) in pending_bonds {
+        let order = symbol
+            .order
+            .unwrap_or_else(|| default_order(&aromatic, node1, node2));
+        let edge = molecule.add_bond(node1, node2, Bond::new(order));
+        if symbol.order.is_none() && order == BondOrder::Aromatic {
+            implicit_aromatic.push(edge);
+        }
+        if let Some(direction) = symbol.direction {
+            directions.insert(edge, (node2, direction));
+        }
+    }
+    // an implicit bond between aromatic atoms of two rings, like in biphenyl `c1ccccc1c1ccccc1`,
+    // is single; chains outside of rings, like the query fragment `cc`, stay aromatic
+    for edge in implicit_aromatic {
+        let (a, b) = molecule.graph.edge_endpoints(edge).unwrap();
+        let rings = molecule.ring_info();
+        if !rings.is_bond_in_ring(edge) && rings.is_atom_in_ring(a) && rings.is_atom_in_ring(b) {
+            molecule.graph[edge].order = BondOrder::Single;
+        }
+    }
+
+    set_chirality(&mut molecule, &neighbor_order);
+    if !directions.is_empty() {
+        set_double_bond_stereo(&mut molecule, &directions);
+    }
+
+    Ok((input, molecule))
+}
+
+/// Parses a line of a SMILES file: a molecule, optionally followed by whitespace and a name,
+/// which is returned trimmed as the remaining input. Unlike `parse_molecule`, fails for lines
+/// without atoms and for SMILES followed directly by other characters, which were not read.
+pub fn parse_molecule_line(input: &str) -> IResult<&str, Molecule> {
+    let (rest, molecule) = parse_molecule(input)?;
+    if molecule.count_atoms() == 0 || rest.starts_with(|c: char| !c.is_whitespace()) {
         return Err(nom::Err::Failure(nom::error::Error::new(
-            input,
+            rest,
             ErrorKind::Verify,
         )));
     }
+    Ok((rest.trim(), molecule))
+}
 
-    for (node1, node2, bond) in pending_bonds {
-        molecule.add_bond(node1, node2, bond);
+/// Converts the chirality of atoms from the written neighbor order to the order of
+/// `Molecule::stereo_neighbors`.
+fn set_chirality(
+    molecule: &mut Molecule,
+    neighbor_order: &HashMap<NodeIndex, Vec<Option<NodeIndex>>>,
+) {
+    let atoms: Vec<NodeIndex> = molecule
+        .graph
+        .node_indices()
+        .filter(|a| molecule.graph[*a].chirality != Chirality::None)
+        .collect();
+    for atom in atoms {
+        let stereo_neighbors = molecule.stereo_neighbors(atom);
+        let chirality = molecule.graph[atom].chirality;
+        molecule.graph[atom].chirality =
+            match permutation_parity(&neighbor_order[&atom], &stereo_neighbors) {
+                Some(false) => chirality,
+                Some(true) => chirality.inverted(),
+                None => Chirality::None,
+            };
     }
+}
 
-    Ok((input, molecule))
+/// Sets the configuration of double bonds with a directional single bond on both sides.
+/// `directions` gives the atom on the right of every directional bond and its symbol.
+fn set_double_bond_stereo(
+    molecule: &mut Molecule,
+    directions: &HashMap<EdgeIndex, (NodeIndex, bool)>,
+) {
+    // +1 or -1 for the marked neighbor of `atom`: neighbors of the two ends with equal
+    // values are on the same side of the double bond
+    let side = |molecule: &Molecule, atom: NodeIndex, partner: NodeIndex| {
+        let neighbors = molecule.double_bond_neighbors(atom, partner);
+        if neighbors.len() > 2 {
+            return None;
+        }
+        neighbors.iter().find_map(|n| {
+            let edge = molecule.graph.find_edge(atom, *n)?;
+            let (right, direction) = directions.get(&edge)?;
+            let up = direction == &(*right == atom);
+            Some((*n != neighbors[0], up))
+        })
+    };
+    let double_bonds: Vec<(EdgeIndex, NodeIndex, NodeIndex)> = (&molecule.graph)
+        .edge_references()
+        .filter(|e| e.weight().order == BondOrder::Double)
+        .map(|e| (e.id(), e.source(), e.target()))
+        .collect();
+    for (edge, a, b) in double_bonds {
+        if let (Some((swap_a, up_a)), Some((swap_b, up_b))) =
+            (side(molecule, a, b), side(molecule, b, a))
+        {
+            // neighbors other than the reference ones flip the configuration
+            let cis = (up_a == up_b) ^ swap_a ^ swap_b;
+            molecule.graph[edge].double_bond_stereo = if cis {
+                DoubleBondStereo::Cis
+            } else {
+                DoubleBondStereo::Trans
+            };
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::io::smiles::reader::molecule::{parse_molecule, parse_molecule_line};
     use crate::model::bond::BondOrder;
     use petgraph::stable_graph::{EdgeIndex, NodeIndex};
 
@@ -430,15 +607,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_molecule_lines() {
+        let (name, m) = parse_molecule_line("BC boron ").unwrap();
+        assert_eq!(name, "boron");
+        assert_eq!(m.count_atoms(), 2);
+        let (name, m) = parse_molecule_line("c1cc[se]c1").unwrap();
+        assert_eq!(name, "");
+        assert!(m.sanitize().is_empty());
+        assert!(parse_molecule_line("").is_err());
+        assert!(parse_molecule_line(" CCO").is_err());
+        assert!(parse_molecule_line("CC&O").is_err());
+        assert!(parse_molecule_line("C[Xx]").is_err());
+    }
+
     #[test]
     fn parse_molecule_c1cc() {
         assert!(parse_molecule("C1CC").is_err())
     }
 
+    #[test]
+    fn parse_molecule_ring_closure_numbers() {
+        let m = parse_molecule("C12CC1CC2").unwrap().1;
+        assert_eq!(m.count_bonds(), 6);
+        assert!(m.has_bond(NodeIndex::new(0), NodeIndex::new(2)));
+        assert!(m.has_bond(NodeIndex::new(0), NodeIndex::new(4)));
+        let m = parse_molecule("C%10CC%10").unwrap().1;
+        assert_eq!(m.count_bonds(), 3);
+        let m = parse_molecule("C=1CCCCC1").unwrap().1;
+        assert_eq!(
+            m.get_bond_by_atoms(NodeIndex::new(0), NodeIndex::new(5))
+                .unwrap()
+                .order,
+            BondOrder::Double
+        );
+    }
+
+    #[test]
+    fn parse_molecule_aromatic() {
+        let m = parse_molecule("c1ccccc1-c1cc[nH]c1").unwrap().1;
+        assert_eq!(m.count_atoms(), 11);
+        let orders: Vec<BondOrder> = m.graph.edge_weights().map(|b| b.order).collect();
+        assert_eq!(
+            orders.iter().filter(|o| **o == BondOrder::Aromatic).count(),
+            11
+        );
+        assert_eq!(
+            m.get_bond_by_atoms(NodeIndex::new(5), NodeIndex::new(6))
+                .unwrap()
+                .order,
+            BondOrder::Single
+        );
+        assert_eq!(m.formula().to_string(), "C10H9N");
+    }
+
+    #[test]
+    fn parse_molecule_aromatic_implicit_single() {
+        let implicit = parse_molecule("c1ccccc1c1ccncc1").unwrap().1;
+        let explicit = parse_molecule("c1ccccc1-c1ccncc1").unwrap().1;
+        assert_eq!(
+            implicit
+                .get_bond_by_atoms(NodeIndex::new(5), NodeIndex::new(6))
+                .unwrap()
+                .order,
+            BondOrder::Single
+        );
+        assert!(implicit.sanitize().is_empty());
+        assert_eq!(implicit.canonical_smiles(), explicit.canonical_smiles());
+    }
+
+    #[test]
+    fn parse_molecule_components() {
+        let m = parse_molecule("CC(=O)[O-].[NH4+]").unwrap().1;
+        assert_eq!(m.count_atoms(), 5);
+        assert_eq!(m.count_bonds(), 3);
+        assert!(parse_molecule("C(C.C)").is_err());
+    }
+
     #[test]
     fn molecule_weight() {
         let m = parse_molecule("C([H])([H])([H])(H)").unwrap().1;
-        assert_eq!(m.weight(), 16.0423);
+        assert!((m.weight().unwrap() - 16.043).abs() < 1e-9);
     }
 
     #[test]
@@ -0,0 +1,44 @@
+use crate::io::smiles::reader::molecule::parse_molecule;
+use crate::model::reaction::Reaction;
+use nom::character::complete::char;
+use nom::sequence::{terminated, tuple};
+use nom::IResult;
+
+/// Parses reaction SMILES like `CC(=O)O.OCC>>CC(=O)OCC`, or a reaction template with atom
+/// maps like `[C:1](=[O:2])O.[N:3]>>[C:1](=[O:2])[N:3]`. Any part may be empty.
+pub fn parse_reaction(input: &str) -> IResult<&str, Reaction> {
+    let (input, (reactants, agents, products)) = tuple((
+        terminated(parse_molecule, char('>')),
+        terminated(parse_molecule, char('>')),
+        parse_molecule,
+    ))(input)?;
+    Ok((
+        input,
+        Reaction {
+            reactants: reactants.fragments(),
+            agents: agents.fragments(),
+            products: products.fragments(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::NodeIndex;
+
+    #[test]
+    fn parse_reaction() {
+        let (rest, reaction) = super::parse_reaction("CC(=O)O.OCC>[H+]>CC(=O)OCC.O").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(reaction.reactants.len(), 2);
+        assert_eq!(reaction.agents.len(), 1);
+        assert_eq!(reaction.products.len(), 2);
+        assert_eq!(reaction.products[0].count_atoms(), 6);
+
+        let (_, reaction) = super::parse_reaction("[C:1]=[O:2]>>[C:1][O:2]").unwrap();
+        assert!(reaction.agents.is_empty());
+        assert_eq!(reaction.products[0].graph[NodeIndex::new(1)].map, 2);
+
+        assert!(super::parse_reaction("CCO").is_err());
+    }
+}
@@ -0,0 +1,608 @@
+use crate::chem::stereo::permutation_parity;
+use crate::model::atom::Chirality;
+use crate::model::bond::{BondOrder, DoubleBondStereo};
+use crate::model::molecule::Molecule;
+use crate::model::reaction::Reaction;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Atoms which may be written without square brackets.
+const ORGANIC_SUBSET: [u8; 10] = [5, 6, 7, 8, 9, 15, 16, 17, 35, 53];
+/// Elements with a lowercase aromatic symbol; selenium and arsenic only in square brackets.
+const AROMATIC_SYMBOLS: [u8; 8] = [5, 6, 7, 8, 15, 16, 33, 34];
+/// Limit of tie breaking orders compared for canonical SMILES with stereo.
+const MAX_STEREO_RANKINGS: usize = 256;
+
+impl Molecule {
+    /// SMILES with atoms in graph order and bonds as stored (Kekulé structures stay Kekulé).
+    pub fn to_smiles(&self) -> String {
+        let ranks = self
+            .graph
+            .node_indices()
+            .enumerate()
+            .map(|(i, n)| (n, i))
+            .collect();
+        Writer::new(self.clone(), self, ranks).write()
+    }
+
+    /// Canonical SMILES: equal for equal molecules regardless of atom order and of the
+    /// Kekulé structure of aromatic rings, which are written with lowercase atoms.
+    /// Tetrahedral and double bond stereo is kept; wedge bonds are ignored.
+    pub fn canonical_smiles(&self) -> String {
+        let mut work = self.clone();
+        for bond in self.aromaticity().bonds {
+            work.graph[bond].order = BondOrder::Aromatic;
+        }
+        // fix hydrogen counts, so that ranks do not depend on the bond representation
+        for atom in self.graph.node_indices() {
+            work.graph[atom].hs = self.total_hydrogens(atom);
+            work.graph[atom].no_implicit_hs = true;
+        }
+        if !work.has_stereo() {
+            let ranks = work.canonical_ranks();
+            return Writer::new(work, self, ranks).write();
+        }
+        // the smallest SMILES over all ways of breaking ties between equivalent atoms, which
+        // differ in the written configurations
+        work.canonical_rank_candidates(MAX_STEREO_RANKINGS)
+            .into_iter()
+            .map(|ranks| Writer::new(work.clone(), self, ranks).write())
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+impl Reaction {
+    /// Reaction SMILES `reactants>agents>products`, with the molecules of every part in
+    /// order and separated by dots.
+    pub fn to_smiles(&self) -> String {
+        let part = |molecules: &[Molecule]| {
+            let smiles: Vec<String> = molecules.iter().map(|m| m.to_smiles()).collect();
+            smiles.join(".")
+        };
+        format!(
+            "{}>{}>{}",
+            part(&self.reactants),
+            part(&self.agents),
+            part(&self.products)
+        )
+    }
+
+    /// Canonical reaction SMILES: the canonical SMILES of the molecules of every part,
+    /// sorted and separated by dots.
+    pub fn canonical_smiles(&self) -> String {
+        let part = |molecules: &[Molecule]| {
+            let mut smiles: Vec<String> = molecules.iter().map(|m| m.canonical_smiles()).collect();
+            smiles.sort();
+            smiles.join(".")
+        };
+        format!(
+            "{}>{}>{}",
+            part(&self.reactants),
+            part(&self.agents),
+            part(&self.products)
+        )
+    }
+}
+
+struct Writer {
+    /// Molecule with the bond orders to write.
+    molecule: Molecule,
+    /// Total hydrogen count of every atom.
+    hydrogens: HashMap<NodeIndex, u8>,
+    /// Hydrogens every atom would get when written without brackets.
+    implicit_hydrogens: HashMap<NodeIndex, u8>,
+    aromatic: HashSet<NodeIndex>,
+    /// Atoms are visited in order of increasing rank.
+    ranks: HashMap<NodeIndex, usize>,
+    visited: HashSet<NodeIndex>,
+    /// Ring closure bonds of every atom.
+    ring_closures: HashMap<NodeIndex, Vec<EdgeIndex>>,
+    children: HashMap<NodeIndex, Vec<(NodeIndex, EdgeIndex)>>,
+    /// Ring closure digits of the currently open ring bonds.
+    open_digits: HashMap<EdgeIndex, u8>,
+    /// Position of every atom in the output.
+    positions: HashMap<NodeIndex, usize>,
+    /// Bond to the parent atom in the depth-first tree.
+    parent_bonds: HashMap<NodeIndex, EdgeIndex>,
+    /// Directional single bonds written as `/` (true) or `\` (false).
+    directions: HashMap<EdgeIndex, bool>,
+}
+
+impl Writer {
+    fn new(molecule: Molecule, source: &Molecule, ranks: HashMap<NodeIndex, usize>) -> Writer {
+        let hydrogens = source
+            .graph
+            .node_indices()
+            .map(|a| (a, source.total_hydrogens(a)))
+            .collect();
+        let mut unbracketed = molecule.clone();
+        for atom in unbracketed.graph.node_weights_mut() {
+            atom.hs = 0;
+            atom.no_implicit_hs = false;
+        }
+        let implicit_hydrogens = unbracketed
+            .graph
+            .node_indices()
+            .map(|a| (a, unbracketed.implicit_hydrogens(a)))
+            .collect();
+        let aromatic = molecule
+            .graph
+            .node_indices()
+            .filter(|a| {
+                AROMATIC_SYMBOLS.contains(&molecule.graph[*a].element.atomic_number)
+                    && molecule
+                        .graph
+                        .edges(*a)
+                        .any(|e| e.weight().order == BondOrder::Aromatic)
+            })
+            .collect();
+        Writer {
+            molecule,
+            hydrogens,
+            implicit_hydrogens,
+            aromatic,
+            ranks,
+            visited: HashSet::new(),
+            ring_closures: HashMap::new(),
+            children: HashMap::new(),
+            open_digits: HashMap::new(),
+            positions: HashMap::new(),
+            parent_bonds: HashMap::new(),
+            directions: HashMap::new(),
+        }
+    }
+
+    fn write(mut self) -> String {
+        let mut atoms: Vec<NodeIndex> = self.molecule.graph.node_indices().collect();
+        atoms.sort_by_key(|a| self.ranks[a]);
+        let mut roots = Vec::new();
+        for atom in atoms {
+            if !self.visited.contains(&atom) {
+                self.build_tree(atom, None);
+                roots.push(atom);
+            }
+        }
+        self.assign_directions();
+        let mut components = Vec::new();
+        for root in roots {
+            let mut smiles = String::new();
+            self.write_atom(&mut smiles, root);
+            components.push(smiles);
+        }
+        components.join(".")
+    }
+
+    /// Depth-first traversal recording tree children and ring closure bonds.
+    fn build_tree(&mut self, atom: NodeIndex, parent: Option<EdgeIndex>) {
+        self.visited.insert(atom);
+        self.positions.insert(atom, self.positions.len());
+        if let Some(parent) = parent {
+            self.parent_bonds.insert(atom, parent);
+        }
+        let mut edges: Vec<(NodeIndex, EdgeIndex)> = self
+            .molecule
+            .graph
+            .edges(atom)
+            .map(|e| (other_end(e.source(), e.target(), atom), e.id()))
+            .filter(|(_, e)| Some(*e) != parent)
+            .collect();
+        edges.sort_by_key(|(n, _)| self.ranks[n]);
+        for (neighbor, edge) in edges {
+            if self.visited.contains(&neighbor) {
+                // ring closure to an atom already on the path, unless recorded from there
+                let closures = self.ring_closures.entry(atom).or_default();
+                if !closures.contains(&edge) {
+                    closures.push(edge);
+                    self.ring_closures.entry(neighbor).or_default().push(edge);
+                }
+            } else {
+                self.children
+                    .entry(atom)
+                    .or_default()
+                    .push((neighbor, edge));
+                self.build_tree(neighbor, Some(edge));
+            }
+        }
+    }
+
+    /// Marks single bonds next to stereo double bonds with directions which give their
+    /// configuration. Bonds shared by conjugated double bonds are marked only once.
+    fn assign_directions(&mut self) {
+        let mut double_bonds: Vec<(EdgeIndex, NodeIndex, NodeIndex)> = (&self.molecule.graph)
+            .edge_references()
+            .filter(|e| {
+                e.weight().order == BondOrder::Double
+                    && e.weight().double_bond_stereo != DoubleBondStereo::None
+            })
+            .map(|e| (e.id(), e.source(), e.target()))
+            .collect();
+        double_bonds.sort_by_key(|(_, a, b)| self.ranks[a].min(self.ranks[b]));
+        for (edge, a, b) in double_bonds {
+            let (Some(side_a), Some(side_b)) = (self.stereo_side(a, b), self.stereo_side(b, a))
+            else {
+                continue;
+            };
+            let cis = self.molecule.graph[edge].double_bond_stereo == DoubleBondStereo::Cis;
+            let sides = [(a, side_a), (b, side_b)];
+            // the side with a direction from an earlier double bond, if any, comes first
+            let [(first, (first_edge, first_swap, first_up)), (second, (second_edge, second_swap, _))] =
+                if side_a.2.is_none() && side_b.2.is_some() {
+                    [sides[1], sides[0]]
+                } else {
+                    sides
+                };
+            let first_up = match first_up {
+                Some(up) => up,
+                None => {
+                    self.set_direction(first_edge, first, true);
+                    true
+                }
+            };
+            if !self.directions.contains_key(&second_edge) {
+                // neighbors other than the reference ones flip the configuration
+                let same_side = cis ^ first_swap ^ second_swap;
+                self.set_direction(second_edge, second, first_up == same_side);
+            }
+        }
+    }
+
+    /// Bond to mark next to `atom` in the double bond to `partner`: the bond, whether its
+    /// other atom is not the reference neighbor of the configuration, and whether it points
+    /// up from `atom` if it already has a direction.
+    fn stereo_side(
+        &self,
+        atom: NodeIndex,
+        partner: NodeIndex,
+    ) -> Option<(EdgeIndex, bool, Option<bool>)> {
+        let neighbors = self.molecule.double_bond_neighbors(atom, partner);
+        if neighbors.len() > 2 {
+            return None;
+        }
+        let mut candidates: Vec<(NodeIndex, EdgeIndex)> = neighbors
+            .iter()
+            .filter_map(|n| Some((*n, self.molecule.graph.find_edge(atom, *n)?)))
+            .filter(|(_, e)| self.molecule.graph[*e].order == BondOrder::Single)
+            .collect();
+        let marked = candidates
+            .iter()
+            .find(|(_, e)| self.directions.contains_key(e))
+            .copied();
+        // prefer tree bonds over ring closures, then low ranks
+        candidates.sort_by_key(|(n, e)| (self.is_ring_closure(*e), self.ranks[n]));
+        let (neighbor, edge) = marked.or(candidates.first().copied())?;
+        let up = self
+            .directions
+            .get(&edge)
+            .map(|direction| *direction == (self.right_atom(edge) == atom));
+        Some((edge, neighbor != neighbors[0], up))
+    }
+
+    /// Marks `edge` so that it points up (or down) from `atom`.
+    fn set_direction(&mut self, edge: EdgeIndex, atom: NodeIndex, up: bool) {
+        let direction = up == (self.right_atom(edge) == atom);
+        self.directions.insert(edge, direction);
+    }
+
+    fn is_ring_closure(&self, edge: EdgeIndex) -> bool {
+        let (a, b) = self.molecule.graph.edge_endpoints(edge).unwrap();
+        self.parent_bonds.get(&a) != Some(&edge) && self.parent_bonds.get(&b) != Some(&edge)
+    }
+
+    /// Atom written after the bond symbol: the child of a tree bond, or the atom which
+    /// opened a ring closure, as the symbol is written at the closing digit.
+    fn right_atom(&self, edge: EdgeIndex) -> NodeIndex {
+        let (a, b) = self.molecule.graph.edge_endpoints(edge).unwrap();
+        if self.parent_bonds.get(&a) == Some(&edge) {
+            a
+        } else if self.parent_bonds.get(&b) == Some(&edge) {
+            b
+        } else if self.positions[&a] < self.positions[&b] {
+            a
+        } else {
+            b
+        }
+    }
+
+    fn write_atom(&mut self, smiles: &mut String, atom: NodeIndex) {
+        let closures = self.ring_closures.remove(&atom).unwrap_or_default();
+        // close rings first, so that their digits can be reused by the rings opened here
+        let (closing, opening): (Vec<EdgeIndex>, Vec<EdgeIndex>) = closures
+            .into_iter()
+            .partition(|e| self.open_digits.contains_key(e));
+        let children = self.children.remove(&atom).unwrap_or_default();
+
+        // neighbors in output order: parent, hydrogen, ring closures, children
+        let mut written: Vec<Option<NodeIndex>> = Vec::new();
+        let neighbor = |edge: &EdgeIndex| {
+            let (a, b) = self.molecule.graph.edge_endpoints(*edge).unwrap();
+            other_end(a, b, atom)
+        };
+        written.extend(self.parent_bonds.get(&atom).map(|e| Some(neighbor(e))));
+        if self.hydrogens[&atom] == 1 {
+            written.push(None);
+        }
+        written.extend(closing.iter().chain(&opening).map(|e| Some(neighbor(e))));
+        written.extend(children.iter().map(|(child, _)| Some(*child)));
+        let chirality = self.molecule.graph[atom].chirality;
+        let chirality = match permutation_parity(&self.molecule.stereo_neighbors(atom), &written) {
+            _ if written.len() < 3 || self.hydrogens[&atom] > 1 => Chirality::None,
+            Some(false) => chirality,
+            Some(true) => chirality.inverted(),
+            None => Chirality::None,
+        };
+        smiles.push_str(&self.atom_symbol(atom, chirality));
+
+        for edge in closing {
+            let digit = self.open_digits.remove(&edge).unwrap();
+            smiles.push_str(&self.bond_symbol(edge));
+            smiles.push_str(&digit_symbol(digit));
+        }
+        for edge in opening {
+            let used: BTreeSet<u8> = self.open_digits.values().copied().collect();
+            let digit = (1..).find(|d| !used.contains(d)).unwrap();
+            self.open_digits.insert(edge, digit);
+            smiles.push_str(&digit_symbol(digit));
+        }
+
+        let last = children.len().saturating_sub(1);
+        for (i, (child, edge)) in children.into_iter().enumerate() {
+            if i != last {
+                smiles.push('(');
+            }
+            smiles.push_str(&self.bond_symbol(edge));
+            self.write_atom(smiles, child);
+            if i != last {
+                smiles.push(')');
+            }
+        }
+    }
+
+    fn bond_symbol(&self, edge: EdgeIndex) -> String {
+        let (a, b) = self.molecule.graph.edge_endpoints(edge).unwrap();
+        let aromatic = self.aromatic.contains(&a) && self.aromatic.contains(&b);
+        if let Some(direction) = self.directions.get(&edge) {
+            return if *direction { "/" } else { "\\" }.to_owned();
+        }
+        match self.molecule.graph[edge].order {
+            BondOrder::Single if aromatic => "-",
+            BondOrder::Single => "",
+            BondOrder::Double => "=",
+            BondOrder::Triple => "#",
+            BondOrder::Aromatic if aromatic => "",
+            BondOrder::Aromatic => ":",
+        }
+        .to_owned()
+    }
+
+    fn atom_symbol(&self, atom: NodeIndex, chirality: Chirality) -> String {
+        let a = &self.molecule.graph[atom];
+        let symbol = a.element.symbol().unwrap_or("*");
+        let symbol = if self.aromatic.contains(&atom) {
+            symbol.to_lowercase()
+        } else {
+            symbol.to_owned()
+        };
+        let hydrogens = self.hydrogens[&atom];
+        if a.is_wildcard() && a.isotope == 0 && a.charge == 0 && hydrogens == 0 && a.map == 0 {
+            return symbol;
+        }
+        if ORGANIC_SUBSET.contains(&a.element.atomic_number)
+            && a.isotope == 0
+            && a.map == 0
+            && a.charge == 0
+            && self.implicit_hydrogens[&atom] == hydrogens
+            && chirality == Chirality::None
+        {
+            return symbol;
+        }
+
+        let mut bracket = String::from("[");
+        if a.isotope != 0 {
+            bracket.push_str(&a.isotope.to_string());
+        }
+        bracket.push_str(&symbol);
+        match chirality {
+            Chirality::None => {}
+            Chirality::AntiClockwise => bracket.push('@'),
+            Chirality::Clockwise => bracket.push_str("@@"),
+        }
+        match hydrogens {
+            0 => {}
+            1 => bracket.push('H'),
+            n => bracket.push_str(&format!("H{}", n)),
+        }
+        match a.charge {
+            0 => {}
+            1 => bracket.push('+'),
+            -1 => bracket.push('-'),
+            c => bracket.push_str(&format!("{:+}", c)),
+        }
+        if a.map != 0 {
+            bracket.push_str(&format!(":{}", a.map));
+        }
+        bracket.push(']');
+        bracket
+    }
+}
+
+fn other_end(source: NodeIndex, target: NodeIndex, atom: NodeIndex) -> NodeIndex {
+    if source == atom {
+        target
+    } else {
+        source
+    }
+}
+
+fn digit_symbol(digit: u8) -> String {
+    if digit < 10 {
+        digit.to_string()
+    } else {
+        format!("%{}", digit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::molecule::Molecule;
+    use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+    use std::collections::HashMap;
+
+    /// Copy of the molecule with atoms added in the order given by `key` and bonds reversed.
+    fn reorder(m: &Molecule, key: impl Fn(usize) -> usize) -> Molecule {
+        let mut nodes: Vec<_> = m.graph.node_indices().collect();
+        nodes.sort_by_key(|n| key(n.index()));
+        let mut copy = Molecule::new();
+        let mapping: HashMap<_, _> = nodes
+            .iter()
+            .map(|n| (*n, copy.add_atom(m.graph[*n].clone())))
+            .collect();
+        let mut edges: Vec<_> = (&m.graph).edge_references().collect();
+        edges.reverse();
+        for edge in edges {
+            copy.add_bond(
+                mapping[&edge.target()],
+                mapping[&edge.source()],
+                edge.weight().clone(),
+            );
+        }
+        copy
+    }
+
+    fn roundtrip(smiles: &str) -> String {
+        parse_molecule(smiles).unwrap().1.to_smiles()
+    }
+
+    fn canonical(smiles: &str) -> String {
+        parse_molecule(smiles).unwrap().1.canonical_smiles()
+    }
+
+    #[test]
+    fn test_to_smiles() {
+        assert_eq!(roundtrip("CCO"), "CCO");
+        assert_eq!(roundtrip("CC(C)(C)O"), "CC(C)(C)O");
+        assert_eq!(roundtrip("C1=CC=CC=C1"), "C1=CC=CC=C1");
+        assert_eq!(roundtrip("C1CC2CCC1CC2"), "C1CC2CCC1CC2");
+        assert_eq!(roundtrip("[13CH3][NH3+]"), "[13CH3][NH3+]");
+        assert_eq!(roundtrip("[O-2].[CH3]"), "[O-2].[CH3]");
+        assert_eq!(roundtrip("C#N"), "C#N");
+        assert_eq!(roundtrip("c1ccc2cc[nH]c2c1"), "c1ccc2cc[nH]c2c1");
+        assert_eq!(roundtrip("c1ccccc1-c1ccccc1"), "c1ccccc1-c1ccccc1");
+        assert_eq!(roundtrip("*CC[3*]"), "*CC[3*]");
+        assert_eq!(roundtrip("[*]C"), "*C");
+        assert_eq!(roundtrip("[*:1]C[CH2:2]O"), "[*:1]C[CH2:2]O");
+        assert_eq!(roundtrip("C[C@H](N)O"), "C[C@H](N)O");
+        assert_eq!(roundtrip("[C@@H](C)(N)O"), "[C@@H](C)(N)O");
+        assert_eq!(roundtrip("C[C@@H]1CCCCO1"), "C[C@@H]1CCCCO1");
+        assert_eq!(roundtrip("F/C=C/F"), "F/C=C/F");
+        assert_eq!(roundtrip("F/C=C(/Cl)C"), "F/C=C(/Cl)C");
+        assert_eq!(roundtrip("C/C=C\\C=C\\C"), "C/C=C\\C=C\\C");
+    }
+
+    #[test]
+    fn test_to_smiles_reparses() {
+        for smiles in [
+            "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O",
+            "C1CC2(C1)CCCC2",
+            "N1C(=P)S=1O",
+            "c1ccsc1",
+            "CC(=O)[O-].[NH4+]",
+        ] {
+            let m = parse_molecule(smiles).unwrap().1;
+            let written = m.to_smiles();
+            let reparsed = parse_molecule(&written).unwrap();
+            assert_eq!(reparsed.0, "");
+            assert_eq!(reparsed.1.formula(), m.formula(), "{}", written);
+            assert_eq!(reparsed.1.count_bonds(), m.count_bonds(), "{}", written);
+            assert_eq!(reparsed.1.canonical_smiles(), m.canonical_smiles());
+        }
+    }
+
+    #[test]
+    fn test_canonical_smiles() {
+        assert_eq!(canonical("OCC"), canonical("CCO"));
+        assert_eq!(canonical("C1=CC=CC=C1"), "c1ccccc1");
+        assert_eq!(canonical("C1=CC=CC=C1"), canonical("c1ccccc1"));
+        // Kekulé structures of naphthalene and indole
+        assert_eq!(
+            canonical("C1=CC=C2C=CC=CC2=C1"),
+            canonical("C1=CC2=CC=CC=C2C=C1")
+        );
+        assert_eq!(
+            canonical("C1=CC=C2C(=C1)C=CN2"),
+            canonical("N1C=CC2=CC=CC=C12")
+        );
+        assert!(canonical("C1=CC=C2C(=C1)C=CN2").contains("[nH]"));
+        assert_eq!(
+            canonical("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O"),
+            canonical("OC(=O)C(C)C1=CC=C(CC(C)C)C=C1")
+        );
+        assert_ne!(canonical("CCO"), canonical("COC"));
+        assert_eq!(
+            canonical("[NH4+].CC(=O)[O-]"),
+            canonical("CC([O-])=O.[NH4+]")
+        );
+        assert_eq!(
+            canonical("CC(C)CC1=CC=C(C=C1)C(C)C(=O)O"),
+            "CC(C)Cc1ccc(cc1)C(C)C(=O)O"
+        );
+        assert_eq!(
+            canonical("C1=CC=C2C=C3C=CC=CC3=CC2=C1"),
+            "c1ccc2cc3ccccc3cc2c1"
+        );
+    }
+
+    #[test]
+    fn test_canonical_smiles_atom_order() {
+        for smiles in [
+            "CC(C1=CC2=C(C=C1)C=C(C=C2)OC)C(=O)O",
+            "CN1C=NC2=C1C(=O)N(C(=O)N2C)C",
+            "C12C3C4C1C5C2C3C45",
+            "C1CCC2(CC1)CCCCC2",
+            "OCC(O)C(O)C(O)C(O)CO",
+        ] {
+            let m = parse_molecule(smiles).unwrap().1;
+            let expected = m.canonical_smiles();
+            // sorting by i * factor modulo a prime shuffles the atoms
+            for factor in [2, 3, 5000, 7919] {
+                let reordered = reorder(&m, |i| i * factor % 10007);
+                assert_eq!(reordered.canonical_smiles(), expected, "{}", smiles);
+            }
+            let reparsed = parse_molecule(&expected).unwrap().1;
+            assert_eq!(reparsed.canonical_smiles(), expected);
+        }
+    }
+
+    #[test]
+    fn test_canonical_smiles_sanitizes() {
+        for smiles in [
+            "CN1C=NC2=C1C(=O)N(C)C(=O)N2C",
+            "O=C1C=CC=CN1",
+            "C1=CC=C[CH-]1",
+            "C1=CC=C2C(=C1)C=CN2",
+            "CC(C)CC1=CC=C(C=C1)C(C)C(=O)O",
+            "C1=CC=C(C=C1)C1=CC=NC=C1",
+            "CB(C)C",
+            "c1cc[se]c1",
+            "c1cc[asH]c1",
+        ] {
+            let m = parse_molecule(smiles).unwrap().1;
+            assert!(m.sanitize().is_empty(), "{}", smiles);
+            let written = m.canonical_smiles();
+            let reparsed = parse_molecule(&written).unwrap();
+            assert_eq!(reparsed.0, "");
+            assert!(reparsed.1.sanitize().is_empty(), "{}", written);
+            assert_eq!(reparsed.1.canonical_smiles(), written);
+        }
+    }
+
+    #[test]
+    fn test_reaction_smiles() {
+        use crate::io::smiles::reader::reaction::parse_reaction;
+        let reaction = parse_reaction("OCC.CC(=O)O>>CC(=O)OCC.O").unwrap().1;
+        assert_eq!(reaction.to_smiles(), "OCC.CC(=O)O>>CC(=O)OCC.O");
+        assert_eq!(reaction.canonical_smiles(), "CC(=O)O.CCO>>CCOC(C)=O.O");
+    }
+}
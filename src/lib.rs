@@ -1,5 +1,7 @@
+pub mod chem;
 pub mod db;
+pub mod depiction;
 mod descriptors;
-mod io;
+pub mod io;
 mod math;
-mod model;
+pub mod model;
@@ -1,5 +1,15 @@
+pub mod adduct;
 pub mod atom;
 pub mod bond;
 pub mod element;
 pub mod fingerprint;
+pub mod formula;
+pub mod isotope_pattern;
+pub mod isotope_table;
 pub mod molecule;
+pub mod periodic_table;
+pub mod point;
+pub mod reaction;
+pub mod ring_descriptors;
+#[cfg(feature = "serde")]
+mod serialization;
@@ -0,0 +1,41 @@
+use crate::model::element::Element;
+
+pub const ELECTRON_MASS: f64 = 0.000548579909;
+pub const PROTON_MASS: f64 = 1.007276466621;
+
+/// Common mass spectrometry adducts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adduct {
+    /// [M+H]+
+    MPlusH,
+    /// [M-H]-
+    MMinusH,
+    /// [M+Na]+
+    MPlusNa,
+    /// [M+K]+
+    MPlusK,
+    /// [M+NH4]+
+    MPlusNH4,
+}
+
+impl Adduct {
+    /// Mass added to the molecule.
+    pub fn mass_delta(&self) -> f64 {
+        let mass = |z: u8| Element { atomic_number: z }.monoisotopic_mass().unwrap();
+        match self {
+            Adduct::MPlusH => PROTON_MASS,
+            Adduct::MMinusH => -PROTON_MASS,
+            Adduct::MPlusNa => mass(11) - ELECTRON_MASS,
+            Adduct::MPlusK => mass(19) - ELECTRON_MASS,
+            Adduct::MPlusNH4 => mass(7) + 4.0 * mass(1) - ELECTRON_MASS,
+        }
+    }
+
+    /// Charge added to the molecule.
+    pub fn charge(&self) -> i32 {
+        match self {
+            Adduct::MMinusH => -1,
+            _ => 1,
+        }
+    }
+}
@@ -1,11 +1,89 @@
 use crate::model::element::Element;
+use crate::model::point::Point;
+use bincode::{Decode, Encode};
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+/// Tetrahedral configuration as in SMILES `@` and `@@`: looking from the first neighbor in
+/// stereo order (see `Molecule::stereo_neighbors`), the other three are arranged
+/// anticlockwise or clockwise.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Encode, Decode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Chirality {
+    #[default]
+    None,
+    AntiClockwise,
+    Clockwise,
+}
+
+impl Chirality {
+    pub fn inverted(self) -> Chirality {
+        match self {
+            Chirality::None => Chirality::None,
+            Chirality::AntiClockwise => Chirality::Clockwise,
+            Chirality::Clockwise => Chirality::AntiClockwise,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Encode, Decode)]
 pub struct Atom {
     pub element: Element,
-    pub isotope: u8,
+    pub isotope: u16,
     pub charge: i8,
     pub hs: u8,
+    /// Set for atoms written in square brackets: their hydrogen count is given by `hs` only
+    /// and no implicit hydrogens are added to fill the valence.
+    pub no_implicit_hs: bool,
+    /// Set for atoms written with a lowercase aromatic symbol, like `c` or `[nH]`. Substructure
+    /// queries match such atoms only to aromatic atoms, also outside of a complete ring.
+    pub aromatic: bool,
+    /// 2D depiction coordinates, if known. See `Molecule::generate_2d_coords`.
+    pub coords: Option<Point>,
+    pub chirality: Chirality,
+    /// Atom map number (atom class) as in SMILES `[CH3:1]`, 0 if unmapped. Marks attachment
+    /// points of library scaffolds and the atom mapping of reactions.
+    pub map: u16,
+}
+
+impl Atom {
+    /// Wildcard atom `*` (atomic number 0), e.g. an attachment point of a fragment. The
+    /// isotope field holds the attachment label, written as `[1*]`.
+    pub fn wildcard(label: u16) -> Atom {
+        Atom {
+            element: Element { atomic_number: 0 },
+            isotope: label,
+            charge: 0,
+            hs: 0,
+            no_implicit_hs: true,
+            aromatic: false,
+            coords: None,
+            chirality: Chirality::None,
+            map: 0,
+        }
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        self.element.atomic_number == 0
+    }
+
+    /// Exact mass of the labelled isotope, or of the most abundant isotope if unlabelled.
+    pub fn exact_mass(&self) -> Option<f64> {
+        match self.isotope {
+            0 => self.element.monoisotopic_mass(),
+            isotope => self.element.isotope(isotope).map(|i| i.exact_mass),
+        }
+    }
+
+    /// Exact mass of the labelled isotope, or the standard atomic weight if unlabelled.
+    pub fn average_mass(&self) -> Option<f64> {
+        match self.isotope {
+            0 => self.element.atomic_weight(),
+            _ => self.exact_mass(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -19,10 +97,43 @@ mod tests {
             isotope: 12,
             charge: 0,
             hs: 0,
+            no_implicit_hs: false,
+            aromatic: false,
+            coords: None,
+            chirality: Chirality::None,
+            map: 0,
         };
         assert_eq!(atom.element, Element { atomic_number: 6 });
         assert_eq!(atom.isotope, 12);
         assert_eq!(atom.charge, 0);
         assert_eq!(atom.hs, 0);
+        assert!(!atom.no_implicit_hs);
+        assert_eq!(atom.exact_mass(), Some(12.0));
+        assert_eq!(atom.average_mass(), Some(12.0));
+    }
+
+    #[test]
+    fn test_atom_mass() {
+        let mut atom = Atom {
+            element: Element { atomic_number: 1 },
+            isotope: 0,
+            charge: 0,
+            hs: 0,
+            no_implicit_hs: true,
+            aromatic: false,
+            coords: None,
+            chirality: Chirality::None,
+            map: 0,
+        };
+        assert_eq!(atom.exact_mass(), Some(1.00782503223));
+        assert_eq!(atom.average_mass(), Some(1.008));
+        atom.isotope = 2;
+        assert_eq!(atom.exact_mass(), Some(2.01410177812));
+        assert_eq!(atom.average_mass(), Some(2.01410177812));
+        atom.isotope = 7;
+        assert_eq!(atom.exact_mass(), None);
+        atom.element = Element { atomic_number: 92 };
+        atom.isotope = 238;
+        assert_eq!(atom.exact_mass(), Some(238.0507884));
     }
 }
@@ -1,4 +1,11 @@
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+use bincode::{Decode, Encode};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Encode, Decode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum BondOrder {
     Single,
     Double,
@@ -6,8 +13,64 @@ pub enum BondOrder {
     Aromatic,
 }
 
+/// Wedge/hash depiction of a stereo bond. The narrow end is at the first atom of the bond,
+/// i.e. the atom passed first to `Molecule::add_bond`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Encode, Decode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum BondStereo {
+    #[default]
+    None,
+    /// Bond pointing towards the viewer.
+    Wedge,
+    /// Bond pointing away from the viewer.
+    Hash,
+}
+
+/// Configuration of a double bond, relative to the lowest-index neighbor (other than the
+/// partner atom) at each end of the bond.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Encode, Decode)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum DoubleBondStereo {
+    #[default]
+    None,
+    Cis,
+    Trans,
+}
+
+impl DoubleBondStereo {
+    pub fn inverted(self) -> DoubleBondStereo {
+        match self {
+            DoubleBondStereo::None => DoubleBondStereo::None,
+            DoubleBondStereo::Cis => DoubleBondStereo::Trans,
+            DoubleBondStereo::Trans => DoubleBondStereo::Cis,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
 pub struct Bond {
     pub order: BondOrder,
+    pub stereo: BondStereo,
+    pub double_bond_stereo: DoubleBondStereo,
+}
+
+impl Bond {
+    /// Bond of the given order without stereo.
+    pub fn new(order: BondOrder) -> Bond {
+        Bond {
+            order,
+            stereo: BondStereo::None,
+            double_bond_stereo: DoubleBondStereo::None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -16,9 +79,9 @@ mod tests {
 
     #[test]
     fn test_bond_order() {
-        let bond = Bond {
-            order: BondOrder::Single,
-        };
+        let bond = Bond::new(BondOrder::Single);
         assert_eq!(bond.order, BondOrder::Single);
+        assert_eq!(bond.stereo, BondStereo::None);
+        assert_eq!(bond.double_bond_stereo, DoubleBondStereo::None);
     }
 }
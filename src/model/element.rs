@@ -1,95 +1,124 @@
-#[derive(Hash, Eq, PartialEq, Debug)]
+use crate::model::isotope_table::ISOTOPES;
+use crate::model::periodic_table::ELEMENTS;
+use bincode::{Decode, Encode};
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Encode, Decode)]
 pub struct Element {
     pub atomic_number: u8,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ElementData {
+    pub symbol: &'static str,
+    pub name: &'static str,
+    /// Standard atomic weight (average mass), g/mol.
+    pub atomic_weight: f64,
+    /// Exact mass of the most abundant isotope, u.
+    pub monoisotopic_mass: f64,
+    /// Mass number of the most abundant (or the longest-lived) isotope.
+    pub most_abundant_isotope: u16,
+    /// Allowed valences of a neutral atom in increasing order.
+    /// Empty for elements without well-defined covalent valences (e.g. transition metals).
+    pub valences: &'static [u8],
+    /// Pauling electronegativity.
+    pub electronegativity: Option<f64>,
+    pub period: u8,
+    /// IUPAC group 1-18, None for lanthanides and actinides except La and Ac.
+    pub group: Option<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Isotope {
+    pub mass_number: u16,
+    /// Exact mass, u.
+    pub exact_mass: f64,
+    /// Natural abundance as a fraction of 1, zero for synthetic isotopes.
+    pub abundance: f64,
+}
+
 impl Element {
-    pub fn atomic_weight(&self) -> f64 {
-        match self.atomic_number {
-            1 => 1.007825,
-            2 => 4.002603,
-            3 => 6.938,
-            4 => 9.0121831,
-            5 => 10.806,
-            6 => 12.011,
-            7 => 14.007,
-            8 => 15.999,
-            9 => 18.998403163,
-            10 => 20.180,
-            11 => 22.990,
-            12 => 24.305,
-            13 => 26.9815385,
-            14 => 28.085,
-            15 => 30.973761998,
-            16 => 32.06,
-            17 => 35.45,
-            18 => 39.948,
-            19 => 39.0983,
-            20 => 40.078,
-            21 => 44.955908,
-            22 => 47.867,
-            23 => 50.9415,
-            24 => 51.9961,
-            25 => 54.938044,
-            26 => 55.845,
-            27 => 58.933194,
-            28 => 58.6934,
-            29 => 63.546,
-            30 => 65.38,
-            31 => 69.723,
-            32 => 72.630,
-            33 => 74.921595,
-            34 => 78.96,
-            35 => 79.904,
-            36 => 83.798,
-            37 => 85.4678,
-            38 => 87.62,
-            39 => 88.90584,
-            40 => 91.224,
-            41 => 92.90637,
-            42 => 95.95,
-            43 => 98.0,
-            44 => 101.07,
-            45 => 102.90550,
-            46 => 106.42,
-            47 => 107.8682,
-            48 => 112.414,
-            49 => 114.818,
-            50 => 118.710,
-            51 => 121.760,
-            52 => 127.60,
-            53 => 126.90447,
-            54 => 131.293,
-            55 => 132.90545196,
-            56 => 137.327,
-            57 => 138.90547,
-            58 => 140.116,
-            59 => 140.90766,
-            60 => 144.242,
-            61 => 145.0,
-            62 => 150.36,
-            63 => 151.964,
-            64 => 157.25,
-            65 => 158.92535,
-            66 => 162.500,
-            67 => 164.93033,
-            68 => 167.259,
-            69 => 168.93422,
-            70 => 173.054,
-            71 => 174.9668,
-            72 => 178.49,
-            73 => 180.94788,
-            74 => 183.84,
-            75 => 186.207,
-            76 => 190.23,
-            77 => 192.217,
-            78 => 195.084,
-            79 => 196.966569,
-            80 => 200.592,
-            81 => 204.38,
-            82 => 207.2,
-            83 => 208.98040,
-            _ => panic!("Unsupported atomic number {}", self.atomic_number),
+    /// Returns None if there is no element with such atomic number.
+    pub fn new(atomic_number: u8) -> Option<Element> {
+        if (1..=ELEMENTS.len()).contains(&(atomic_number as usize)) {
+            Some(Element { atomic_number })
+        } else {
+            None
+        }
+    }
+
+    /// Looks up an element by its case-sensitive symbol, e.g. `Cl`.
+    pub fn from_symbol(symbol: &str) -> Option<Element> {
+        ELEMENTS
+            .iter()
+            .position(|e| e.symbol == symbol)
+            .map(|i| Element {
+                atomic_number: i as u8 + 1,
+            })
+    }
+
+    pub fn data(&self) -> Option<&'static ElementData> {
+        ELEMENTS.get((self.atomic_number as usize).checked_sub(1)?)
+    }
+
+    pub fn symbol(&self) -> Option<&'static str> {
+        self.data().map(|d| d.symbol)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        self.data().map(|d| d.name)
+    }
+
+    pub fn atomic_weight(&self) -> Option<f64> {
+        self.data().map(|d| d.atomic_weight)
+    }
+
+    pub fn monoisotopic_mass(&self) -> Option<f64> {
+        self.data().map(|d| d.monoisotopic_mass)
+    }
+
+    pub fn most_abundant_isotope(&self) -> Option<u16> {
+        self.data().map(|d| d.most_abundant_isotope)
+    }
+
+    /// Known isotopes sorted by mass number, empty for unknown elements.
+    pub fn isotopes(&self) -> &'static [Isotope] {
+        match (self.atomic_number as usize).checked_sub(1) {
+            Some(i) if i < ISOTOPES.len() => ISOTOPES[i],
+            _ => &[],
+        }
+    }
+
+    pub fn isotope(&self, mass_number: u16) -> Option<&'static Isotope> {
+        self.isotopes()
+            .iter()
+            .find(|i| i.mass_number == mass_number)
+    }
+
+    /// Allowed valences of a neutral atom, in increasing order.
+    /// Empty for unknown elements and elements without well-defined covalent valences.
+    pub fn valences(&self) -> &'static [u8] {
+        self.data().map_or(&[], |d| d.valences)
+    }
+
+    pub fn electronegativity(&self) -> Option<f64> {
+        self.data()?.electronegativity
+    }
+
+    pub fn period(&self) -> Option<u8> {
+        self.data().map(|d| d.period)
+    }
+
+    pub fn group(&self) -> Option<u8> {
+        self.data()?.group
+    }
+
+    /// Number of valence electrons of main group elements.
+    pub fn valence_electrons(&self) -> Option<u8> {
+        match self.group()? {
+            g @ 1..=2 => Some(g),
+            g @ 13..=18 if self.atomic_number != 2 => Some(g - 10),
+            18 => Some(2),
+            _ => None,
         }
     }
 }
@@ -97,10 +126,113 @@ impl Element {
 #[cfg(test)]
 mod test {
     use crate::model::element::Element;
+    use crate::model::periodic_table::ELEMENTS;
 
     #[test]
     fn test_element() {
         let element = Element { atomic_number: 1 };
-        assert_eq!(element.atomic_weight(), 1.007825);
+        assert_eq!(element.atomic_weight(), Some(1.008));
+        assert_eq!(element.monoisotopic_mass(), Some(1.00782503223));
+        assert_eq!(element.symbol(), Some("H"));
+        assert_eq!(element.name(), Some("Hydrogen"));
+    }
+
+    #[test]
+    fn test_element_unknown() {
+        assert!(Element::new(0).is_none());
+        assert!(Element::new(119).is_none());
+        let element = Element { atomic_number: 200 };
+        assert_eq!(element.atomic_weight(), None);
+        assert_eq!(element.symbol(), None);
+        assert!(element.valences().is_empty());
+    }
+
+    #[test]
+    fn test_element_from_symbol() {
+        assert_eq!(
+            Element::from_symbol("C"),
+            Some(Element { atomic_number: 6 })
+        );
+        assert_eq!(
+            Element::from_symbol("Cl"),
+            Some(Element { atomic_number: 17 })
+        );
+        assert_eq!(
+            Element::from_symbol("Og"),
+            Some(Element { atomic_number: 118 })
+        );
+        assert_eq!(Element::from_symbol("cl"), None);
+        assert_eq!(Element::from_symbol("Xx"), None);
+    }
+
+    #[test]
+    fn test_element_table_symbols_unique() {
+        for (i, e) in ELEMENTS.iter().enumerate() {
+            assert_eq!(
+                Element::from_symbol(e.symbol).unwrap().atomic_number as usize,
+                i + 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_element_properties() {
+        let fe = Element::from_symbol("Fe").unwrap();
+        assert_eq!(fe.period(), Some(4));
+        assert_eq!(fe.group(), Some(8));
+        assert_eq!(fe.most_abundant_isotope(), Some(56));
+        assert_eq!(fe.electronegativity(), Some(1.83));
+        assert_eq!(Element::from_symbol("Ce").unwrap().group(), None);
+        assert_eq!(
+            Element::from_symbol("Ne").unwrap().electronegativity(),
+            None
+        );
+        assert_eq!(Element::from_symbol("Og").unwrap().period(), Some(7));
+    }
+
+    #[test]
+    fn test_element_isotopes() {
+        let c = Element { atomic_number: 6 };
+        assert_eq!(c.isotope(13).unwrap().exact_mass, 13.00335483507);
+        assert_eq!(c.isotope(13).unwrap().abundance, 0.0107);
+        assert_eq!(c.isotope(14).unwrap().abundance, 0.0);
+        assert!(c.isotope(20).is_none());
+        assert!(Element { atomic_number: 0 }.isotopes().is_empty());
+        assert!(Element { atomic_number: 119 }.isotopes().is_empty());
+    }
+
+    #[test]
+    fn test_isotope_table_consistency() {
+        for (i, e) in ELEMENTS.iter().enumerate() {
+            let element = Element {
+                atomic_number: i as u8 + 1,
+            };
+            let isotopes = element.isotopes();
+            assert!(!isotopes.is_empty(), "{}", e.symbol);
+            assert_eq!(
+                element.isotope(e.most_abundant_isotope).unwrap().exact_mass,
+                e.monoisotopic_mass,
+                "{}",
+                e.symbol
+            );
+            let total: f64 = isotopes.iter().map(|i| i.abundance).sum();
+            assert!(total == 0.0 || (total - 1.0).abs() < 1e-3, "{}", e.symbol);
+        }
+    }
+
+    #[test]
+    fn test_element_valences() {
+        assert_eq!(Element { atomic_number: 6 }.valences(), &[4]);
+        assert_eq!(Element { atomic_number: 16 }.valences(), &[2, 4, 6]);
+        assert!(Element { atomic_number: 26 }.valences().is_empty());
+    }
+
+    #[test]
+    fn test_element_valence_electrons() {
+        assert_eq!(Element { atomic_number: 1 }.valence_electrons(), Some(1));
+        assert_eq!(Element { atomic_number: 2 }.valence_electrons(), Some(2));
+        assert_eq!(Element { atomic_number: 7 }.valence_electrons(), Some(5));
+        assert_eq!(Element { atomic_number: 53 }.valence_electrons(), Some(7));
+        assert_eq!(Element { atomic_number: 26 }.valence_electrons(), None);
     }
 }
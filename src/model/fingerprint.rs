@@ -1,12 +1,38 @@
 use bincode::de::BorrowDecoder;
 use bincode::error::{DecodeError, EncodeError};
 use fixedbitset::{Block, FixedBitSet};
+use petgraph::stable_graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub const FINGERPRINT_SIZE: usize = 512;
 
 #[derive(Debug)]
 pub struct Fingerprint(pub FixedBitSet);
 
+/// Atom environments that set each bit of a fingerprint. An environment is the path of
+/// atoms walked from the root atom when the bit was set, root first.
+#[derive(Debug, Default, Clone)]
+pub struct BitProvenance {
+    pub environments: BTreeMap<usize, Vec<Vec<NodeIndex>>>,
+}
+
+impl BitProvenance {
+    pub fn add(&mut self, bit: usize, environment: Vec<NodeIndex>) {
+        self.environments.entry(bit).or_default().push(environment);
+    }
+
+    /// All atoms of all environments that set the bit.
+    pub fn atoms(&self, bit: usize) -> BTreeSet<NodeIndex> {
+        self.environments
+            .get(&bit)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
 impl bincode::Encode for Fingerprint {
     fn encode<E: bincode::enc::Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.0.as_slice().encode(encoder)?;
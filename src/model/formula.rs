@@ -0,0 +1,179 @@
+use crate::model::element::Element;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Molecular formula: atom counts by element and isotope plus the total charge.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Formula {
+    /// Counts by (atomic number, mass number), mass number 0 means natural isotopic composition.
+    pub counts: BTreeMap<(u8, u16), u32>,
+    pub charge: i32,
+}
+
+impl Formula {
+    pub fn new() -> Formula {
+        Default::default()
+    }
+
+    pub fn add(&mut self, atomic_number: u8, isotope: u16, count: u32) {
+        if count > 0 {
+            *self.counts.entry((atomic_number, isotope)).or_insert(0) += count;
+        }
+    }
+
+    /// Number of atoms of the element, all isotopes included.
+    pub fn count(&self, atomic_number: u8) -> u32 {
+        self.counts
+            .iter()
+            .filter(|((z, _), _)| *z == atomic_number)
+            .map(|(_, c)| c)
+            .sum()
+    }
+
+    pub fn atom_count(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    /// Counts by atomic number with isotopes merged into their elements.
+    pub fn element_counts(&self) -> BTreeMap<u8, u32> {
+        let mut counts: BTreeMap<u8, u32> = BTreeMap::new();
+        for ((z, _), c) in &self.counts {
+            *counts.entry(*z).or_insert(0) += c;
+        }
+        counts
+    }
+
+    /// True if every atom of `other` (by element and isotope) is present in this formula.
+    pub fn contains(&self, other: &Formula) -> bool {
+        other
+            .counts
+            .iter()
+            .all(|(key, c)| self.counts.get(key).is_some_and(|own| own >= c))
+    }
+
+    /// Keys in Hill order: C, H, then other elements alphabetically; without carbon all
+    /// elements alphabetically. Isotopes follow their unlabelled element by mass number.
+    fn hill_order(&self) -> Vec<(u8, u16)> {
+        let has_carbon = self.count(6) > 0;
+        let rank = |z: u8| -> (u8, &'static str) {
+            let symbol = Element { atomic_number: z }.symbol().unwrap_or("*");
+            match z {
+                6 if has_carbon => (0, symbol),
+                1 if has_carbon => (1, symbol),
+                _ => (2, symbol),
+            }
+        };
+        let mut keys: Vec<(u8, u16)> = self.counts.keys().copied().collect();
+        keys.sort_by(|a, b| match rank(a.0).cmp(&rank(b.0)) {
+            Ordering::Equal => a.1.cmp(&b.1),
+            ordering => ordering,
+        });
+        keys
+    }
+}
+
+impl fmt::Display for Formula {
+    /// Hill notation, e.g. `C2H6O`, `C[13C]H6O`, `H4N+`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (z, isotope) in self.hill_order() {
+            let symbol = Element { atomic_number: z }.symbol().unwrap_or("*");
+            if isotope == 0 {
+                write!(f, "{}", symbol)?;
+            } else {
+                write!(f, "[{}{}]", isotope, symbol)?;
+            }
+            let count = self.counts[&(z, isotope)];
+            if count > 1 {
+                write!(f, "{}", count)?;
+            }
+        }
+        match self.charge {
+            0 => Ok(()),
+            1 => write!(f, "+"),
+            -1 => write!(f, "-"),
+            c if c > 0 => write!(f, "+{}", c),
+            c => write!(f, "-{}", -c),
+        }
+    }
+}
+
+/// Formula based filter for search results.
+#[derive(Debug, Clone)]
+pub enum FormulaFilter {
+    /// Same atoms and charge.
+    Exact(Formula),
+    /// At least the atoms of the given formula.
+    Contains(Formula),
+    /// No atoms beyond the given formula.
+    ContainedIn(Formula),
+}
+
+impl FormulaFilter {
+    pub fn matches(&self, formula: &Formula) -> bool {
+        match self {
+            FormulaFilter::Exact(f) => formula == f,
+            FormulaFilter::Contains(f) => formula.contains(f),
+            FormulaFilter::ContainedIn(f) => f.contains(formula),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::formula::{Formula, FormulaFilter};
+
+    fn formula(counts: &[(u8, u16, u32)], charge: i32) -> Formula {
+        let mut f = Formula::new();
+        for (z, isotope, count) in counts {
+            f.add(*z, *isotope, *count);
+        }
+        f.charge = charge;
+        f
+    }
+
+    #[test]
+    fn formula_display_hill_order() {
+        assert_eq!(
+            formula(&[(8, 0, 1), (1, 0, 6), (6, 0, 2)], 0).to_string(),
+            "C2H6O"
+        );
+        assert_eq!(
+            formula(&[(17, 0, 1), (1, 0, 1), (6, 0, 1), (7, 0, 1)], 0).to_string(),
+            "CHClN"
+        );
+        assert_eq!(
+            formula(&[(17, 0, 1), (1, 0, 1), (11, 0, 1)], 0).to_string(),
+            "ClHNa"
+        );
+    }
+
+    #[test]
+    fn formula_display_isotopes_and_charge() {
+        assert_eq!(
+            formula(&[(6, 13, 1), (6, 0, 1), (1, 0, 5), (1, 2, 1)], 0).to_string(),
+            "C[13C]H5[2H]"
+        );
+        assert_eq!(formula(&[(7, 0, 1), (1, 0, 4)], 1).to_string(), "H4N+");
+        assert_eq!(formula(&[(8, 0, 4), (16, 0, 1)], -2).to_string(), "O4S-2");
+    }
+
+    #[test]
+    fn formula_counts() {
+        let f = formula(&[(6, 13, 1), (6, 0, 2), (1, 0, 5)], 0);
+        assert_eq!(f.count(6), 3);
+        assert_eq!(f.atom_count(), 8);
+        assert_eq!(f.element_counts().get(&6), Some(&3));
+    }
+
+    #[test]
+    fn formula_filter() {
+        let ethanol = formula(&[(6, 0, 2), (1, 0, 6), (8, 0, 1)], 0);
+        let co = formula(&[(6, 0, 1), (8, 0, 1)], 0);
+        assert!(FormulaFilter::Exact(ethanol.clone()).matches(&ethanol));
+        assert!(!FormulaFilter::Exact(co.clone()).matches(&ethanol));
+        assert!(FormulaFilter::Contains(co.clone()).matches(&ethanol));
+        assert!(!FormulaFilter::ContainedIn(co).matches(&ethanol));
+        assert!(FormulaFilter::ContainedIn(ethanol.clone()).matches(&ethanol));
+    }
+}
@@ -0,0 +1,23 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Peak {
+    pub mz: f64,
+    /// Intensity relative to the most intense peak of the pattern (1.0).
+    pub intensity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsotopePatternOptions {
+    /// Peaks with intensity below this fraction of the most intense peak are dropped.
+    pub min_intensity: f64,
+    /// Peaks closer than this (in Da) are merged into one peak with averaged mass.
+    pub resolution: f64,
+}
+
+impl Default for IsotopePatternOptions {
+    fn default() -> Self {
+        IsotopePatternOptions {
+            min_intensity: 1e-4,
+            resolution: 0.01,
+        }
+    }
+}
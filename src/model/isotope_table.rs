@@ -0,0 +1,630 @@
+use crate::model::element::Isotope;
+
+const fn isotope(mass_number: u16, exact_mass: f64, abundance: f64) -> Isotope {
+    Isotope {
+        mass_number,
+        exact_mass,
+        abundance,
+    }
+}
+
+/// Isotopes of elements 1-118 indexed by atomic number - 1, sorted by mass number.
+/// Contains naturally occurring isotopes with their natural abundances (fraction of 1) and
+/// isotopes commonly used for labelling with zero abundance. Elements without stable
+/// isotopes list at least their longest-lived isotope.
+pub(crate) static ISOTOPES: [&[Isotope]; 118] = [
+    // H
+    &[
+        isotope(1, 1.00782503223, 0.999885),
+        isotope(2, 2.01410177812, 0.000115),
+        isotope(3, 3.0160492779, 0.0),
+    ],
+    // He
+    &[
+        isotope(3, 3.0160293201, 0.00000134),
+        isotope(4, 4.00260325413, 0.99999866),
+    ],
+    // Li
+    &[
+        isotope(6, 6.0151228874, 0.0759),
+        isotope(7, 7.0160034366, 0.9241),
+    ],
+    // Be
+    &[isotope(9, 9.012183065, 1.0)],
+    // B
+    &[
+        isotope(10, 10.01293695, 0.199),
+        isotope(11, 11.00930536, 0.801),
+    ],
+    // C
+    &[
+        isotope(11, 11.0114336, 0.0),
+        isotope(12, 12.0, 0.9893),
+        isotope(13, 13.00335483507, 0.0107),
+        isotope(14, 14.0032419884, 0.0),
+    ],
+    // N
+    &[
+        isotope(13, 13.00573861, 0.0),
+        isotope(14, 14.00307400443, 0.99636),
+        isotope(15, 15.00010889888, 0.00364),
+    ],
+    // O
+    &[
+        isotope(15, 15.0030656, 0.0),
+        isotope(16, 15.99491461957, 0.99757),
+        isotope(17, 16.9991317565, 0.00038),
+        isotope(18, 17.99915961286, 0.00205),
+    ],
+    // F
+    &[
+        isotope(18, 18.000938, 0.0),
+        isotope(19, 18.99840316273, 1.0),
+    ],
+    // Ne
+    &[
+        isotope(20, 19.9924401762, 0.9048),
+        isotope(21, 20.993846685, 0.0027),
+        isotope(22, 21.991385114, 0.0925),
+    ],
+    // Na
+    &[isotope(23, 22.989769282, 1.0)],
+    // Mg
+    &[
+        isotope(24, 23.985041697, 0.7899),
+        isotope(25, 24.985836976, 0.1),
+        isotope(26, 25.982592968, 0.1101),
+    ],
+    // Al
+    &[isotope(27, 26.98153853, 1.0)],
+    // Si
+    &[
+        isotope(28, 27.97692653465, 0.92223),
+        isotope(29, 28.9764946649, 0.04685),
+        isotope(30, 29.973770136, 0.03092),
+    ],
+    // P
+    &[
+        isotope(31, 30.97376199842, 1.0),
+        isotope(32, 31.97390764, 0.0),
+        isotope(33, 32.9717257, 0.0),
+    ],
+    // S
+    &[
+        isotope(32, 31.9720711744, 0.9499),
+        isotope(33, 32.9714589098, 0.0075),
+        isotope(34, 33.967867004, 0.0425),
+        isotope(35, 34.96903231, 0.0),
+        isotope(36, 35.96708071, 0.0001),
+    ],
+    // Cl
+    &[
+        isotope(35, 34.968852682, 0.7576),
+        isotope(36, 35.96830698, 0.0),
+        isotope(37, 36.965902602, 0.2424),
+    ],
+    // Ar
+    &[
+        isotope(36, 35.967545105, 0.003336),
+        isotope(38, 37.96273211, 0.000629),
+        isotope(40, 39.9623831237, 0.996035),
+    ],
+    // K
+    &[
+        isotope(39, 38.9637064864, 0.932581),
+        isotope(40, 39.963998166, 0.000117),
+        isotope(41, 40.9618252579, 0.067302),
+    ],
+    // Ca
+    &[
+        isotope(40, 39.962590863, 0.96941),
+        isotope(42, 41.95861783, 0.00647),
+        isotope(43, 42.95876644, 0.00135),
+        isotope(44, 43.9554816, 0.02086),
+        isotope(46, 45.953689, 0.00004),
+        isotope(48, 47.95252276, 0.00187),
+    ],
+    // Sc
+    &[isotope(45, 44.95590828, 1.0)],
+    // Ti
+    &[
+        isotope(46, 45.95262772, 0.0825),
+        isotope(47, 46.95175879, 0.0744),
+        isotope(48, 47.94794198, 0.7372),
+        isotope(49, 48.94786568, 0.0541),
+        isotope(50, 49.94478689, 0.0518),
+    ],
+    // V
+    &[
+        isotope(50, 49.94715601, 0.0025),
+        isotope(51, 50.94395704, 0.9975),
+    ],
+    // Cr
+    &[
+        isotope(50, 49.94604183, 0.04345),
+        isotope(52, 51.94050623, 0.83789),
+        isotope(53, 52.94064815, 0.09501),
+        isotope(54, 53.93887916, 0.02365),
+    ],
+    // Mn
+    &[isotope(55, 54.93804391, 1.0)],
+    // Fe
+    &[
+        isotope(54, 53.93960899, 0.05845),
+        isotope(56, 55.93493633, 0.91754),
+        isotope(57, 56.93539284, 0.02119),
+        isotope(58, 57.93327443, 0.00282),
+    ],
+    // Co
+    &[isotope(59, 58.93319429, 1.0)],
+    // Ni
+    &[
+        isotope(58, 57.93534241, 0.68077),
+        isotope(60, 59.93078588, 0.26223),
+        isotope(61, 60.93105557, 0.011399),
+        isotope(62, 61.92834537, 0.036346),
+        isotope(64, 63.92796682, 0.009255),
+    ],
+    // Cu
+    &[
+        isotope(63, 62.92959772, 0.6915),
+        isotope(64, 63.9297642, 0.0),
+        isotope(65, 64.9277897, 0.3085),
+    ],
+    // Zn
+    &[
+        isotope(64, 63.92914201, 0.4917),
+        isotope(66, 65.92603381, 0.2773),
+        isotope(67, 66.92712775, 0.0404),
+        isotope(68, 67.92484455, 0.1845),
+        isotope(70, 69.9253192, 0.0061),
+    ],
+    // Ga
+    &[
+        isotope(67, 66.9282025, 0.0),
+        isotope(68, 67.9279805, 0.0),
+        isotope(69, 68.9255735, 0.60108),
+        isotope(71, 70.92470258, 0.39892),
+    ],
+    // Ge
+    &[
+        isotope(70, 69.92424875, 0.2057),
+        isotope(72, 71.922075826, 0.2745),
+        isotope(73, 72.923458956, 0.0775),
+        isotope(74, 73.921177761, 0.365),
+        isotope(76, 75.921402726, 0.0773),
+    ],
+    // As
+    &[isotope(75, 74.92159457, 1.0)],
+    // Se
+    &[
+        isotope(74, 73.922475934, 0.0089),
+        isotope(76, 75.919213704, 0.0937),
+        isotope(77, 76.919914154, 0.0763),
+        isotope(78, 77.91730928, 0.2377),
+        isotope(80, 79.9165218, 0.4961),
+        isotope(82, 81.9166995, 0.0873),
+    ],
+    // Br
+    &[
+        isotope(76, 75.924542, 0.0),
+        isotope(77, 76.921379, 0.0),
+        isotope(79, 78.9183376, 0.5069),
+        isotope(81, 80.9162897, 0.4931),
+        isotope(82, 81.9168041, 0.0),
+    ],
+    // Kr
+    &[
+        isotope(78, 77.92036494, 0.00355),
+        isotope(80, 79.91637808, 0.02286),
+        isotope(82, 81.91348273, 0.11593),
+        isotope(83, 82.91412716, 0.115),
+        isotope(84, 83.9114977282, 0.56987),
+        isotope(86, 85.9106106269, 0.17279),
+    ],
+    // Rb
+    &[
+        isotope(85, 84.9117897379, 0.7217),
+        isotope(87, 86.909180531, 0.2783),
+    ],
+    // Sr
+    &[
+        isotope(84, 83.9134191, 0.0056),
+        isotope(86, 85.9092606, 0.0986),
+        isotope(87, 86.9088775, 0.07),
+        isotope(88, 87.9056125, 0.8258),
+    ],
+    // Y
+    &[isotope(89, 88.9058403, 1.0), isotope(90, 89.9071439, 0.0)],
+    // Zr
+    &[
+        isotope(89, 88.9088814, 0.0),
+        isotope(90, 89.9046977, 0.5145),
+        isotope(91, 90.9056396, 0.1122),
+        isotope(92, 91.9050347, 0.1715),
+        isotope(94, 93.9063108, 0.1738),
+        isotope(96, 95.9082714, 0.028),
+    ],
+    // Nb
+    &[isotope(93, 92.906373, 1.0)],
+    // Mo
+    &[
+        isotope(92, 91.90680796, 0.1453),
+        isotope(94, 93.9050849, 0.0915),
+        isotope(95, 94.90583877, 0.1584),
+        isotope(96, 95.90467612, 0.1667),
+        isotope(97, 96.90601812, 0.096),
+        isotope(98, 97.90540482, 0.2439),
+        isotope(100, 99.9074718, 0.0982),
+    ],
+    // Tc
+    &[
+        isotope(97, 96.9063667, 0.0),
+        isotope(98, 97.9072124, 0.0),
+        isotope(99, 98.9062508, 0.0),
+    ],
+    // Ru
+    &[
+        isotope(96, 95.90759025, 0.0554),
+        isotope(98, 97.9052868, 0.0187),
+        isotope(99, 98.9059341, 0.1276),
+        isotope(100, 99.9042143, 0.126),
+        isotope(101, 100.9055769, 0.1706),
+        isotope(102, 101.9043441, 0.3155),
+        isotope(104, 103.9054275, 0.1862),
+    ],
+    // Rh
+    &[isotope(103, 102.905498, 1.0)],
+    // Pd
+    &[
+        isotope(102, 101.9056022, 0.0102),
+        isotope(104, 103.9040305, 0.1114),
+        isotope(105, 104.9050796, 0.2233),
+        isotope(106, 105.9034804, 0.2733),
+        isotope(108, 107.9038916, 0.2646),
+        isotope(110, 109.9051722, 0.1172),
+    ],
+    // Ag
+    &[
+        isotope(107, 106.9050916, 0.51839),
+        isotope(109, 108.9047553, 0.48161),
+    ],
+    // Cd
+    &[
+        isotope(106, 105.9064599, 0.0125),
+        isotope(108, 107.9041834, 0.0089),
+        isotope(110, 109.90300661, 0.1249),
+        isotope(111, 110.90418287, 0.128),
+        isotope(112, 111.90276287, 0.2413),
+        isotope(113, 112.90440813, 0.1222),
+        isotope(114, 113.90336509, 0.2873),
+        isotope(116, 115.90476315, 0.0749),
+    ],
+    // In
+    &[
+        isotope(111, 110.9051085, 0.0),
+        isotope(113, 112.90406184, 0.0429),
+        isotope(115, 114.903878776, 0.9571),
+    ],
+    // Sn
+    &[
+        isotope(112, 111.90482387, 0.0097),
+        isotope(114, 113.9027827, 0.0066),
+        isotope(115, 114.903344699, 0.0034),
+        isotope(116, 115.9017428, 0.1454),
+        isotope(117, 116.90295398, 0.0768),
+        isotope(118, 117.90160657, 0.2422),
+        isotope(119, 118.90331117, 0.0859),
+        isotope(120, 119.90220163, 0.3258),
+        isotope(122, 121.9034438, 0.0463),
+        isotope(124, 123.9052766, 0.0579),
+    ],
+    // Sb
+    &[
+        isotope(121, 120.903812, 0.5721),
+        isotope(123, 122.9042132, 0.4279),
+    ],
+    // Te
+    &[
+        isotope(120, 119.9040593, 0.0009),
+        isotope(122, 121.9030435, 0.0255),
+        isotope(123, 122.9042698, 0.0089),
+        isotope(124, 123.9028171, 0.0474),
+        isotope(125, 124.9044299, 0.0707),
+        isotope(126, 125.9033109, 0.1884),
+        isotope(128, 127.90446128, 0.3174),
+        isotope(130, 129.906222748, 0.3408),
+    ],
+    // I
+    &[
+        isotope(123, 122.905589, 0.0),
+        isotope(124, 123.9062099, 0.0),
+        isotope(125, 124.9046294, 0.0),
+        isotope(127, 126.9044719, 1.0),
+        isotope(131, 130.9061263, 0.0),
+    ],
+    // Xe
+    &[
+        isotope(124, 123.905892, 0.000952),
+        isotope(126, 125.9042983, 0.00089),
+        isotope(128, 127.903531, 0.019102),
+        isotope(129, 128.9047808611, 0.264006),
+        isotope(130, 129.903509349, 0.04071),
+        isotope(131, 130.90508406, 0.212324),
+        isotope(132, 131.9041550856, 0.269086),
+        isotope(134, 133.90539466, 0.104357),
+        isotope(136, 135.907214484, 0.088573),
+    ],
+    // Cs
+    &[isotope(133, 132.905451961, 1.0)],
+    // Ba
+    &[
+        isotope(130, 129.9063207, 0.00106),
+        isotope(132, 131.9050611, 0.00101),
+        isotope(134, 133.90450818, 0.02417),
+        isotope(135, 134.90568838, 0.06592),
+        isotope(136, 135.90457573, 0.07854),
+        isotope(137, 136.90582714, 0.11232),
+        isotope(138, 137.905247, 0.71698),
+    ],
+    // La
+    &[
+        isotope(138, 137.9071149, 0.0008881),
+        isotope(139, 138.9063563, 0.9991119),
+    ],
+    // Ce
+    &[
+        isotope(136, 135.90712921, 0.00185),
+        isotope(138, 137.905991, 0.00251),
+        isotope(140, 139.9054431, 0.8845),
+        isotope(142, 141.9092504, 0.11114),
+    ],
+    // Pr
+    &[isotope(141, 140.9076576, 1.0)],
+    // Nd
+    &[
+        isotope(142, 141.907729, 0.27152),
+        isotope(143, 142.90982, 0.12174),
+        isotope(144, 143.910093, 0.23798),
+        isotope(145, 144.9125793, 0.08293),
+        isotope(146, 145.9131226, 0.17189),
+        isotope(148, 147.9168993, 0.05756),
+        isotope(150, 149.9209022, 0.05638),
+    ],
+    // Pm
+    &[
+        isotope(145, 144.9127559, 0.0),
+        isotope(147, 146.915145, 0.0),
+    ],
+    // Sm
+    &[
+        isotope(144, 143.9120065, 0.0307),
+        isotope(147, 146.9149044, 0.1499),
+        isotope(148, 147.9148292, 0.1124),
+        isotope(149, 148.9171921, 0.1382),
+        isotope(150, 149.9172829, 0.0738),
+        isotope(152, 151.9197397, 0.2675),
+        isotope(154, 153.9222169, 0.2275),
+    ],
+    // Eu
+    &[
+        isotope(151, 150.9198578, 0.4781),
+        isotope(153, 152.921238, 0.5219),
+    ],
+    // Gd
+    &[
+        isotope(152, 151.9197995, 0.002),
+        isotope(154, 153.9208741, 0.0218),
+        isotope(155, 154.9226305, 0.148),
+        isotope(156, 155.9221312, 0.2047),
+        isotope(157, 156.9239686, 0.1565),
+        isotope(158, 157.9241123, 0.2484),
+        isotope(160, 159.9270624, 0.2186),
+    ],
+    // Tb
+    &[isotope(159, 158.9253547, 1.0)],
+    // Dy
+    &[
+        isotope(156, 155.9242847, 0.00056),
+        isotope(158, 157.9244159, 0.00095),
+        isotope(160, 159.9252046, 0.02329),
+        isotope(161, 160.9269405, 0.18889),
+        isotope(162, 161.9268056, 0.25475),
+        isotope(163, 162.9287383, 0.24896),
+        isotope(164, 163.9291819, 0.2826),
+    ],
+    // Ho
+    &[isotope(165, 164.9303288, 1.0)],
+    // Er
+    &[
+        isotope(162, 161.9287884, 0.00139),
+        isotope(164, 163.9292088, 0.01601),
+        isotope(166, 165.9302995, 0.33503),
+        isotope(167, 166.9320546, 0.22869),
+        isotope(168, 167.9323767, 0.26978),
+        isotope(170, 169.9354702, 0.1491),
+    ],
+    // Tm
+    &[isotope(169, 168.9342179, 1.0)],
+    // Yb
+    &[
+        isotope(168, 167.9338896, 0.00123),
+        isotope(170, 169.9347664, 0.02982),
+        isotope(171, 170.9363302, 0.1409),
+        isotope(172, 171.9363859, 0.2168),
+        isotope(173, 172.9382151, 0.16103),
+        isotope(174, 173.9388664, 0.32026),
+        isotope(176, 175.9425764, 0.12996),
+    ],
+    // Lu
+    &[
+        isotope(175, 174.9407752, 0.97401),
+        isotope(176, 175.9426897, 0.02599),
+        isotope(177, 176.9437615, 0.0),
+    ],
+    // Hf
+    &[
+        isotope(174, 173.9400461, 0.0016),
+        isotope(176, 175.9414076, 0.0526),
+        isotope(177, 176.9432277, 0.186),
+        isotope(178, 177.9437058, 0.2728),
+        isotope(179, 178.9458232, 0.1362),
+        isotope(180, 179.946557, 0.3508),
+    ],
+    // Ta
+    &[
+        isotope(180, 179.9474648, 0.0001201),
+        isotope(181, 180.9479958, 0.9998799),
+    ],
+    // W
+    &[
+        isotope(180, 179.9467108, 0.0012),
+        isotope(182, 181.94820394, 0.265),
+        isotope(183, 182.95022275, 0.1431),
+        isotope(184, 183.95093092, 0.3064),
+        isotope(186, 185.9543628, 0.2843),
+    ],
+    // Re
+    &[
+        isotope(185, 184.9529545, 0.374),
+        isotope(187, 186.9557501, 0.626),
+    ],
+    // Os
+    &[
+        isotope(184, 183.9524885, 0.0002),
+        isotope(186, 185.953835, 0.0159),
+        isotope(187, 186.9557474, 0.0196),
+        isotope(188, 187.9558352, 0.1324),
+        isotope(189, 188.9581442, 0.1615),
+        isotope(190, 189.9584437, 0.2626),
+        isotope(192, 191.961477, 0.4078),
+    ],
+    // Ir
+    &[
+        isotope(191, 190.9605893, 0.373),
+        isotope(193, 192.9629216, 0.627),
+    ],
+    // Pt
+    &[
+        isotope(190, 189.9599297, 0.00012),
+        isotope(192, 191.9610387, 0.00782),
+        isotope(194, 193.9626809, 0.3286),
+        isotope(195, 194.9647917, 0.3378),
+        isotope(196, 195.96495209, 0.2521),
+        isotope(198, 197.9678949, 0.07356),
+    ],
+    // Au
+    &[isotope(197, 196.96656879, 1.0)],
+    // Hg
+    &[
+        isotope(196, 195.9658326, 0.0015),
+        isotope(198, 197.9667686, 0.0997),
+        isotope(199, 198.96828064, 0.1687),
+        isotope(200, 199.96832659, 0.231),
+        isotope(201, 200.97030284, 0.1318),
+        isotope(202, 201.9706434, 0.2986),
+        isotope(204, 203.97349398, 0.0687),
+    ],
+    // Tl
+    &[
+        isotope(201, 200.970819, 0.0),
+        isotope(203, 202.9723446, 0.2952),
+        isotope(205, 204.9744278, 0.7048),
+    ],
+    // Pb
+    &[
+        isotope(204, 203.973044, 0.014),
+        isotope(206, 205.9744657, 0.241),
+        isotope(207, 206.9758973, 0.221),
+        isotope(208, 207.9766525, 0.524),
+        isotope(212, 211.9918959, 0.0),
+    ],
+    // Bi
+    &[isotope(209, 208.9803991, 1.0)],
+    // Po
+    &[
+        isotope(209, 208.9824308, 0.0),
+        isotope(210, 209.9828741, 0.0),
+    ],
+    // At
+    &[
+        isotope(210, 209.9871479, 0.0),
+        isotope(211, 210.9874963, 0.0),
+    ],
+    // Rn
+    &[isotope(222, 222.0175782, 0.0)],
+    // Fr
+    &[isotope(223, 223.019736, 0.0)],
+    // Ra
+    &[
+        isotope(223, 223.0185023, 0.0),
+        isotope(226, 226.0254103, 0.0),
+    ],
+    // Ac
+    &[isotope(225, 225.02323, 0.0), isotope(227, 227.0277523, 0.0)],
+    // Th
+    &[
+        isotope(230, 230.0331341, 0.0002),
+        isotope(232, 232.0380558, 0.9998),
+    ],
+    // Pa
+    &[isotope(231, 231.0358842, 1.0)],
+    // U
+    &[
+        isotope(234, 234.0409523, 0.000054),
+        isotope(235, 235.0439301, 0.007204),
+        isotope(238, 238.0507884, 0.992742),
+    ],
+    // Np
+    &[isotope(237, 237.0481736, 0.0)],
+    // Pu
+    &[isotope(244, 244.0642053, 0.0)],
+    // Am
+    &[isotope(243, 243.0613813, 0.0)],
+    // Cm
+    &[isotope(247, 247.0703541, 0.0)],
+    // Bk
+    &[isotope(247, 247.0703073, 0.0)],
+    // Cf
+    &[isotope(251, 251.0795886, 0.0)],
+    // Es
+    &[isotope(252, 252.08298, 0.0)],
+    // Fm
+    &[isotope(257, 257.0951061, 0.0)],
+    // Md
+    &[isotope(258, 258.0984315, 0.0)],
+    // No
+    &[isotope(259, 259.10103, 0.0)],
+    // Lr
+    &[isotope(266, 266.11983, 0.0)],
+    // Rf
+    &[isotope(267, 267.12179, 0.0)],
+    // Db
+    &[isotope(268, 268.12567, 0.0)],
+    // Sg
+    &[isotope(269, 269.12863, 0.0)],
+    // Bh
+    &[isotope(270, 270.13336, 0.0)],
+    // Hs
+    &[isotope(269, 269.13375, 0.0)],
+    // Mt
+    &[isotope(278, 278.15631, 0.0)],
+    // Ds
+    &[isotope(281, 281.16451, 0.0)],
+    // Rg
+    &[isotope(282, 282.16912, 0.0)],
+    // Cn
+    &[isotope(285, 285.17712, 0.0)],
+    // Nh
+    &[isotope(286, 286.18221, 0.0)],
+    // Fl
+    &[isotope(289, 289.19042, 0.0)],
+    // Mc
+    &[isotope(290, 290.19598, 0.0)],
+    // Lv
+    &[isotope(293, 293.20449, 0.0)],
+    // Ts
+    &[isotope(294, 294.21046, 0.0)],
+    // Og
+    &[isotope(294, 294.21392, 0.0)],
+];
@@ -1,27 +1,49 @@
-use crate::model::atom::Atom;
-use crate::model::bond::Bond;
+use crate::chem::rings::RingInfo;
+use crate::model::atom::{Atom, Chirality};
+use crate::model::bond::{Bond, BondOrder, DoubleBondStereo};
+use crate::model::element::Element;
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{Decode, Encode};
 use petgraph::stable_graph::{EdgeIndex, NodeIndex, StableGraph};
-use petgraph::visit::EdgeRef;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 use petgraph::Undirected;
 use std::borrow::Borrow;
-use std::collections::BTreeSet;
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+#[derive(Clone, Default)]
 pub struct Molecule {
+    /// Atoms and bonds. Call `invalidate_caches` after adding or removing atoms or bonds
+    /// directly through the graph.
     pub graph: StableGraph<Atom, Bond, Undirected>,
+    /// Arbitrary named values attached to the molecule, e.g. name or identifier.
+    pub properties: BTreeMap<String, String>,
+    pub(crate) ring_info: OnceCell<RingInfo>,
 }
 
 impl Molecule {
     pub fn new() -> Molecule {
         Molecule {
             graph: Default::default(),
+            properties: BTreeMap::new(),
+            ring_info: OnceCell::new(),
         }
     }
 
+    /// Drops derived data that depends on the molecule topology (e.g. ring information).
+    pub fn invalidate_caches(&mut self) {
+        self.ring_info.take();
+    }
+
     pub fn add_atom(&mut self, atom: Atom) -> NodeIndex {
+        self.invalidate_caches();
         return self.graph.add_node(atom);
     }
 
     pub fn add_bond(&mut self, atom1: NodeIndex, atom2: NodeIndex, bond: Bond) -> EdgeIndex {
+        self.invalidate_caches();
         return self.graph.add_edge(atom1, atom2, bond);
     }
 
@@ -29,16 +51,21 @@ impl Molecule {
         return self.graph.node_weight(node);
     }
 
+    pub fn get_atom_mut(&mut self, node: NodeIndex) -> Option<&mut Atom> {
+        self.graph.node_weight_mut(node)
+    }
+
     pub fn get_bond(&self, edge: EdgeIndex) -> Option<&Bond> {
         return self.graph.edge_weight(edge);
     }
 
+    pub fn get_bond_mut(&mut self, edge: EdgeIndex) -> Option<&mut Bond> {
+        self.graph.edge_weight_mut(edge)
+    }
+
     pub fn get_bond_by_atoms(&self, ni1: NodeIndex, ni2: NodeIndex) -> Option<&Bond> {
-        let e = self.graph.find_edge_undirected(ni1, ni2);
-        if e.is_none() {
-            return None;
-        }
-        return self.graph.edge_weight(e.unwrap().0);
+        let (edge, _) = self.graph.find_edge_undirected(ni1, ni2)?;
+        return self.graph.edge_weight(edge);
     }
 
     pub fn has_bond(&self, ni1: NodeIndex, ni2: NodeIndex) -> bool {
@@ -70,7 +97,401 @@ impl Molecule {
         atoms.remove(atom.borrow());
         return atoms;
     }
+
+    /// Removes an atom together with all its bonds.
+    /// Indices of the remaining atoms and bonds are not changed.
+    pub fn remove_atom(&mut self, atom: NodeIndex) -> Option<Atom> {
+        self.invalidate_caches();
+        self.graph.remove_node(atom)
+    }
+
+    pub fn remove_bond(&mut self, edge: EdgeIndex) -> Option<Bond> {
+        self.invalidate_caches();
+        self.graph.remove_edge(edge)
+    }
+
+    pub fn remove_bond_by_atoms(&mut self, ni1: NodeIndex, ni2: NodeIndex) -> Option<Bond> {
+        let (edge, _) = self.graph.find_edge_undirected(ni1, ni2)?;
+        self.remove_bond(edge)
+    }
+
+    /// Returns false if there is no such bond.
+    pub fn set_bond_order(&mut self, edge: EdgeIndex, order: BondOrder) -> bool {
+        match self.graph.edge_weight_mut(edge) {
+            Some(bond) => {
+                bond.order = order;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns false if there is no such atom.
+    pub fn set_element(&mut self, atom: NodeIndex, element: Element) -> bool {
+        self.update_atom(atom, |a| a.element = element)
+    }
+
+    pub fn set_charge(&mut self, atom: NodeIndex, charge: i8) -> bool {
+        self.update_atom(atom, |a| a.charge = charge)
+    }
+
+    pub fn set_isotope(&mut self, atom: NodeIndex, isotope: u16) -> bool {
+        self.update_atom(atom, |a| a.isotope = isotope)
+    }
+
+    pub fn set_hs(&mut self, atom: NodeIndex, hs: u8) -> bool {
+        self.update_atom(atom, |a| a.hs = hs)
+    }
+
+    fn update_atom<F: FnOnce(&mut Atom)>(&mut self, atom: NodeIndex, f: F) -> bool {
+        match self.graph.node_weight_mut(atom) {
+            Some(a) => {
+                f(a);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Converts the hydrogens of all atoms, written and implicit, into explicit hydrogen
+    /// atoms. The atoms get no implicit hydrogens afterwards.
+    /// Returns the number of added hydrogens.
+    pub fn add_explicit_hydrogens(&mut self) -> usize {
+        let counts: Vec<(NodeIndex, u8)> = self
+            .graph
+            .node_indices()
+            .map(|node| (node, self.total_hydrogens(node)))
+            .collect();
+        let snapshot = self.stereo_snapshot();
+        let mut replaced = HashMap::new();
+        let mut added = 0;
+        for (node, hs) in counts {
+            for _ in 0..hs {
+                let h = self.add_atom(Atom {
+                    element: Element { atomic_number: 1 },
+                    isotope: 0,
+                    charge: 0,
+                    hs: 0,
+                    no_implicit_hs: true,
+                    aromatic: false,
+                    coords: None,
+                    chirality: Chirality::None,
+                    map: 0,
+                });
+                self.add_bond(node, h, Bond::new(BondOrder::Single));
+                replaced.insert((node, None), Some(h));
+            }
+            self.graph[node].hs = 0;
+            self.graph[node].no_implicit_hs = true;
+            added += hs as usize;
+        }
+        self.restore_stereo(snapshot, &replaced);
+        added
+    }
+
+    /// Removes plain hydrogen atoms (no isotope, no charge, single bond to a non-hydrogen atom)
+    /// and adds them to the hydrogen count of their neighbor.
+    /// Returns the number of removed hydrogens.
+    pub fn remove_explicit_hydrogens(&mut self) -> usize {
+        let mut removable: Vec<(NodeIndex, NodeIndex)> = Vec::new();
+        for node in self.graph.node_indices() {
+            let atom = &self.graph[node];
+            if atom.element.atomic_number != 1 || atom.isotope != 0 || atom.charge != 0 {
+                continue;
+            }
+            let mut edges = self.graph.edges(node);
+            if let (Some(edge), None) = (edges.next(), edges.next()) {
+                let neighbor = if edge.source() == node {
+                    edge.target()
+                } else {
+                    edge.source()
+                };
+                if edge.weight().order == BondOrder::Single
+                    && self.graph[neighbor].element.atomic_number != 1
+                {
+                    removable.push((node, neighbor));
+                }
+            }
+        }
+        let snapshot = self.stereo_snapshot();
+        let mut replaced = HashMap::new();
+        for (h, neighbor) in &removable {
+            self.remove_atom(*h);
+            self.graph[*neighbor].hs += 1;
+            replaced.insert((*neighbor, Some(*h)), None);
+        }
+        self.restore_stereo(snapshot, &replaced);
+        removable.len()
+    }
+
+    /// Copies all atoms and bonds of `other` into this molecule as a disconnected component.
+    /// Returns the mapping from atoms of `other` to the newly added atoms.
+    pub fn merge(&mut self, other: &Molecule) -> HashMap<NodeIndex, NodeIndex> {
+        let mut mapping: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        for node in other.graph.node_indices() {
+            mapping.insert(node, self.add_atom(other.graph[node].clone()));
+        }
+        for edge in (&other.graph).edge_references() {
+            self.add_bond(
+                mapping[&edge.source()],
+                mapping[&edge.target()],
+                edge.weight().clone(),
+            );
+        }
+        mapping
+    }
+
+    /// Copies the given atoms and the bonds between them into a new molecule, keeping their
+    /// order. Returns the new molecule and the mapping from original to new atoms.
+    /// Stereo is dropped at atoms which lose a neighbor.
+    pub fn submolecule(
+        &self,
+        atoms: &BTreeSet<NodeIndex>,
+    ) -> (Molecule, HashMap<NodeIndex, NodeIndex>) {
+        let mut molecule = Molecule::new();
+        let mut mapping: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        for node in self.graph.node_indices().filter(|n| atoms.contains(n)) {
+            mapping.insert(node, molecule.add_atom(self.graph[node].clone()));
+        }
+        for edge in (&self.graph).edge_references() {
+            if let (Some(a), Some(b)) = (mapping.get(&edge.source()), mapping.get(&edge.target())) {
+                molecule.add_bond(*a, *b, edge.weight().clone());
+            }
+        }
+        // stereo refers to the neighbors, so it is dropped where some are left out
+        let changed: HashSet<NodeIndex> = mapping
+            .iter()
+            .filter(|(old, new)| {
+                self.graph.edges(**old).count() != molecule.graph.edges(**new).count()
+            })
+            .map(|(_, new)| *new)
+            .collect();
+        for atom in &changed {
+            molecule.graph[*atom].chirality = Chirality::None;
+        }
+        for edge in molecule.graph.edge_indices().collect::<Vec<_>>() {
+            let (a, b) = molecule.graph.edge_endpoints(edge).unwrap();
+            if changed.contains(&a) || changed.contains(&b) {
+                molecule.graph[edge].double_bond_stereo = DoubleBondStereo::None;
+            }
+        }
+        (molecule, mapping)
+    }
 }
 
+/// Compact binary layout: atoms, bonds as pairs of atom positions with bond data, properties.
+/// Atoms are numbered in graph order, skipping removed atoms.
+impl Encode for Molecule {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        let mut positions: HashMap<NodeIndex, u32> = HashMap::new();
+        (self.count_atoms() as u32).encode(encoder)?;
+        for node in self.graph.node_indices() {
+            positions.insert(node, positions.len() as u32);
+            self.graph[node].encode(encoder)?;
+        }
+        (self.count_bonds() as u32).encode(encoder)?;
+        for edge in (&self.graph).edge_references() {
+            positions[&edge.source()].encode(encoder)?;
+            positions[&edge.target()].encode(encoder)?;
+            edge.weight().encode(encoder)?;
+        }
+        self.properties.encode(encoder)
+    }
+}
+
+impl Decode for Molecule {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let mut molecule = Molecule::new();
+        let atom_count = u32::decode(decoder)?;
+        let mut nodes: Vec<NodeIndex> = Vec::new();
+        for _ in 0..atom_count {
+            nodes.push(molecule.add_atom(Atom::decode(decoder)?));
+        }
+        let bond_count = u32::decode(decoder)?;
+        for _ in 0..bond_count {
+            let a = u32::decode(decoder)? as usize;
+            let b = u32::decode(decoder)? as usize;
+            let bond = Bond::decode(decoder)?;
+            if a >= nodes.len() || b >= nodes.len() {
+                return Err(DecodeError::OtherString(format!(
+                    "invalid bond between atoms {} and {}",
+                    a, b
+                )));
+            }
+            molecule.add_bond(nodes[a], nodes[b], bond);
+        }
+        molecule.properties = BTreeMap::decode(decoder)?;
+        Ok(molecule)
+    }
+}
+
+bincode::impl_borrow_decode!(Molecule);
+
 #[cfg(test)]
-mod test {}
+mod test {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::bond::BondOrder;
+    use crate::model::element::Element;
+    use crate::model::molecule::Molecule;
+    use bincode::config::standard;
+    use bincode::{decode_from_slice, encode_to_vec};
+    use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn encode_decode() {
+        let mut m = parse_molecule("CC(=O)[13CH2][O-]").unwrap().1;
+        m.remove_atom(NodeIndex::new(0));
+        m.properties.insert("name".to_owned(), "test".to_owned());
+
+        let encoded = encode_to_vec(&m, standard()).unwrap();
+        let decoded: Molecule = decode_from_slice(&encoded, standard()).unwrap().0;
+        assert_eq!(decoded.count_atoms(), 4);
+        assert_eq!(decoded.count_bonds(), 3);
+        assert_eq!(decoded.formula(), m.formula());
+        assert_eq!(decoded.properties["name"], "test");
+        assert_eq!(
+            decoded
+                .get_bond_by_atoms(NodeIndex::new(0), NodeIndex::new(1))
+                .unwrap()
+                .order,
+            BondOrder::Double
+        );
+        assert_eq!(decoded.get_atom(NodeIndex::new(2)).unwrap().isotope, 13);
+    }
+
+    #[test]
+    fn decode_invalid_bond() {
+        let mut encoded = encode_to_vec(&parse_molecule("CC").unwrap().1, standard()).unwrap();
+        // second atom position of the only bond
+        let position = encoded.len() - 5;
+        assert_eq!(encoded[position], 1);
+        encoded[position] = 5;
+        assert!(decode_from_slice::<Molecule, _>(&encoded, standard()).is_err());
+    }
+
+    #[test]
+    fn submolecule() {
+        let m = parse_molecule("CC(O)NC").unwrap().1;
+        let atoms = BTreeSet::from([NodeIndex::new(1), NodeIndex::new(2), NodeIndex::new(4)]);
+        let (sub, mapping) = m.submolecule(&atoms);
+        assert_eq!(sub.count_atoms(), 3);
+        assert_eq!(sub.count_bonds(), 1);
+        assert_eq!(mapping[&NodeIndex::new(4)], NodeIndex::new(2));
+        assert!(sub.has_bond(NodeIndex::new(0), NodeIndex::new(1)));
+        assert_eq!(sub.formula().to_string(), "C2H8O");
+    }
+
+    #[test]
+    fn submolecule_drops_stereo() {
+        let m = parse_molecule("C[C@H](O)C(Cl)/C=C/C").unwrap().1;
+        let (sub, _) = m.submolecule(&(0..5).map(NodeIndex::new).collect());
+        assert_eq!(sub.canonical_smiles(), "C[C@H](O)CCl");
+        // the first stereocenter loses its methyl group, the double bond keeps its neighbors
+        let (sub, _) = m.submolecule(&(1..8).map(NodeIndex::new).collect());
+        let expected = parse_molecule("O[CH]C(Cl)/C=C/C").unwrap().1;
+        assert_eq!(sub.canonical_smiles(), expected.canonical_smiles());
+    }
+
+    #[test]
+    fn remove_atom() {
+        let mut m = parse_molecule("CC(O)N").unwrap().1;
+        let removed = m.remove_atom(NodeIndex::new(1)).unwrap();
+        assert_eq!(removed.element.atomic_number, 6);
+        assert_eq!(m.count_atoms(), 3);
+        assert_eq!(m.count_bonds(), 0);
+        assert_eq!(
+            m.get_atom(NodeIndex::new(3)).unwrap().element.atomic_number,
+            7
+        );
+        assert!(m.remove_atom(NodeIndex::new(1)).is_none());
+    }
+
+    #[test]
+    fn remove_bond() {
+        let mut m = parse_molecule("C1CC1").unwrap().1;
+        assert!(m
+            .remove_bond_by_atoms(NodeIndex::new(0), NodeIndex::new(2))
+            .is_some());
+        assert_eq!(m.count_bonds(), 2);
+        assert!(!m.has_bond(NodeIndex::new(0), NodeIndex::new(2)));
+        assert!(m.remove_bond(EdgeIndex::new(0)).is_some());
+        assert_eq!(m.count_bonds(), 1);
+    }
+
+    #[test]
+    fn set_bond_order() {
+        let mut m = parse_molecule("CC").unwrap().1;
+        assert!(m.set_bond_order(EdgeIndex::new(0), BondOrder::Double));
+        assert_eq!(
+            m.get_bond(EdgeIndex::new(0)).unwrap().order,
+            BondOrder::Double
+        );
+        assert!(!m.set_bond_order(EdgeIndex::new(1), BondOrder::Double));
+    }
+
+    #[test]
+    fn set_atom_properties() {
+        let mut m = parse_molecule("CO").unwrap().1;
+        let o = NodeIndex::new(1);
+        assert!(m.set_element(o, Element { atomic_number: 7 }));
+        assert!(m.set_charge(o, 1));
+        assert!(m.set_isotope(o, 15));
+        assert!(m.set_hs(o, 3));
+        let atom = m.get_atom(o).unwrap();
+        assert_eq!(atom.element.atomic_number, 7);
+        assert_eq!(atom.charge, 1);
+        assert_eq!(atom.isotope, 15);
+        assert_eq!(atom.hs, 3);
+        assert!(!m.set_charge(NodeIndex::new(2), 1));
+    }
+
+    #[test]
+    fn explicit_hydrogens() {
+        let mut m = parse_molecule("[CH3][OH]").unwrap().1;
+        assert_eq!(m.add_explicit_hydrogens(), 4);
+        assert_eq!(m.count_atoms(), 6);
+        assert_eq!(m.count_bonds(), 5);
+        assert_eq!(m.get_atom(NodeIndex::new(0)).unwrap().hs, 0);
+
+        assert_eq!(m.remove_explicit_hydrogens(), 4);
+        assert_eq!(m.count_atoms(), 2);
+        assert_eq!(m.count_bonds(), 1);
+        assert_eq!(m.get_atom(NodeIndex::new(0)).unwrap().hs, 3);
+        assert_eq!(m.get_atom(NodeIndex::new(1)).unwrap().hs, 1);
+    }
+
+    #[test]
+    fn explicit_implicit_hydrogens() {
+        let mut m = parse_molecule("CCO").unwrap().1;
+        assert_eq!(m.add_explicit_hydrogens(), 6);
+        assert_eq!(m.count_atoms(), 9);
+        assert_eq!(m.total_hydrogens(NodeIndex::new(0)), 0);
+        assert_eq!(m.formula().to_string(), "C2H6O");
+
+        assert_eq!(m.remove_explicit_hydrogens(), 6);
+        assert_eq!(m.canonical_smiles(), "CCO");
+    }
+
+    #[test]
+    fn remove_explicit_hydrogens_keeps_labelled() {
+        let mut m = parse_molecule("C([2H])[H]").unwrap().1;
+        assert_eq!(m.remove_explicit_hydrogens(), 1);
+        assert_eq!(m.count_atoms(), 2);
+        assert_eq!(m.get_atom(NodeIndex::new(0)).unwrap().hs, 1);
+    }
+
+    #[test]
+    fn merge() {
+        let mut m = parse_molecule("CC").unwrap().1;
+        let other = parse_molecule("N=O").unwrap().1;
+        let mapping = m.merge(&other);
+        assert_eq!(m.count_atoms(), 4);
+        assert_eq!(m.count_bonds(), 2);
+        let n = mapping[&NodeIndex::new(0)];
+        let o = mapping[&NodeIndex::new(1)];
+        assert_eq!(m.get_atom(n).unwrap().element.atomic_number, 7);
+        assert_eq!(m.get_bond_by_atoms(n, o).unwrap().order, BondOrder::Double);
+        assert!(!m.has_bond(NodeIndex::new(1), n));
+    }
+}
@@ -0,0 +1,1306 @@
+use crate::model::element::ElementData;
+
+/// Elements 1-118 indexed by atomic number - 1.
+/// Atomic weights are IUPAC standard atomic weights; for elements without stable isotopes
+/// the mass number of the longest-lived isotope is used. Monoisotopic mass is the exact mass
+/// of `most_abundant_isotope`.
+pub(crate) static ELEMENTS: [ElementData; 118] = [
+    ElementData {
+        symbol: "H",
+        name: "Hydrogen",
+        atomic_weight: 1.008,
+        monoisotopic_mass: 1.00782503223,
+        most_abundant_isotope: 1,
+        valences: &[1],
+        electronegativity: Some(2.20),
+        period: 1,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "He",
+        name: "Helium",
+        atomic_weight: 4.002602,
+        monoisotopic_mass: 4.00260325413,
+        most_abundant_isotope: 4,
+        valences: &[0],
+        electronegativity: None,
+        period: 1,
+        group: Some(18),
+    },
+    ElementData {
+        symbol: "Li",
+        name: "Lithium",
+        atomic_weight: 6.94,
+        monoisotopic_mass: 7.0160034366,
+        most_abundant_isotope: 7,
+        valences: &[1],
+        electronegativity: Some(0.98),
+        period: 2,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "Be",
+        name: "Beryllium",
+        atomic_weight: 9.0121831,
+        monoisotopic_mass: 9.012183065,
+        most_abundant_isotope: 9,
+        valences: &[2],
+        electronegativity: Some(1.57),
+        period: 2,
+        group: Some(2),
+    },
+    ElementData {
+        symbol: "B",
+        name: "Boron",
+        atomic_weight: 10.81,
+        monoisotopic_mass: 11.00930536,
+        most_abundant_isotope: 11,
+        valences: &[3],
+        electronegativity: Some(2.04),
+        period: 2,
+        group: Some(13),
+    },
+    ElementData {
+        symbol: "C",
+        name: "Carbon",
+        atomic_weight: 12.011,
+        monoisotopic_mass: 12.0,
+        most_abundant_isotope: 12,
+        valences: &[4],
+        electronegativity: Some(2.55),
+        period: 2,
+        group: Some(14),
+    },
+    ElementData {
+        symbol: "N",
+        name: "Nitrogen",
+        atomic_weight: 14.007,
+        monoisotopic_mass: 14.00307400443,
+        most_abundant_isotope: 14,
+        valences: &[3, 5],
+        electronegativity: Some(3.04),
+        period: 2,
+        group: Some(15),
+    },
+    ElementData {
+        symbol: "O",
+        name: "Oxygen",
+        atomic_weight: 15.999,
+        monoisotopic_mass: 15.99491461957,
+        most_abundant_isotope: 16,
+        valences: &[2],
+        electronegativity: Some(3.44),
+        period: 2,
+        group: Some(16),
+    },
+    ElementData {
+        symbol: "F",
+        name: "Fluorine",
+        atomic_weight: 18.998403163,
+        monoisotopic_mass: 18.99840316273,
+        most_abundant_isotope: 19,
+        valences: &[1],
+        electronegativity: Some(3.98),
+        period: 2,
+        group: Some(17),
+    },
+    ElementData {
+        symbol: "Ne",
+        name: "Neon",
+        atomic_weight: 20.1797,
+        monoisotopic_mass: 19.9924401762,
+        most_abundant_isotope: 20,
+        valences: &[0],
+        electronegativity: None,
+        period: 2,
+        group: Some(18),
+    },
+    ElementData {
+        symbol: "Na",
+        name: "Sodium",
+        atomic_weight: 22.98976928,
+        monoisotopic_mass: 22.989769282,
+        most_abundant_isotope: 23,
+        valences: &[1],
+        electronegativity: Some(0.93),
+        period: 3,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "Mg",
+        name: "Magnesium",
+        atomic_weight: 24.305,
+        monoisotopic_mass: 23.985041697,
+        most_abundant_isotope: 24,
+        valences: &[2],
+        electronegativity: Some(1.31),
+        period: 3,
+        group: Some(2),
+    },
+    ElementData {
+        symbol: "Al",
+        name: "Aluminium",
+        atomic_weight: 26.9815385,
+        monoisotopic_mass: 26.98153853,
+        most_abundant_isotope: 27,
+        valences: &[3],
+        electronegativity: Some(1.61),
+        period: 3,
+        group: Some(13),
+    },
+    ElementData {
+        symbol: "Si",
+        name: "Silicon",
+        atomic_weight: 28.085,
+        monoisotopic_mass: 27.97692653465,
+        most_abundant_isotope: 28,
+        valences: &[4],
+        electronegativity: Some(1.90),
+        period: 3,
+        group: Some(14),
+    },
+    ElementData {
+        symbol: "P",
+        name: "Phosphorus",
+        atomic_weight: 30.973761998,
+        monoisotopic_mass: 30.97376199842,
+        most_abundant_isotope: 31,
+        valences: &[3, 5],
+        electronegativity: Some(2.19),
+        period: 3,
+        group: Some(15),
+    },
+    ElementData {
+        symbol: "S",
+        name: "Sulfur",
+        atomic_weight: 32.06,
+        monoisotopic_mass: 31.9720711744,
+        most_abundant_isotope: 32,
+        valences: &[2, 4, 6],
+        electronegativity: Some(2.58),
+        period: 3,
+        group: Some(16),
+    },
+    ElementData {
+        symbol: "Cl",
+        name: "Chlorine",
+        atomic_weight: 35.45,
+        monoisotopic_mass: 34.968852682,
+        most_abundant_isotope: 35,
+        valences: &[1, 3, 5, 7],
+        electronegativity: Some(3.16),
+        period: 3,
+        group: Some(17),
+    },
+    ElementData {
+        symbol: "Ar",
+        name: "Argon",
+        atomic_weight: 39.948,
+        monoisotopic_mass: 39.9623831237,
+        most_abundant_isotope: 40,
+        valences: &[0],
+        electronegativity: None,
+        period: 3,
+        group: Some(18),
+    },
+    ElementData {
+        symbol: "K",
+        name: "Potassium",
+        atomic_weight: 39.0983,
+        monoisotopic_mass: 38.9637064864,
+        most_abundant_isotope: 39,
+        valences: &[1],
+        electronegativity: Some(0.82),
+        period: 4,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "Ca",
+        name: "Calcium",
+        atomic_weight: 40.078,
+        monoisotopic_mass: 39.962590863,
+        most_abundant_isotope: 40,
+        valences: &[2],
+        electronegativity: Some(1.00),
+        period: 4,
+        group: Some(2),
+    },
+    ElementData {
+        symbol: "Sc",
+        name: "Scandium",
+        atomic_weight: 44.955908,
+        monoisotopic_mass: 44.95590828,
+        most_abundant_isotope: 45,
+        valences: &[],
+        electronegativity: Some(1.36),
+        period: 4,
+        group: Some(3),
+    },
+    ElementData {
+        symbol: "Ti",
+        name: "Titanium",
+        atomic_weight: 47.867,
+        monoisotopic_mass: 47.94794198,
+        most_abundant_isotope: 48,
+        valences: &[],
+        electronegativity: Some(1.54),
+        period: 4,
+        group: Some(4),
+    },
+    ElementData {
+        symbol: "V",
+        name: "Vanadium",
+        atomic_weight: 50.9415,
+        monoisotopic_mass: 50.94395704,
+        most_abundant_isotope: 51,
+        valences: &[],
+        electronegativity: Some(1.63),
+        period: 4,
+        group: Some(5),
+    },
+    ElementData {
+        symbol: "Cr",
+        name: "Chromium",
+        atomic_weight: 51.9961,
+        monoisotopic_mass: 51.94050623,
+        most_abundant_isotope: 52,
+        valences: &[],
+        electronegativity: Some(1.66),
+        period: 4,
+        group: Some(6),
+    },
+    ElementData {
+        symbol: "Mn",
+        name: "Manganese",
+        atomic_weight: 54.938044,
+        monoisotopic_mass: 54.93804391,
+        most_abundant_isotope: 55,
+        valences: &[],
+        electronegativity: Some(1.55),
+        period: 4,
+        group: Some(7),
+    },
+    ElementData {
+        symbol: "Fe",
+        name: "Iron",
+        atomic_weight: 55.845,
+        monoisotopic_mass: 55.93493633,
+        most_abundant_isotope: 56,
+        valences: &[],
+        electronegativity: Some(1.83),
+        period: 4,
+        group: Some(8),
+    },
+    ElementData {
+        symbol: "Co",
+        name: "Cobalt",
+        atomic_weight: 58.933194,
+        monoisotopic_mass: 58.93319429,
+        most_abundant_isotope: 59,
+        valences: &[],
+        electronegativity: Some(1.88),
+        period: 4,
+        group: Some(9),
+    },
+    ElementData {
+        symbol: "Ni",
+        name: "Nickel",
+        atomic_weight: 58.6934,
+        monoisotopic_mass: 57.93534241,
+        most_abundant_isotope: 58,
+        valences: &[],
+        electronegativity: Some(1.91),
+        period: 4,
+        group: Some(10),
+    },
+    ElementData {
+        symbol: "Cu",
+        name: "Copper",
+        atomic_weight: 63.546,
+        monoisotopic_mass: 62.92959772,
+        most_abundant_isotope: 63,
+        valences: &[],
+        electronegativity: Some(1.90),
+        period: 4,
+        group: Some(11),
+    },
+    ElementData {
+        symbol: "Zn",
+        name: "Zinc",
+        atomic_weight: 65.38,
+        monoisotopic_mass: 63.92914201,
+        most_abundant_isotope: 64,
+        valences: &[],
+        electronegativity: Some(1.65),
+        period: 4,
+        group: Some(12),
+    },
+    ElementData {
+        symbol: "Ga",
+        name: "Gallium",
+        atomic_weight: 69.723,
+        monoisotopic_mass: 68.9255735,
+        most_abundant_isotope: 69,
+        valences: &[3],
+        electronegativity: Some(1.81),
+        period: 4,
+        group: Some(13),
+    },
+    ElementData {
+        symbol: "Ge",
+        name: "Germanium",
+        atomic_weight: 72.630,
+        monoisotopic_mass: 73.921177761,
+        most_abundant_isotope: 74,
+        valences: &[4],
+        electronegativity: Some(2.01),
+        period: 4,
+        group: Some(14),
+    },
+    ElementData {
+        symbol: "As",
+        name: "Arsenic",
+        atomic_weight: 74.921595,
+        monoisotopic_mass: 74.92159457,
+        most_abundant_isotope: 75,
+        valences: &[3, 5],
+        electronegativity: Some(2.18),
+        period: 4,
+        group: Some(15),
+    },
+    ElementData {
+        symbol: "Se",
+        name: "Selenium",
+        atomic_weight: 78.971,
+        monoisotopic_mass: 79.9165218,
+        most_abundant_isotope: 80,
+        valences: &[2, 4, 6],
+        electronegativity: Some(2.55),
+        period: 4,
+        group: Some(16),
+    },
+    ElementData {
+        symbol: "Br",
+        name: "Bromine",
+        atomic_weight: 79.904,
+        monoisotopic_mass: 78.9183376,
+        most_abundant_isotope: 79,
+        valences: &[1, 3, 5, 7],
+        electronegativity: Some(2.96),
+        period: 4,
+        group: Some(17),
+    },
+    ElementData {
+        symbol: "Kr",
+        name: "Krypton",
+        atomic_weight: 83.798,
+        monoisotopic_mass: 83.9114977282,
+        most_abundant_isotope: 84,
+        valences: &[0, 2],
+        electronegativity: Some(3.00),
+        period: 4,
+        group: Some(18),
+    },
+    ElementData {
+        symbol: "Rb",
+        name: "Rubidium",
+        atomic_weight: 85.4678,
+        monoisotopic_mass: 84.9117897379,
+        most_abundant_isotope: 85,
+        valences: &[1],
+        electronegativity: Some(0.82),
+        period: 5,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "Sr",
+        name: "Strontium",
+        atomic_weight: 87.62,
+        monoisotopic_mass: 87.9056125,
+        most_abundant_isotope: 88,
+        valences: &[2],
+        electronegativity: Some(0.95),
+        period: 5,
+        group: Some(2),
+    },
+    ElementData {
+        symbol: "Y",
+        name: "Yttrium",
+        atomic_weight: 88.90584,
+        monoisotopic_mass: 88.9058403,
+        most_abundant_isotope: 89,
+        valences: &[],
+        electronegativity: Some(1.22),
+        period: 5,
+        group: Some(3),
+    },
+    ElementData {
+        symbol: "Zr",
+        name: "Zirconium",
+        atomic_weight: 91.224,
+        monoisotopic_mass: 89.9046977,
+        most_abundant_isotope: 90,
+        valences: &[],
+        electronegativity: Some(1.33),
+        period: 5,
+        group: Some(4),
+    },
+    ElementData {
+        symbol: "Nb",
+        name: "Niobium",
+        atomic_weight: 92.90637,
+        monoisotopic_mass: 92.906373,
+        most_abundant_isotope: 93,
+        valences: &[],
+        electronegativity: Some(1.6),
+        period: 5,
+        group: Some(5),
+    },
+    ElementData {
+        symbol: "Mo",
+        name: "Molybdenum",
+        atomic_weight: 95.95,
+        monoisotopic_mass: 97.90540482,
+        most_abundant_isotope: 98,
+        valences: &[],
+        electronegativity: Some(2.16),
+        period: 5,
+        group: Some(6),
+    },
+    ElementData {
+        symbol: "Tc",
+        name: "Technetium",
+        atomic_weight: 98.0,
+        monoisotopic_mass: 97.9072124,
+        most_abundant_isotope: 98,
+        valences: &[],
+        electronegativity: Some(1.9),
+        period: 5,
+        group: Some(7),
+    },
+    ElementData {
+        symbol: "Ru",
+        name: "Ruthenium",
+        atomic_weight: 101.07,
+        monoisotopic_mass: 101.9043441,
+        most_abundant_isotope: 102,
+        valences: &[],
+        electronegativity: Some(2.2),
+        period: 5,
+        group: Some(8),
+    },
+    ElementData {
+        symbol: "Rh",
+        name: "Rhodium",
+        atomic_weight: 102.90550,
+        monoisotopic_mass: 102.905498,
+        most_abundant_isotope: 103,
+        valences: &[],
+        electronegativity: Some(2.28),
+        period: 5,
+        group: Some(9),
+    },
+    ElementData {
+        symbol: "Pd",
+        name: "Palladium",
+        atomic_weight: 106.42,
+        monoisotopic_mass: 105.9034804,
+        most_abundant_isotope: 106,
+        valences: &[],
+        electronegativity: Some(2.20),
+        period: 5,
+        group: Some(10),
+    },
+    ElementData {
+        symbol: "Ag",
+        name: "Silver",
+        atomic_weight: 107.8682,
+        monoisotopic_mass: 106.9050916,
+        most_abundant_isotope: 107,
+        valences: &[],
+        electronegativity: Some(1.93),
+        period: 5,
+        group: Some(11),
+    },
+    ElementData {
+        symbol: "Cd",
+        name: "Cadmium",
+        atomic_weight: 112.414,
+        monoisotopic_mass: 113.90336509,
+        most_abundant_isotope: 114,
+        valences: &[],
+        electronegativity: Some(1.69),
+        period: 5,
+        group: Some(12),
+    },
+    ElementData {
+        symbol: "In",
+        name: "Indium",
+        atomic_weight: 114.818,
+        monoisotopic_mass: 114.903878776,
+        most_abundant_isotope: 115,
+        valences: &[3],
+        electronegativity: Some(1.78),
+        period: 5,
+        group: Some(13),
+    },
+    ElementData {
+        symbol: "Sn",
+        name: "Tin",
+        atomic_weight: 118.710,
+        monoisotopic_mass: 119.90220163,
+        most_abundant_isotope: 120,
+        valences: &[2, 4],
+        electronegativity: Some(1.96),
+        period: 5,
+        group: Some(14),
+    },
+    ElementData {
+        symbol: "Sb",
+        name: "Antimony",
+        atomic_weight: 121.760,
+        monoisotopic_mass: 120.903812,
+        most_abundant_isotope: 121,
+        valences: &[3, 5],
+        electronegativity: Some(2.05),
+        period: 5,
+        group: Some(15),
+    },
+    ElementData {
+        symbol: "Te",
+        name: "Tellurium",
+        atomic_weight: 127.60,
+        monoisotopic_mass: 129.906222748,
+        most_abundant_isotope: 130,
+        valences: &[2, 4, 6],
+        electronegativity: Some(2.1),
+        period: 5,
+        group: Some(16),
+    },
+    ElementData {
+        symbol: "I",
+        name: "Iodine",
+        atomic_weight: 126.90447,
+        monoisotopic_mass: 126.9044719,
+        most_abundant_isotope: 127,
+        valences: &[1, 3, 5, 7],
+        electronegativity: Some(2.66),
+        period: 5,
+        group: Some(17),
+    },
+    ElementData {
+        symbol: "Xe",
+        name: "Xenon",
+        atomic_weight: 131.293,
+        monoisotopic_mass: 131.9041550856,
+        most_abundant_isotope: 132,
+        valences: &[0, 2, 4, 6, 8],
+        electronegativity: Some(2.6),
+        period: 5,
+        group: Some(18),
+    },
+    ElementData {
+        symbol: "Cs",
+        name: "Caesium",
+        atomic_weight: 132.90545196,
+        monoisotopic_mass: 132.905451961,
+        most_abundant_isotope: 133,
+        valences: &[1],
+        electronegativity: Some(0.79),
+        period: 6,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "Ba",
+        name: "Barium",
+        atomic_weight: 137.327,
+        monoisotopic_mass: 137.905247,
+        most_abundant_isotope: 138,
+        valences: &[2],
+        electronegativity: Some(0.89),
+        period: 6,
+        group: Some(2),
+    },
+    ElementData {
+        symbol: "La",
+        name: "Lanthanum",
+        atomic_weight: 138.90547,
+        monoisotopic_mass: 138.9063563,
+        most_abundant_isotope: 139,
+        valences: &[],
+        electronegativity: Some(1.10),
+        period: 6,
+        group: Some(3),
+    },
+    ElementData {
+        symbol: "Ce",
+        name: "Cerium",
+        atomic_weight: 140.116,
+        monoisotopic_mass: 139.9054431,
+        most_abundant_isotope: 140,
+        valences: &[],
+        electronegativity: Some(1.12),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Pr",
+        name: "Praseodymium",
+        atomic_weight: 140.90766,
+        monoisotopic_mass: 140.9076576,
+        most_abundant_isotope: 141,
+        valences: &[],
+        electronegativity: Some(1.13),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Nd",
+        name: "Neodymium",
+        atomic_weight: 144.242,
+        monoisotopic_mass: 141.907729,
+        most_abundant_isotope: 142,
+        valences: &[],
+        electronegativity: Some(1.14),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Pm",
+        name: "Promethium",
+        atomic_weight: 145.0,
+        monoisotopic_mass: 144.9127559,
+        most_abundant_isotope: 145,
+        valences: &[],
+        electronegativity: Some(1.13),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Sm",
+        name: "Samarium",
+        atomic_weight: 150.36,
+        monoisotopic_mass: 151.9197397,
+        most_abundant_isotope: 152,
+        valences: &[],
+        electronegativity: Some(1.17),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Eu",
+        name: "Europium",
+        atomic_weight: 151.964,
+        monoisotopic_mass: 152.921238,
+        most_abundant_isotope: 153,
+        valences: &[],
+        electronegativity: Some(1.2),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Gd",
+        name: "Gadolinium",
+        atomic_weight: 157.25,
+        monoisotopic_mass: 157.9241123,
+        most_abundant_isotope: 158,
+        valences: &[],
+        electronegativity: Some(1.2),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Tb",
+        name: "Terbium",
+        atomic_weight: 158.92535,
+        monoisotopic_mass: 158.9253547,
+        most_abundant_isotope: 159,
+        valences: &[],
+        electronegativity: Some(1.1),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Dy",
+        name: "Dysprosium",
+        atomic_weight: 162.500,
+        monoisotopic_mass: 163.9291819,
+        most_abundant_isotope: 164,
+        valences: &[],
+        electronegativity: Some(1.22),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Ho",
+        name: "Holmium",
+        atomic_weight: 164.93033,
+        monoisotopic_mass: 164.9303288,
+        most_abundant_isotope: 165,
+        valences: &[],
+        electronegativity: Some(1.23),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Er",
+        name: "Erbium",
+        atomic_weight: 167.259,
+        monoisotopic_mass: 165.9302995,
+        most_abundant_isotope: 166,
+        valences: &[],
+        electronegativity: Some(1.24),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Tm",
+        name: "Thulium",
+        atomic_weight: 168.93422,
+        monoisotopic_mass: 168.9342179,
+        most_abundant_isotope: 169,
+        valences: &[],
+        electronegativity: Some(1.25),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Yb",
+        name: "Ytterbium",
+        atomic_weight: 173.045,
+        monoisotopic_mass: 173.9388664,
+        most_abundant_isotope: 174,
+        valences: &[],
+        electronegativity: Some(1.1),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Lu",
+        name: "Lutetium",
+        atomic_weight: 174.9668,
+        monoisotopic_mass: 174.9407752,
+        most_abundant_isotope: 175,
+        valences: &[],
+        electronegativity: Some(1.27),
+        period: 6,
+        group: None,
+    },
+    ElementData {
+        symbol: "Hf",
+        name: "Hafnium",
+        atomic_weight: 178.49,
+        monoisotopic_mass: 179.946557,
+        most_abundant_isotope: 180,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 6,
+        group: Some(4),
+    },
+    ElementData {
+        symbol: "Ta",
+        name: "Tantalum",
+        atomic_weight: 180.94788,
+        monoisotopic_mass: 180.9479958,
+        most_abundant_isotope: 181,
+        valences: &[],
+        electronegativity: Some(1.5),
+        period: 6,
+        group: Some(5),
+    },
+    ElementData {
+        symbol: "W",
+        name: "Tungsten",
+        atomic_weight: 183.84,
+        monoisotopic_mass: 183.95093092,
+        most_abundant_isotope: 184,
+        valences: &[],
+        electronegativity: Some(2.36),
+        period: 6,
+        group: Some(6),
+    },
+    ElementData {
+        symbol: "Re",
+        name: "Rhenium",
+        atomic_weight: 186.207,
+        monoisotopic_mass: 186.9557501,
+        most_abundant_isotope: 187,
+        valences: &[],
+        electronegativity: Some(1.9),
+        period: 6,
+        group: Some(7),
+    },
+    ElementData {
+        symbol: "Os",
+        name: "Osmium",
+        atomic_weight: 190.23,
+        monoisotopic_mass: 191.961477,
+        most_abundant_isotope: 192,
+        valences: &[],
+        electronegativity: Some(2.2),
+        period: 6,
+        group: Some(8),
+    },
+    ElementData {
+        symbol: "Ir",
+        name: "Iridium",
+        atomic_weight: 192.217,
+        monoisotopic_mass: 192.9629216,
+        most_abundant_isotope: 193,
+        valences: &[],
+        electronegativity: Some(2.20),
+        period: 6,
+        group: Some(9),
+    },
+    ElementData {
+        symbol: "Pt",
+        name: "Platinum",
+        atomic_weight: 195.084,
+        monoisotopic_mass: 194.9647917,
+        most_abundant_isotope: 195,
+        valences: &[],
+        electronegativity: Some(2.28),
+        period: 6,
+        group: Some(10),
+    },
+    ElementData {
+        symbol: "Au",
+        name: "Gold",
+        atomic_weight: 196.966569,
+        monoisotopic_mass: 196.96656879,
+        most_abundant_isotope: 197,
+        valences: &[],
+        electronegativity: Some(2.54),
+        period: 6,
+        group: Some(11),
+    },
+    ElementData {
+        symbol: "Hg",
+        name: "Mercury",
+        atomic_weight: 200.592,
+        monoisotopic_mass: 201.9706434,
+        most_abundant_isotope: 202,
+        valences: &[],
+        electronegativity: Some(2.00),
+        period: 6,
+        group: Some(12),
+    },
+    ElementData {
+        symbol: "Tl",
+        name: "Thallium",
+        atomic_weight: 204.38,
+        monoisotopic_mass: 204.9744278,
+        most_abundant_isotope: 205,
+        valences: &[1, 3],
+        electronegativity: Some(1.62),
+        period: 6,
+        group: Some(13),
+    },
+    ElementData {
+        symbol: "Pb",
+        name: "Lead",
+        atomic_weight: 207.2,
+        monoisotopic_mass: 207.9766525,
+        most_abundant_isotope: 208,
+        valences: &[2, 4],
+        electronegativity: Some(2.33),
+        period: 6,
+        group: Some(14),
+    },
+    ElementData {
+        symbol: "Bi",
+        name: "Bismuth",
+        atomic_weight: 208.98040,
+        monoisotopic_mass: 208.9803991,
+        most_abundant_isotope: 209,
+        valences: &[3, 5],
+        electronegativity: Some(2.02),
+        period: 6,
+        group: Some(15),
+    },
+    ElementData {
+        symbol: "Po",
+        name: "Polonium",
+        atomic_weight: 209.0,
+        monoisotopic_mass: 208.9824308,
+        most_abundant_isotope: 209,
+        valences: &[2, 4, 6],
+        electronegativity: Some(2.0),
+        period: 6,
+        group: Some(16),
+    },
+    ElementData {
+        symbol: "At",
+        name: "Astatine",
+        atomic_weight: 210.0,
+        monoisotopic_mass: 209.9871479,
+        most_abundant_isotope: 210,
+        valences: &[1, 3, 5, 7],
+        electronegativity: Some(2.2),
+        period: 6,
+        group: Some(17),
+    },
+    ElementData {
+        symbol: "Rn",
+        name: "Radon",
+        atomic_weight: 222.0,
+        monoisotopic_mass: 222.0175782,
+        most_abundant_isotope: 222,
+        valences: &[0],
+        electronegativity: Some(2.2),
+        period: 6,
+        group: Some(18),
+    },
+    ElementData {
+        symbol: "Fr",
+        name: "Francium",
+        atomic_weight: 223.0,
+        monoisotopic_mass: 223.019736,
+        most_abundant_isotope: 223,
+        valences: &[1],
+        electronegativity: Some(0.7),
+        period: 7,
+        group: Some(1),
+    },
+    ElementData {
+        symbol: "Ra",
+        name: "Radium",
+        atomic_weight: 226.0,
+        monoisotopic_mass: 226.0254103,
+        most_abundant_isotope: 226,
+        valences: &[2],
+        electronegativity: Some(0.9),
+        period: 7,
+        group: Some(2),
+    },
+    ElementData {
+        symbol: "Ac",
+        name: "Actinium",
+        atomic_weight: 227.0,
+        monoisotopic_mass: 227.0277523,
+        most_abundant_isotope: 227,
+        valences: &[],
+        electronegativity: Some(1.1),
+        period: 7,
+        group: Some(3),
+    },
+    ElementData {
+        symbol: "Th",
+        name: "Thorium",
+        atomic_weight: 232.0377,
+        monoisotopic_mass: 232.0380558,
+        most_abundant_isotope: 232,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Pa",
+        name: "Protactinium",
+        atomic_weight: 231.03588,
+        monoisotopic_mass: 231.0358842,
+        most_abundant_isotope: 231,
+        valences: &[],
+        electronegativity: Some(1.5),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "U",
+        name: "Uranium",
+        atomic_weight: 238.02891,
+        monoisotopic_mass: 238.0507884,
+        most_abundant_isotope: 238,
+        valences: &[],
+        electronegativity: Some(1.38),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Np",
+        name: "Neptunium",
+        atomic_weight: 237.0,
+        monoisotopic_mass: 237.0481736,
+        most_abundant_isotope: 237,
+        valences: &[],
+        electronegativity: Some(1.36),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Pu",
+        name: "Plutonium",
+        atomic_weight: 244.0,
+        monoisotopic_mass: 244.0642053,
+        most_abundant_isotope: 244,
+        valences: &[],
+        electronegativity: Some(1.28),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Am",
+        name: "Americium",
+        atomic_weight: 243.0,
+        monoisotopic_mass: 243.0613813,
+        most_abundant_isotope: 243,
+        valences: &[],
+        electronegativity: Some(1.13),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Cm",
+        name: "Curium",
+        atomic_weight: 247.0,
+        monoisotopic_mass: 247.0703541,
+        most_abundant_isotope: 247,
+        valences: &[],
+        electronegativity: Some(1.28),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Bk",
+        name: "Berkelium",
+        atomic_weight: 247.0,
+        monoisotopic_mass: 247.0703073,
+        most_abundant_isotope: 247,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Cf",
+        name: "Californium",
+        atomic_weight: 251.0,
+        monoisotopic_mass: 251.0795886,
+        most_abundant_isotope: 251,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Es",
+        name: "Einsteinium",
+        atomic_weight: 252.0,
+        monoisotopic_mass: 252.08298,
+        most_abundant_isotope: 252,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Fm",
+        name: "Fermium",
+        atomic_weight: 257.0,
+        monoisotopic_mass: 257.0951061,
+        most_abundant_isotope: 257,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Md",
+        name: "Mendelevium",
+        atomic_weight: 258.0,
+        monoisotopic_mass: 258.0984315,
+        most_abundant_isotope: 258,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "No",
+        name: "Nobelium",
+        atomic_weight: 259.0,
+        monoisotopic_mass: 259.10103,
+        most_abundant_isotope: 259,
+        valences: &[],
+        electronegativity: Some(1.3),
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Lr",
+        name: "Lawrencium",
+        atomic_weight: 266.0,
+        monoisotopic_mass: 266.11983,
+        most_abundant_isotope: 266,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: None,
+    },
+    ElementData {
+        symbol: "Rf",
+        name: "Rutherfordium",
+        atomic_weight: 267.0,
+        monoisotopic_mass: 267.12179,
+        most_abundant_isotope: 267,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(4),
+    },
+    ElementData {
+        symbol: "Db",
+        name: "Dubnium",
+        atomic_weight: 268.0,
+        monoisotopic_mass: 268.12567,
+        most_abundant_isotope: 268,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(5),
+    },
+    ElementData {
+        symbol: "Sg",
+        name: "Seaborgium",
+        atomic_weight: 269.0,
+        monoisotopic_mass: 269.12863,
+        most_abundant_isotope: 269,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(6),
+    },
+    ElementData {
+        symbol: "Bh",
+        name: "Bohrium",
+        atomic_weight: 270.0,
+        monoisotopic_mass: 270.13336,
+        most_abundant_isotope: 270,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(7),
+    },
+    ElementData {
+        symbol: "Hs",
+        name: "Hassium",
+        atomic_weight: 269.0,
+        monoisotopic_mass: 269.13375,
+        most_abundant_isotope: 269,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(8),
+    },
+    ElementData {
+        symbol: "Mt",
+        name: "Meitnerium",
+        atomic_weight: 278.0,
+        monoisotopic_mass: 278.15631,
+        most_abundant_isotope: 278,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(9),
+    },
+    ElementData {
+        symbol: "Ds",
+        name: "Darmstadtium",
+        atomic_weight: 281.0,
+        monoisotopic_mass: 281.16451,
+        most_abundant_isotope: 281,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(10),
+    },
+    ElementData {
+        symbol: "Rg",
+        name: "Roentgenium",
+        atomic_weight: 282.0,
+        monoisotopic_mass: 282.16912,
+        most_abundant_isotope: 282,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(11),
+    },
+    ElementData {
+        symbol: "Cn",
+        name: "Copernicium",
+        atomic_weight: 285.0,
+        monoisotopic_mass: 285.17712,
+        most_abundant_isotope: 285,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(12),
+    },
+    ElementData {
+        symbol: "Nh",
+        name: "Nihonium",
+        atomic_weight: 286.0,
+        monoisotopic_mass: 286.18221,
+        most_abundant_isotope: 286,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(13),
+    },
+    ElementData {
+        symbol: "Fl",
+        name: "Flerovium",
+        atomic_weight: 289.0,
+        monoisotopic_mass: 289.19042,
+        most_abundant_isotope: 289,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(14),
+    },
+    ElementData {
+        symbol: "Mc",
+        name: "Moscovium",
+        atomic_weight: 290.0,
+        monoisotopic_mass: 290.19598,
+        most_abundant_isotope: 290,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(15),
+    },
+    ElementData {
+        symbol: "Lv",
+        name: "Livermorium",
+        atomic_weight: 293.0,
+        monoisotopic_mass: 293.20449,
+        most_abundant_isotope: 293,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(16),
+    },
+    ElementData {
+        symbol: "Ts",
+        name: "Tennessine",
+        atomic_weight: 294.0,
+        monoisotopic_mass: 294.21046,
+        most_abundant_isotope: 294,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(17),
+    },
+    ElementData {
+        symbol: "Og",
+        name: "Oganesson",
+        atomic_weight: 294.0,
+        monoisotopic_mass: 294.21392,
+        most_abundant_isotope: 294,
+        valences: &[],
+        electronegativity: None,
+        period: 7,
+        group: Some(18),
+    },
+];
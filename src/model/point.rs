@@ -0,0 +1,98 @@
+use bincode::{Decode, Encode};
+use std::ops::{Add, Mul, Sub};
+
+/// Point or vector in the 2D depiction plane.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Encode, Decode)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    /// Unit vector at the given angle (radians) from the x axis.
+    pub fn from_angle(angle: f64) -> Point {
+        Point::new(angle.cos(), angle.sin())
+    }
+
+    pub fn length(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn distance(&self, other: Point) -> f64 {
+        (*self - other).length()
+    }
+
+    /// Angle (radians) of the vector from the x axis, in (-pi, pi].
+    pub fn angle(&self) -> f64 {
+        self.y.atan2(self.x)
+    }
+
+    /// Vector of unit length in the same direction, or zero for a zero vector.
+    pub fn normalized(&self) -> Point {
+        let length = self.length();
+        if length == 0.0 {
+            Point::default()
+        } else {
+            *self * (1.0 / length)
+        }
+    }
+
+    /// Mirror image of the point across the line through `a` and `b`.
+    pub fn reflect(&self, a: Point, b: Point) -> Point {
+        let direction = (b - a).normalized();
+        let v = *self - a;
+        let projection = direction * (v.x * direction.x + v.y * direction.y);
+        a + projection * 2.0 - v
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let a = Point::new(3.0, 4.0);
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(a + Point::new(1.0, 1.0), Point::new(4.0, 5.0));
+        assert_eq!(a - Point::new(1.0, 1.0), Point::new(2.0, 3.0));
+        assert_eq!(a * 2.0, Point::new(6.0, 8.0));
+        assert_eq!(a.distance(Point::default()), 5.0);
+        assert!((a.normalized().length() - 1.0).abs() < 1e-12);
+        assert_eq!(Point::default().normalized(), Point::default());
+    }
+
+    #[test]
+    fn test_reflect() {
+        let p = Point::new(1.0, 1.0).reflect(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+        assert!(p.distance(Point::new(1.0, -1.0)) < 1e-12);
+    }
+}
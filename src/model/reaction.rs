@@ -0,0 +1,10 @@
+use crate::model::molecule::Molecule;
+
+/// A reaction or reaction template, as written in reaction SMILES
+/// `reactants>agents>products`. Every connected component is a molecule of its own.
+#[derive(Clone, Default)]
+pub struct Reaction {
+    pub reactants: Vec<Molecule>,
+    pub agents: Vec<Molecule>,
+    pub products: Vec<Molecule>,
+}
@@ -0,0 +1,15 @@
+/// Ring system counts used by library design filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RingDescriptors {
+    /// Number of SSSR rings.
+    pub ring_count: usize,
+    pub ring_system_count: usize,
+    /// Atoms in the largest ring system.
+    pub largest_ring_system_size: usize,
+    /// Rings in the largest ring system.
+    pub largest_ring_system_rings: usize,
+    /// Rings sharing a bond with another ring.
+    pub fused_ring_count: usize,
+    pub spiro_atom_count: usize,
+    pub bridgehead_atom_count: usize,
+}
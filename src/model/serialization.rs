@@ -0,0 +1,355 @@
+//! Serde support with a stable schema independent of the graph representation:
+//!
+//! ```json
+//! {
+//!   "atoms": [
+//!     {"element": "C", "isotope": 0, "charge": 0, "hydrogens": 0, "no_implicit_hydrogens": false,
+//!      "coords": [0.0, 1.5], "chirality": "clockwise", "map": 1}
+//!   ],
+//!   "bonds": [{"atoms": [0, 1], "order": "single", "stereo": "wedge"},
+//!             {"atoms": [1, 2], "order": "double", "double_bond_stereo": "trans"}],
+//!   "properties": {"name": "Ethanol"}
+//! }
+//! ```
+//!
+//! Atoms are numbered in graph order, skipping removed atoms. `coords` is omitted for atoms
+//! without 2D coordinates, `aromatic` for atoms not written with a lowercase symbol,
+//! `chirality` for atoms without tetrahedral configuration, `map` for atoms without atom map
+//! number, `stereo` for bonds without wedge/hash depiction and
+//! `double_bond_stereo` for bonds without cis/trans configuration.
+
+use crate::model::atom::{Atom, Chirality};
+use crate::model::bond::{Bond, BondOrder, BondStereo, DoubleBondStereo};
+use crate::model::element::Element;
+use crate::model::molecule::Molecule;
+use crate::model::point::Point;
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Serialize, Deserialize)]
+struct AtomRecord {
+    element: String,
+    #[serde(default)]
+    isotope: u16,
+    #[serde(default)]
+    charge: i8,
+    #[serde(default)]
+    hydrogens: u8,
+    #[serde(default)]
+    no_implicit_hydrogens: bool,
+    #[serde(default, skip_serializing_if = "is_not_aromatic")]
+    aromatic: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    coords: Option<[f64; 2]>,
+    #[serde(default, skip_serializing_if = "is_no_chirality")]
+    chirality: Chirality,
+    #[serde(default, skip_serializing_if = "is_no_map")]
+    map: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BondRecord {
+    order: BondOrder,
+    #[serde(default, skip_serializing_if = "is_no_stereo")]
+    stereo: BondStereo,
+    #[serde(default, skip_serializing_if = "is_no_double_bond_stereo")]
+    double_bond_stereo: DoubleBondStereo,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MoleculeBondRecord {
+    atoms: [usize; 2],
+    order: BondOrder,
+    #[serde(default, skip_serializing_if = "is_no_stereo")]
+    stereo: BondStereo,
+    #[serde(default, skip_serializing_if = "is_no_double_bond_stereo")]
+    double_bond_stereo: DoubleBondStereo,
+}
+
+fn is_no_chirality(chirality: &Chirality) -> bool {
+    *chirality == Chirality::None
+}
+
+fn is_not_aromatic(aromatic: &bool) -> bool {
+    !*aromatic
+}
+
+fn is_no_map(map: &u16) -> bool {
+    *map == 0
+}
+
+fn is_no_stereo(stereo: &BondStereo) -> bool {
+    *stereo == BondStereo::None
+}
+
+fn is_no_double_bond_stereo(stereo: &DoubleBondStereo) -> bool {
+    *stereo == DoubleBondStereo::None
+}
+
+#[derive(Serialize, Deserialize)]
+struct MoleculeRecord {
+    atoms: Vec<AtomRecord>,
+    #[serde(default)]
+    bonds: Vec<MoleculeBondRecord>,
+    #[serde(default)]
+    properties: BTreeMap<String, String>,
+}
+
+impl AtomRecord {
+    fn new(atom: &Atom) -> AtomRecord {
+        AtomRecord {
+            element: atom.element.symbol().unwrap_or("*").to_owned(),
+            isotope: atom.isotope,
+            charge: atom.charge,
+            hydrogens: atom.hs,
+            no_implicit_hydrogens: atom.no_implicit_hs,
+            aromatic: atom.aromatic,
+            coords: atom.coords.map(|p| [p.x, p.y]),
+            chirality: atom.chirality,
+            map: atom.map,
+        }
+    }
+
+    fn into_atom<E: Error>(self) -> Result<Atom, E> {
+        let element = match self.element.as_str() {
+            "*" => Element { atomic_number: 0 },
+            symbol => Element::from_symbol(symbol)
+                .ok_or_else(|| E::custom(format!("unknown element {}", self.element)))?,
+        };
+        Ok(Atom {
+            element,
+            isotope: self.isotope,
+            charge: self.charge,
+            hs: self.hydrogens,
+            no_implicit_hs: self.no_implicit_hydrogens,
+            aromatic: self.aromatic,
+            coords: self.coords.map(|[x, y]| Point::new(x, y)),
+            chirality: self.chirality,
+            map: self.map,
+        })
+    }
+}
+
+impl Serialize for Atom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AtomRecord::new(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Atom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        AtomRecord::deserialize(deserializer)?.into_atom()
+    }
+}
+
+impl Serialize for Bond {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BondRecord {
+            order: self.order,
+            stereo: self.stereo,
+            double_bond_stereo: self.double_bond_stereo,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bond {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = BondRecord::deserialize(deserializer)?;
+        Ok(Bond {
+            order: record.order,
+            stereo: record.stereo,
+            double_bond_stereo: record.double_bond_stereo,
+        })
+    }
+}
+
+impl Serialize for Molecule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut positions: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut atoms = Vec::with_capacity(self.count_atoms());
+        for node in self.graph.node_indices() {
+            positions.insert(node, atoms.len());
+            atoms.push(AtomRecord::new(&self.graph[node]));
+        }
+        let bonds = (&self.graph)
+            .edge_references()
+            .map(|edge| MoleculeBondRecord {
+                atoms: [positions[&edge.source()], positions[&edge.target()]],
+                order: edge.weight().order,
+                stereo: edge.weight().stereo,
+                double_bond_stereo: edge.weight().double_bond_stereo,
+            })
+            .collect();
+        MoleculeRecord {
+            atoms,
+            bonds,
+            properties: self.properties.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Molecule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = MoleculeRecord::deserialize(deserializer)?;
+        let mut molecule = Molecule::new();
+        let mut nodes = Vec::with_capacity(record.atoms.len());
+        for atom in record.atoms {
+            nodes.push(molecule.add_atom(atom.into_atom()?));
+        }
+        for bond in record.bonds {
+            let [a, b] = bond.atoms;
+            if a >= nodes.len() || b >= nodes.len() || a == b {
+                return Err(D::Error::custom(format!(
+                    "invalid bond between atoms {} and {}",
+                    a, b
+                )));
+            }
+            molecule.add_bond(
+                nodes[a],
+                nodes[b],
+                Bond {
+                    order: bond.order,
+                    stereo: bond.stereo,
+                    double_bond_stereo: bond.double_bond_stereo,
+                },
+            );
+        }
+        molecule.properties = record.properties;
+        Ok(molecule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::smiles::reader::molecule::parse_molecule;
+    use crate::model::atom::Atom;
+    use crate::model::bond::{BondOrder, BondStereo};
+    use crate::model::molecule::Molecule;
+    use crate::model::point::Point;
+    use petgraph::stable_graph::NodeIndex;
+    use serde_json::json;
+
+    #[test]
+    fn molecule_to_json() {
+        let mut m = parse_molecule("C=[OH+]").unwrap().1;
+        m.properties.insert("name".to_owned(), "test".to_owned());
+        assert_eq!(
+            serde_json::to_value(&m).unwrap(),
+            json!({
+                "atoms": [
+                    {"element": "C", "isotope": 0, "charge": 0, "hydrogens": 0, "no_implicit_hydrogens": false},
+                    {"element": "O", "isotope": 0, "charge": 1, "hydrogens": 1, "no_implicit_hydrogens": true}
+                ],
+                "bonds": [{"atoms": [0, 1], "order": "double"}],
+                "properties": {"name": "test"}
+            })
+        );
+    }
+
+    #[test]
+    fn molecule_json_roundtrip() {
+        let m = parse_molecule("CC(C)CC1=CC=C(C=C1)C(C)C(=O)[13CH2][O-]")
+            .unwrap()
+            .1;
+        let json = serde_json::to_string(&m).unwrap();
+        let decoded: Molecule = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.count_atoms(), m.count_atoms());
+        assert_eq!(decoded.count_bonds(), m.count_bonds());
+        assert_eq!(decoded.formula(), m.formula());
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    }
+
+    #[test]
+    fn atom_coords_json() {
+        let mut m = parse_molecule("CO").unwrap().1;
+        m.graph[NodeIndex::new(1)].coords = Some(Point::new(1.5, -0.5));
+        let value = serde_json::to_value(&m).unwrap();
+        assert!(value["atoms"][0].get("coords").is_none());
+        assert_eq!(value["atoms"][1]["coords"], json!([1.5, -0.5]));
+        let decoded: Molecule = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            decoded.graph[NodeIndex::new(1)].coords,
+            Some(Point::new(1.5, -0.5))
+        );
+    }
+
+    #[test]
+    fn bond_stereo_json() {
+        let mut m = parse_molecule("CO").unwrap().1;
+        let value = serde_json::to_value(&m).unwrap();
+        assert!(value["bonds"][0].get("stereo").is_none());
+        m.graph.edge_weights_mut().next().unwrap().stereo = BondStereo::Hash;
+        let value = serde_json::to_value(&m).unwrap();
+        assert_eq!(value["bonds"][0]["stereo"], json!("hash"));
+        let decoded: Molecule = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            decoded.graph.edge_weights().next().unwrap().stereo,
+            BondStereo::Hash
+        );
+    }
+
+    #[test]
+    fn stereo_json() {
+        let m = parse_molecule("C[C@H](O)/C=C/C").unwrap().1;
+        let value = serde_json::to_value(&m).unwrap();
+        assert!(value["atoms"][0].get("chirality").is_none());
+        assert_eq!(value["atoms"][1]["chirality"], json!("clockwise"));
+        assert!(value["bonds"][0].get("double_bond_stereo").is_none());
+        assert_eq!(value["bonds"][3]["double_bond_stereo"], json!("trans"));
+        let decoded: Molecule = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.canonical_smiles(), m.canonical_smiles());
+    }
+
+    #[test]
+    fn atom_map_json() {
+        let m = parse_molecule("[*:1]C[CH3:2]").unwrap().1;
+        let value = serde_json::to_value(&m).unwrap();
+        assert_eq!(value["atoms"][0]["element"], json!("*"));
+        assert_eq!(value["atoms"][0]["map"], json!(1));
+        assert!(value["atoms"][1].get("map").is_none());
+        let decoded: Molecule = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.to_smiles(), "[*:1]C[CH3:2]");
+    }
+
+    #[test]
+    fn molecule_json_compacts_removed_atoms() {
+        let mut m = parse_molecule("OCN").unwrap().1;
+        m.remove_atom(NodeIndex::new(0));
+        let value = serde_json::to_value(&m).unwrap();
+        assert_eq!(value["atoms"].as_array().unwrap().len(), 2);
+        assert_eq!(value["bonds"][0]["atoms"], json!([0, 1]));
+    }
+
+    #[test]
+    fn molecule_from_json_defaults() {
+        let m: Molecule = serde_json::from_value(json!({
+            "atoms": [{"element": "N"}, {"element": "C"}],
+            "bonds": [{"atoms": [0, 1], "order": "triple"}]
+        }))
+        .unwrap();
+        assert_eq!(m.formula().to_string(), "CHN");
+        assert_eq!(m.graph[NodeIndex::new(0)].coords, None);
+        assert_eq!(
+            m.get_bond_by_atoms(NodeIndex::new(0), NodeIndex::new(1))
+                .unwrap()
+                .order,
+            BondOrder::Triple
+        );
+    }
+
+    #[test]
+    fn molecule_from_json_errors() {
+        assert!(serde_json::from_value::<Molecule>(json!({"atoms": [{"element": "Xx"}]})).is_err());
+        assert!(serde_json::from_value::<Molecule>(json!({
+            "atoms": [{"element": "C"}],
+            "bonds": [{"atoms": [0, 1], "order": "single"}]
+        }))
+        .is_err());
+        assert!(serde_json::from_value::<Atom>(json!({"element": "C", "charge": 300})).is_err());
+    }
+}